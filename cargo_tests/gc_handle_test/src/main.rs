@@ -0,0 +1,41 @@
+#![allow(internal_features,incomplete_features,unused_variables,dead_code,improper_ctypes_definitions)]
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![no_std]
+use mycorrhiza::{start,panic_handler};
+use mycorrhiza::system::MObject;
+use mycorrhiza::system::runtime::interop_services::{GcHandle, Marshal};
+panic_handler!{}
+start!{}
+#[lang = "eh_personality"]
+fn rust_eh_personality() {}
+type GC = mycorrhiza::intrinsics::RustcCLRInteropManagedClass<"System.Runtime", "System.GC">;
+fn reference_equals(a: MObject, b: MObject) -> bool {
+    MObject::static2::<"ReferenceEquals", MObject, MObject, bool>(a, b)
+}
+fn check(cond: bool) {
+    if !cond {
+        core::intrinsics::abort();
+    }
+}
+// A `GcHandle` holds no managed references, so it can live in unmanaged memory.
+struct Holder {
+    handle: GcHandle<MObject>,
+    tag: u32,
+}
+fn main() {
+    let obj = MObject::ctor0();
+    let holder = Marshal::alloc_hglobal(core::mem::size_of::<Holder>() as isize) as *mut Holder;
+    unsafe { holder.write(Holder { handle: GcHandle::new(obj), tag: 7 }) };
+    // The handle must keep the object alive, and track it if it gets moved.
+    GC::static0::<"Collect", ()>();
+    check(unsafe { (*holder).tag } == 7);
+    check(reference_equals(unsafe { (*holder).handle.target() }, obj));
+    let other = MObject::ctor0();
+    let other_handle = GcHandle::new(other);
+    check(!reference_equals(other_handle.target(), obj));
+    check(reference_equals(other_handle.target(), other));
+    // Dropping the handles frees them. Freeing a handle twice throws, so `Drop` must run at most once per handle.
+    drop(other_handle);
+    unsafe { core::ptr::drop_in_place(holder) };
+    Marshal::free_hglobal(holder as isize);
+}
@@ -0,0 +1,32 @@
+#![allow(internal_features,incomplete_features,unused_variables,dead_code,improper_ctypes_definitions)]
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![no_std]
+use mycorrhiza::{start,panic_handler};
+use mycorrhiza::ManagedArray;
+panic_handler!{}
+start!{}
+#[lang = "eh_personality"]
+fn rust_eh_personality() {}
+fn check(cond: bool) {
+    if !cond {
+        core::intrinsics::abort();
+    }
+}
+fn main() {
+    let src: [u32; 5] = [1, 2, 3, 0xDEAD_BEEF, 5];
+    let arr = ManagedArray::from_slice(&src);
+    check(arr.len() == 5);
+    check(arr.get(3) == 0xDEAD_BEEF);
+    check(arr.with_slice(|slice| slice == &src[..]));
+    // The pinned slice must alias the managed array, not a copy of it.
+    arr.with_slice_mut(|slice| slice[0] = 10);
+    check(arr.get(0) == 10);
+    arr.set(4, 50);
+    let mut dst = [0_u32; 5];
+    arr.copy_to_slice(&mut dst);
+    check(dst == [10, 2, 3, 0xDEAD_BEEF, 50]);
+    // Empty slices must round-trip too.
+    let empty = ManagedArray::<u64>::from_slice(&[]);
+    check(empty.is_empty());
+    empty.copy_to_slice(&mut []);
+}
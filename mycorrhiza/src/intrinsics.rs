@@ -67,21 +67,192 @@ impl<const ASSEMBLY: &'static str, const CLASS_PATH: &'static str>
         >(self, arg1)
     }
     #[inline(always)]
+    pub fn instance2<const METHOD: &'static str, Arg1, Arg2, Ret>(self, arg1: Arg1, arg2: Arg2) -> Ret {
+        rustc_clr_interop_managed_call3_::<
+            ASSEMBLY,
+            CLASS_PATH,
+            false,
+            METHOD,
+            false,
+            Ret,
+            Self,
+            Arg1,
+            Arg2,
+        >(self, arg1, arg2)
+    }
+    #[inline(always)]
+    pub fn instance4<const METHOD: &'static str, Arg1, Arg2, Arg3, Arg4, Ret>(
+        self,
+        arg1: Arg1,
+        arg2: Arg2,
+        arg3: Arg3,
+        arg4: Arg4,
+    ) -> Ret {
+        rustc_clr_interop_managed_call5_::<
+            ASSEMBLY,
+            CLASS_PATH,
+            false,
+            METHOD,
+            false,
+            Ret,
+            Self,
+            Arg1,
+            Arg2,
+            Arg3,
+            Arg4,
+        >(self, arg1, arg2, arg3, arg4)
+    }
+    #[inline(always)]
     pub fn to_mstring(self) -> crate::system::MString {
         self.instance0::<"ToString", crate::system::MString>()
     }
+    /// Upcasts this reference to `System.Object`.
+    #[inline(always)]
+    pub fn as_object(self) -> crate::system::MObject {
+        self.cast()
+    }
+    /// Casts this reference to a different managed class. Will throw an `InvalidCastException` if the object is not an instance of the target class.
+    #[inline(always)]
+    pub fn cast<const TARGET_ASSEMBLY: &'static str, const TARGET_CLASS_PATH: &'static str>(
+        self,
+    ) -> RustcCLRInteropManagedClass<TARGET_ASSEMBLY, TARGET_CLASS_PATH> {
+        unsafe { core::intrinsics::transmute_unchecked(self) }
+    }
+}
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct RustcCLRInteropManagedStruct<const ASSEMBLY: &'static str, const CLASS_PATH: &'static str>
+{
+    size_hint: usize,
+}
+impl<const ASSEMBLY: &'static str, const CLASS_PATH: &'static str>
+    RustcCLRInteropManagedStruct<ASSEMBLY, CLASS_PATH>
+{
+    #[inline(always)]
+    pub fn static1<const METHOD: &'static str, Arg1, Ret>(arg1: Arg1) -> Ret {
+        rustc_clr_interop_managed_call1_::<ASSEMBLY, CLASS_PATH, true, METHOD, true, Ret, Arg1>(
+            arg1,
+        )
+    }
+    #[inline(always)]
+    pub fn static2<const METHOD: &'static str, Arg1, Arg2, Ret>(arg1: Arg1, arg2: Arg2) -> Ret {
+        rustc_clr_interop_managed_call2_::<ASSEMBLY, CLASS_PATH, true, METHOD, true, Ret, Arg1, Arg2>(
+            arg1, arg2,
+        )
+    }
+    #[inline(always)]
+    pub fn instance0<const METHOD: &'static str, Ret>(&mut self) -> Ret {
+        rustc_clr_interop_managed_call1_::<ASSEMBLY, CLASS_PATH, true, METHOD, false, Ret, *mut Self>(
+            self,
+        )
+    }
+}
+/// A reference to an instance of a generic managed class. `Generics` is a tuple of the generic arguments of this class.
+#[repr(C)]
+pub struct RustcCLRInteropManagedGenericClass<
+    const ASSEMBLY: &'static str,
+    const CLASS_PATH: &'static str,
+    Generics,
+> {
+    size_hint: usize,
+    pd: core::marker::PhantomData<Generics>,
+}
+impl<const ASSEMBLY: &'static str, const CLASS_PATH: &'static str, Generics> Clone
+    for RustcCLRInteropManagedGenericClass<ASSEMBLY, CLASS_PATH, Generics>
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<const ASSEMBLY: &'static str, const CLASS_PATH: &'static str, Generics> Copy
+    for RustcCLRInteropManagedGenericClass<ASSEMBLY, CLASS_PATH, Generics>
+{
+}
+impl<const ASSEMBLY: &'static str, const CLASS_PATH: &'static str, Generics>
+    RustcCLRInteropManagedGenericClass<ASSEMBLY, CLASS_PATH, Generics>
+{
+    /// Upcasts this reference to `System.Object`.
+    #[inline(always)]
+    pub fn as_object(self) -> crate::system::MObject {
+        unsafe { core::intrinsics::transmute_unchecked(self) }
+    }
 }
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub struct RustcCLRInteropManagedChar {
     utf16_char: u16,
 }
-#[derive(Clone, Copy)]
+/// A reference to a managed array of `T`, with `DIMENSIONS` dimensions.
 #[repr(C)]
-struct RustcCLRInteropManagedArray<T, const DIMENSIONS: usize> {
+pub struct RustcCLRInteropManagedArray<T, const DIMENSIONS: usize> {
     object_ref: usize,
     pd: core::marker::PhantomData<T>,
 }
+impl<T, const DIMENSIONS: usize> Clone for RustcCLRInteropManagedArray<T, DIMENSIONS> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T, const DIMENSIONS: usize> Copy for RustcCLRInteropManagedArray<T, DIMENSIONS> {}
+impl<T, const DIMENSIONS: usize> RustcCLRInteropManagedArray<T, DIMENSIONS> {
+    /// Upcasts this reference to `System.Object`.
+    #[inline(always)]
+    pub fn as_object(self) -> crate::system::MObject {
+        unsafe { core::intrinsics::transmute_unchecked(self) }
+    }
+}
+impl<T> RustcCLRInteropManagedArray<T, 1> {
+    /// Allocates a new managed array with `len` elements, all set to their default(zeroed) value.
+    #[inline(always)]
+    pub fn new(len: usize) -> Self {
+        rustc_clr_interop_managed_array_new_::<T>(len)
+    }
+    #[inline(always)]
+    pub fn len(self) -> usize {
+        rustc_clr_interop_managed_array_len_::<T>(self)
+    }
+    #[inline(always)]
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+    /// Gets the element at `idx`. Will throw an `IndexOutOfRangeException` if `idx` is out of bounds.
+    #[inline(always)]
+    pub fn get(self, idx: usize) -> T {
+        rustc_clr_interop_managed_array_get_::<T>(self, idx)
+    }
+    /// Sets the element at `idx`. Will throw an `IndexOutOfRangeException` if `idx` is out of bounds.
+    #[inline(always)]
+    pub fn set(self, idx: usize, value: T) {
+        rustc_clr_interop_managed_array_set_::<T>(self, idx, value)
+    }
+}
+impl<T: Copy> RustcCLRInteropManagedArray<T, 1> {
+    /// Allocates a new managed array, and copies the contents of `slice` into it.
+    pub fn from_slice(slice: &[T]) -> Self {
+        let arr = Self::new(slice.len());
+        arr.with_slice_mut(|dst| dst.copy_from_slice(slice));
+        arr
+    }
+    /// Copies the contents of this array into `dst`. Panics if the lengths don't match.
+    pub fn copy_to_slice(self, dst: &mut [T]) {
+        self.with_slice(|src| dst.copy_from_slice(src));
+    }
+    /// Pins this array, and calls `f` with a slice pointing to its elements. The array is unpinned once `f` returns.
+    /// Only arrays of blittable types(primitives, and structs made out of them) can be pinned, so this will throw for arrays of managed references.
+    pub fn with_slice<R>(self, f: impl FnOnce(&[T]) -> R) -> R {
+        self.with_slice_mut(|slice| f(slice))
+    }
+    /// Pins this array, and calls `f` with a mutable slice pointing to its elements. The array is unpinned once `f` returns.
+    /// Only arrays of blittable types(primitives, and structs made out of them) can be pinned, so this will throw for arrays of managed references.
+    pub fn with_slice_mut<R>(self, f: impl FnOnce(&mut [T]) -> R) -> R {
+        use crate::system::runtime::interop_services::GCHandle;
+        let mut handle = GCHandle::alloc_pinned(self.as_object());
+        let data = handle.addr_of_pinned_object() as *mut T;
+        let res = f(unsafe { core::slice::from_raw_parts_mut(data, self.len()) });
+        handle.free();
+        res
+    }
+}
 //Calls
 #[allow(unused_variables)]
 #[inline(never)]
@@ -126,6 +297,69 @@ pub fn rustc_clr_interop_managed_call2_<
 ) -> Ret {
     core::intrinsics::abort();
 }
+#[allow(unused_variables)]
+#[inline(never)]
+pub fn rustc_clr_interop_managed_call3_<
+    const ASSEMBLY: &'static str,
+    const CLASS_PATH: &'static str,
+    const IS_VALUETYPE: bool,
+    const METHOD: &'static str,
+    const IS_STATIC: bool,
+    Ret,
+    Arg1,
+    Arg2,
+    Arg3,
+>(
+    arg1: Arg1,
+    arg2: Arg2,
+    arg3: Arg3,
+) -> Ret {
+    core::intrinsics::abort();
+}
+#[allow(unused_variables)]
+#[inline(never)]
+pub fn rustc_clr_interop_managed_call4_<
+    const ASSEMBLY: &'static str,
+    const CLASS_PATH: &'static str,
+    const IS_VALUETYPE: bool,
+    const METHOD: &'static str,
+    const IS_STATIC: bool,
+    Ret,
+    Arg1,
+    Arg2,
+    Arg3,
+    Arg4,
+>(
+    arg1: Arg1,
+    arg2: Arg2,
+    arg3: Arg3,
+    arg4: Arg4,
+) -> Ret {
+    core::intrinsics::abort();
+}
+#[allow(unused_variables)]
+#[inline(never)]
+pub fn rustc_clr_interop_managed_call5_<
+    const ASSEMBLY: &'static str,
+    const CLASS_PATH: &'static str,
+    const IS_VALUETYPE: bool,
+    const METHOD: &'static str,
+    const IS_STATIC: bool,
+    Ret,
+    Arg1,
+    Arg2,
+    Arg3,
+    Arg4,
+    Arg5,
+>(
+    arg1: Arg1,
+    arg2: Arg2,
+    arg3: Arg3,
+    arg4: Arg4,
+    arg5: Arg5,
+) -> Ret {
+    core::intrinsics::abort();
+}
 //VCalls
 #[allow(unused_variables)]
 #[inline(never)]
@@ -222,6 +456,40 @@ pub fn rustc_clr_interop_managed_ctor3_<
 ) -> RustcCLRInteropManagedClass<ASSEMBLY, CLASS_PATH> {
     core::intrinsics::abort();
 }
+//Delegates
+#[allow(unused_variables)]
+#[inline(never)]
+pub fn rustc_clr_interop_managed_delegate_ctor_<Delegate, Target, F>(target: Target, f: F) -> Delegate {
+    core::intrinsics::abort();
+}
+//Arrays
+#[allow(unused_variables)]
+#[inline(never)]
+pub fn rustc_clr_interop_managed_array_new_<T>(len: usize) -> RustcCLRInteropManagedArray<T, 1> {
+    core::intrinsics::abort();
+}
+#[allow(unused_variables)]
+#[inline(never)]
+pub fn rustc_clr_interop_managed_array_len_<T>(arr: RustcCLRInteropManagedArray<T, 1>) -> usize {
+    core::intrinsics::abort();
+}
+#[allow(unused_variables)]
+#[inline(never)]
+pub fn rustc_clr_interop_managed_array_get_<T>(
+    arr: RustcCLRInteropManagedArray<T, 1>,
+    idx: usize,
+) -> T {
+    core::intrinsics::abort();
+}
+#[allow(unused_variables)]
+#[inline(never)]
+pub fn rustc_clr_interop_managed_array_set_<T>(
+    arr: RustcCLRInteropManagedArray<T, 1>,
+    idx: usize,
+    value: T,
+) {
+    core::intrinsics::abort();
+}
 impl From<u16> for RustcCLRInteropManagedChar {
     fn from(utf16_char: u16) -> RustcCLRInteropManagedChar {
         unsafe {
@@ -5,6 +5,8 @@
 #![no_std]
 #![allow(internal_features, incomplete_features)]
 #![feature(core_intrinsics, adt_const_params)]
+#[cfg(feature = "std")]
+extern crate alloc;
 
 /// Very low-level interop stuff. Don't use unless you need to.
 pub mod intrinsics;
@@ -14,6 +16,8 @@ pub mod std;
 pub mod system;
 /// C# `char` type
 pub type DotNetChar = crate::intrinsics::RustcCLRInteropManagedChar;
+/// A reference to a one-dimensional, zero-based managed array (`T[]` in C#).
+pub type ManagedArray<T> = crate::intrinsics::RustcCLRInteropManagedArray<T, 1>;
 
 #[macro_export]
 macro_rules! panic_handler {
@@ -41,3 +45,16 @@ macro_rules! start {
         }
     };
 }
+/// Exports a function as a public static method of a .NET class, making it callable from C#.
+/// ```ignore
+/// mycorrhiza::export!{"MyLib.Math", "Add",
+///     pub fn add(a: i32, b: i32) -> i32 { a + b }
+/// }
+/// ```
+#[macro_export]
+macro_rules! export {
+    ($class_path:literal, $method:literal, $fn_def:item) => {
+        #[export_name = concat!("rustc_clr_interop_export:", $class_path, "::", $method)]
+        $fn_def
+    };
+}
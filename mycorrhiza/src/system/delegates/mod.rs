@@ -0,0 +1,146 @@
+//! Delegates wrapping Rust functions and closures.
+//! A delegate created from a closure targets a `RustClosureState` object, which owns a copy of that closure, placed in unmanaged memory.
+//! When the delegate gets collected, the finalizer of the state object drops the closure and frees its memory.
+use crate::intrinsics::{
+    rustc_clr_interop_managed_delegate_ctor_, RustcCLRInteropManagedClass,
+    RustcCLRInteropManagedGenericClass,
+};
+use crate::system::runtime::interop_services::Marshal;
+use crate::system::MObject;
+pub type Action = RustcCLRInteropManagedClass<"System.Runtime", "System.Action">;
+pub type Action1<A> = RustcCLRInteropManagedGenericClass<"System.Runtime", "System.Action`1", (A,)>;
+pub type Action2<A, B> =
+    RustcCLRInteropManagedGenericClass<"System.Runtime", "System.Action`2", (A, B)>;
+pub type Func1<R> = RustcCLRInteropManagedGenericClass<"System.Runtime", "System.Func`1", (R,)>;
+pub type Func2<A, R> =
+    RustcCLRInteropManagedGenericClass<"System.Runtime", "System.Func`2", (A, R)>;
+pub type Func3<A, B, R> =
+    RustcCLRInteropManagedGenericClass<"System.Runtime", "System.Func`3", (A, B, R)>;
+/// A managed object owning the state of a closure. Emitted by the codegen into each assembly.
+pub type RustClosureState = RustcCLRInteropManagedClass<"", "RustClosureState">;
+impl RustClosureState {
+    /// Moves `closure` into unmanaged memory, owned by the returned object. The closure is dropped when the object is collected.
+    fn new<F>(closure: F) -> Self {
+        // AllocHGlobal may return null for 0 sized allocations, so at least 1 byte is always allocated.
+        let size = core::mem::size_of::<F>().max(1) as isize;
+        let state = Marshal::alloc_hglobal(size) as *mut F;
+        unsafe { state.write(closure) };
+        let drop: unsafe fn(*mut F) = core::ptr::drop_in_place::<F>;
+        Self::ctor2::<*mut (), isize>(state as *mut (), drop as usize as isize)
+    }
+}
+/// Recovers the pointer to closure state from the target of a delegate.
+#[inline(always)]
+fn closure_state<F>(target: MObject) -> *mut F {
+    target
+        .cast::<"", "RustClosureState">()
+        .instance0::<"get_State", *mut ()>() as *mut F
+}
+fn action_trampoline<F: Fn()>(target: MObject) {
+    unsafe { (*closure_state::<F>(target))() }
+}
+fn action1_trampoline<F: Fn(A), A>(target: MObject, a: A) {
+    unsafe { (*closure_state::<F>(target))(a) }
+}
+fn action2_trampoline<F: Fn(A, B), A, B>(target: MObject, a: A, b: B) {
+    unsafe { (*closure_state::<F>(target))(a, b) }
+}
+fn func1_trampoline<F: Fn() -> R, R>(target: MObject) -> R {
+    unsafe { (*closure_state::<F>(target))() }
+}
+fn func2_trampoline<F: Fn(A) -> R, A, R>(target: MObject, a: A) -> R {
+    unsafe { (*closure_state::<F>(target))(a) }
+}
+fn func3_trampoline<F: Fn(A, B) -> R, A, B, R>(target: MObject, a: A, b: B) -> R {
+    unsafe { (*closure_state::<F>(target))(a, b) }
+}
+impl Action {
+    /// Creates a delegate calling the function `f`.
+    #[inline(always)]
+    pub fn from_fn(f: fn()) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, (), fn()>((), f)
+    }
+    /// Creates a delegate calling the closure `f`.
+    #[inline(always)]
+    pub fn from_closure<F: Fn() + 'static>(f: F) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, RustClosureState, fn(MObject)>(
+            RustClosureState::new(f),
+            action_trampoline::<F>,
+        )
+    }
+}
+impl<A> Action1<A> {
+    /// Creates a delegate calling the function `f`.
+    #[inline(always)]
+    pub fn from_fn(f: fn(A)) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, (), fn(A)>((), f)
+    }
+    /// Creates a delegate calling the closure `f`.
+    #[inline(always)]
+    pub fn from_closure<F: Fn(A) + 'static>(f: F) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, RustClosureState, fn(MObject, A)>(
+            RustClosureState::new(f),
+            action1_trampoline::<F, A>,
+        )
+    }
+}
+impl<A, B> Action2<A, B> {
+    /// Creates a delegate calling the function `f`.
+    #[inline(always)]
+    pub fn from_fn(f: fn(A, B)) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, (), fn(A, B)>((), f)
+    }
+    /// Creates a delegate calling the closure `f`.
+    #[inline(always)]
+    pub fn from_closure<F: Fn(A, B) + 'static>(f: F) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, RustClosureState, fn(MObject, A, B)>(
+            RustClosureState::new(f),
+            action2_trampoline::<F, A, B>,
+        )
+    }
+}
+impl<R> Func1<R> {
+    /// Creates a delegate calling the function `f`.
+    #[inline(always)]
+    pub fn from_fn(f: fn() -> R) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, (), fn() -> R>((), f)
+    }
+    /// Creates a delegate calling the closure `f`.
+    #[inline(always)]
+    pub fn from_closure<F: Fn() -> R + 'static>(f: F) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, RustClosureState, fn(MObject) -> R>(
+            RustClosureState::new(f),
+            func1_trampoline::<F, R>,
+        )
+    }
+}
+impl<A, R> Func2<A, R> {
+    /// Creates a delegate calling the function `f`.
+    #[inline(always)]
+    pub fn from_fn(f: fn(A) -> R) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, (), fn(A) -> R>((), f)
+    }
+    /// Creates a delegate calling the closure `f`.
+    #[inline(always)]
+    pub fn from_closure<F: Fn(A) -> R + 'static>(f: F) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, RustClosureState, fn(MObject, A) -> R>(
+            RustClosureState::new(f),
+            func2_trampoline::<F, A, R>,
+        )
+    }
+}
+impl<A, B, R> Func3<A, B, R> {
+    /// Creates a delegate calling the function `f`.
+    #[inline(always)]
+    pub fn from_fn(f: fn(A, B) -> R) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, (), fn(A, B) -> R>((), f)
+    }
+    /// Creates a delegate calling the closure `f`.
+    #[inline(always)]
+    pub fn from_closure<F: Fn(A, B) -> R + 'static>(f: F) -> Self {
+        rustc_clr_interop_managed_delegate_ctor_::<Self, RustClosureState, fn(MObject, A, B) -> R>(
+            RustClosureState::new(f),
+            func3_trampoline::<F, A, B, R>,
+        )
+    }
+}
@@ -1,6 +1,10 @@
 pub mod console;
+pub mod delegates;
 pub mod diagnostics;
 pub mod runtime;
+mod string;
 pub mod text;
+pub type MObject =
+    crate::intrinsics::RustcCLRInteropManagedClass<"System.Runtime", "System.Object">;
 pub type MString =
     crate::intrinsics::RustcCLRInteropManagedClass<"System.Runtime", "System.String">;
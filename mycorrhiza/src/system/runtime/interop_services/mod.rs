@@ -1 +1,89 @@
-
+use crate::intrinsics::{RustcCLRInteropManagedClass, RustcCLRInteropManagedStruct};
+use crate::system::MObject;
+use core::marker::PhantomData;
+pub type Marshal = RustcCLRInteropManagedClass<
+    "System.Runtime.InteropServices",
+    "System.Runtime.InteropServices.Marshal",
+>;
+impl Marshal {
+    #[inline(always)]
+    pub fn alloc_hglobal(size: isize) -> isize {
+        Self::static1::<"AllocHGlobal", isize, isize>(size)
+    }
+    #[inline(always)]
+    pub fn free_hglobal(ptr: isize) {
+        Self::static1::<"FreeHGlobal", isize, ()>(ptr)
+    }
+}
+pub type GCHandle = RustcCLRInteropManagedStruct<
+    "System.Runtime",
+    "System.Runtime.InteropServices.GCHandle",
+>;
+pub type GCHandleType = RustcCLRInteropManagedStruct<
+    "System.Runtime",
+    "System.Runtime.InteropServices.GCHandleType",
+>;
+impl GCHandleType {
+    /// `GCHandleType.Pinned`. The enum is backed by an `int32`, which is read from the low bytes of the value.
+    #[inline(always)]
+    pub fn pinned() -> Self {
+        unsafe { core::intrinsics::transmute_unchecked::<usize, Self>(3) }
+    }
+}
+impl GCHandle {
+    #[inline(always)]
+    pub fn alloc(target: MObject) -> Self {
+        Self::static1::<"Alloc", MObject, Self>(target)
+    }
+    /// Allocates a handle which prevents the GC from moving `target`, until the handle is freed.
+    #[inline(always)]
+    pub fn alloc_pinned(target: MObject) -> Self {
+        Self::static2::<"Alloc", MObject, GCHandleType, Self>(target, GCHandleType::pinned())
+    }
+    #[inline(always)]
+    pub fn addr_of_pinned_object(&mut self) -> isize {
+        self.instance0::<"AddrOfPinnedObject", isize>()
+    }
+    #[inline(always)]
+    pub fn to_intptr(self) -> isize {
+        Self::static1::<"ToIntPtr", Self, isize>(self)
+    }
+    #[inline(always)]
+    pub fn from_intptr(ptr: isize) -> Self {
+        Self::static1::<"FromIntPtr", isize, Self>(ptr)
+    }
+    #[inline(always)]
+    pub fn target(&mut self) -> MObject {
+        self.instance0::<"get_Target", MObject>()
+    }
+    #[inline(always)]
+    pub fn free(&mut self) {
+        self.instance0::<"Free", ()>()
+    }
+}
+/// An owned handle to a managed object. Unlike a raw managed reference, it can be stored anywhere(eg. inside a `Box` or a `Vec`),
+/// since it only holds an index into the GC handle table. The object is kept alive until the handle is dropped.
+pub struct GcHandle<T> {
+    handle: isize,
+    pd: PhantomData<T>,
+}
+impl<const ASSEMBLY: &'static str, const CLASS_PATH: &'static str>
+    GcHandle<RustcCLRInteropManagedClass<ASSEMBLY, CLASS_PATH>>
+{
+    #[inline(always)]
+    pub fn new(target: RustcCLRInteropManagedClass<ASSEMBLY, CLASS_PATH>) -> Self {
+        Self {
+            handle: GCHandle::alloc(target.as_object()).to_intptr(),
+            pd: PhantomData,
+        }
+    }
+    #[inline(always)]
+    pub fn target(&self) -> RustcCLRInteropManagedClass<ASSEMBLY, CLASS_PATH> {
+        GCHandle::from_intptr(self.handle).target().cast()
+    }
+}
+impl<T> Drop for GcHandle<T> {
+    fn drop(&mut self) {
+        GCHandle::from_intptr(self.handle).free();
+    }
+}
@@ -0,0 +1,89 @@
+//! Conversions between Rust strings and `System.String`.
+use crate::system::runtime::interop_services::GCHandle;
+use crate::system::text::Encoding;
+use crate::system::MString;
+impl MString {
+    /// Creates a new managed string, decoding the UTF-8 bytes of `string` in place.
+    #[inline(always)]
+    pub fn from_rust_str(string: &str) -> Self {
+        let len: i32 = string
+            .len()
+            .try_into()
+            .expect("String too long to be converted to a System.String");
+        Encoding::utf8().get_string(string.as_ptr(), len)
+    }
+    /// The length of this string, in UTF-16 code units.
+    #[inline(always)]
+    pub fn len(self) -> usize {
+        self.instance0::<"get_Length", i32>() as usize
+    }
+    #[inline(always)]
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+    /// The length of this string, once encoded as UTF-8.
+    #[inline(always)]
+    pub fn utf8_len(self) -> usize {
+        Encoding::utf8().get_byte_count(self) as usize
+    }
+    /// Pins this string, and calls `f` with its UTF-16 contents. The string is unpinned once `f` returns.
+    pub fn with_utf16<R>(self, f: impl FnOnce(&[u16]) -> R) -> R {
+        let mut handle = GCHandle::alloc_pinned(self.as_object());
+        let chars = handle.addr_of_pinned_object() as *const u16;
+        let res = f(unsafe { core::slice::from_raw_parts(chars, self.len()) });
+        handle.free();
+        res
+    }
+    /// Encodes this string as UTF-8 into `buf`, returning the encoded part of `buf`.
+    /// # Panics
+    /// Panics if `buf` is shorter than [`Self::utf8_len`].
+    pub fn encode_utf8(self, buf: &mut [u8]) -> &mut str {
+        let needed = self.utf8_len();
+        assert!(
+            buf.len() >= needed,
+            "Buffer too small to hold the UTF-8 encoding of a System.String"
+        );
+        let written = self.with_utf16(|chars| {
+            Encoding::utf8().get_bytes(
+                chars.as_ptr().cast(),
+                chars.len() as i32,
+                buf.as_mut_ptr(),
+                needed as i32,
+            )
+        }) as usize;
+        // Safety: `Encoding.UTF8` always produces valid UTF-8.
+        unsafe { core::str::from_utf8_unchecked_mut(&mut buf[..written]) }
+    }
+    /// Converts this string into a Rust `String`.
+    #[cfg(feature = "std")]
+    pub fn to_rust_string(self) -> alloc::string::String {
+        let mut buf = alloc::vec![0; self.utf8_len()];
+        let len = self.encode_utf8(&mut buf).len();
+        buf.truncate(len);
+        // Safety: `encode_utf8` only writes valid UTF-8.
+        unsafe { alloc::string::String::from_utf8_unchecked(buf) }
+    }
+}
+impl From<&str> for MString {
+    #[inline(always)]
+    fn from(string: &str) -> Self {
+        Self::from_rust_str(string)
+    }
+}
+#[cfg(feature = "std")]
+impl From<MString> for alloc::string::String {
+    fn from(string: MString) -> Self {
+        string.to_rust_string()
+    }
+}
+impl core::fmt::Display for MString {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use core::fmt::Write;
+        self.with_utf16(|chars| {
+            for chr in core::char::decode_utf16(chars.iter().copied()) {
+                f.write_char(chr.unwrap_or(core::char::REPLACEMENT_CHARACTER))?;
+            }
+            Ok(())
+        })
+    }
+}
@@ -14,3 +14,33 @@ impl StringBuilder {
         self.append_mchar(crate::DotNetChar::single_codepoint_unchecked(chr))
     }
 }
+pub type Encoding =
+    crate::intrinsics::RustcCLRInteropManagedClass<"System.Runtime", "System.Text.Encoding">;
+impl Encoding {
+    #[inline(always)]
+    pub fn utf8() -> Self {
+        Self::static0::<"get_UTF8", Self>()
+    }
+    /// Decodes `byte_count` bytes at `bytes` into a new managed string.
+    #[inline(always)]
+    pub fn get_string(self, bytes: *const u8, byte_count: i32) -> crate::system::MString {
+        self.instance2::<"GetString", *const u8, i32, crate::system::MString>(bytes, byte_count)
+    }
+    #[inline(always)]
+    pub fn get_byte_count(self, string: crate::system::MString) -> i32 {
+        self.instance1::<"GetByteCount", crate::system::MString, i32>(string)
+    }
+    /// Encodes `char_count` UTF-16 code units at `chars` into `bytes`, returning the number of bytes written.
+    #[inline(always)]
+    pub fn get_bytes(
+        self,
+        chars: *const crate::DotNetChar,
+        char_count: i32,
+        bytes: *mut u8,
+        byte_count: i32,
+    ) -> i32 {
+        self.instance4::<"GetBytes", *const crate::DotNetChar, i32, *mut u8, i32, i32>(
+            chars, char_count, bytes, byte_count,
+        )
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 /// Specifies if an item is public, or not
 #[derive(Copy, Clone, PartialEq, Serialize, Deserialize, Eq, Hash, Debug)]
 pub enum AccessModifer {
-    /// Specifies that the given item is private
+    /// Specifies that the given item is private(not visible outside of its assembly)
     Private,
     /// Specifies that the givem item is public
     Public,
@@ -6,11 +6,12 @@ use crate::{
     place::place_get,
     r#type::{DotnetTypeRef, TyCache, Type},
     utilis::{adt::set_discr, field_name, monomorphize},
+    IString,
 };
 use rustc_index::IndexVec;
 use rustc_middle::mir::{AggregateKind, Operand, Place};
 use rustc_middle::ty::{AdtDef, AdtKind, GenericArg, Instance, List, ParamEnv, Ty, TyCtxt, TyKind};
-use rustc_target::abi::FieldIdx;
+use rustc_target::abi::{FieldIdx, VariantIdx};
 /// Returns the CIL ops to create the aggreagate value specifed by `aggregate_kind` at `target_location`. Uses indivlidual values specifed by `value_index`
 pub fn handle_aggregate<'tyctx>(
     tyctx: TyCtxt<'tyctx>,
@@ -124,6 +125,7 @@ pub fn handle_aggregate<'tyctx>(
                 })
                 .collect();
             let dotnet_tpe = crate::r#type::simple_tuple(&types);
+            let tuple_ty = target_location.ty(method, tyctx).ty;
             let mut sub_trees = Vec::new();
             for field in &values {
                 // Assigining to a Void field is a NOP and must be skipped(since it can have wierd side-effects).
@@ -131,6 +133,16 @@ pub fn handle_aggregate<'tyctx>(
                     continue;
                 }
                 let name = format!("Item{}", field.0 + 1);
+                crate::utilis::check_field_offset(
+                    tuple_ty,
+                    None,
+                    field.0,
+                    &dotnet_tpe,
+                    &name,
+                    tyctx,
+                    method_instance,
+                    tycache,
+                );
 
                 sub_trees.push(CILRoot::SetField {
                     addr: tuple_getter.clone(),
@@ -174,15 +186,21 @@ pub fn handle_aggregate<'tyctx>(
                 let field_ty =
                     crate::utilis::monomorphize(&method_instance, value.ty(method, tyctx), tyctx);
                 let field_ty = tycache.type_from_cache(field_ty, tyctx, Some(method_instance));
-
+                let field_name = format!("f_{}", index.as_u32());
+                crate::utilis::check_field_offset(
+                    closure_ty,
+                    None,
+                    index.as_u32(),
+                    &closure_dotnet,
+                    &field_name,
+                    tyctx,
+                    method_instance,
+                    tycache,
+                );
                 sub_trees.push(CILRoot::SetField {
                     addr: closure_getter.clone(),
                     value: handle_operand(value, tyctx, method, method_instance, tycache),
-                    desc: FieldDescriptor::new(
-                        closure_dotnet.clone(),
-                        field_ty,
-                        format!("f_{}", index.as_u32()).into(),
-                    ),
+                    desc: FieldDescriptor::new(closure_dotnet.clone(), field_ty, field_name.into()),
                 });
             }
 
@@ -197,6 +215,73 @@ pub fn handle_aggregate<'tyctx>(
                 )),
             )
         }
+        AggregateKind::Coroutine(_def_id, _args) => {
+            let coroutine_ty = crate::utilis::monomorphize(
+                &method_instance,
+                target_location.ty(method, tyctx),
+                tyctx,
+            )
+            .ty;
+            let coroutine_type =
+                tycache.type_from_cache(coroutine_ty, tyctx, Some(method_instance));
+            let coroutine_dotnet = coroutine_type
+                .as_dotnet()
+                .expect("Invalid coroutine type!");
+            let coroutine_getter = super::place::place_adress(
+                target_location,
+                tyctx,
+                method,
+                method_instance,
+                tycache,
+            );
+            let mut sub_trees = vec![];
+            for (index, value) in value_index.iter_enumerated() {
+                let field_ty =
+                    crate::utilis::monomorphize(&method_instance, value.ty(method, tyctx), tyctx);
+                let field_ty = tycache.type_from_cache(field_ty, tyctx, Some(method_instance));
+                let field_name = format!("f_{}", index.as_u32());
+                crate::utilis::check_field_offset(
+                    coroutine_ty,
+                    None,
+                    index.as_u32(),
+                    &coroutine_dotnet,
+                    &field_name,
+                    tyctx,
+                    method_instance,
+                    tycache,
+                );
+                sub_trees.push(CILRoot::SetField {
+                    addr: coroutine_getter.clone(),
+                    value: handle_operand(value, tyctx, method, method_instance, tycache),
+                    desc: FieldDescriptor::new(coroutine_dotnet.clone(), field_ty, field_name.into()),
+                });
+            }
+            // A freshly created coroutine is in the `Unresumed` state.
+            let layout = tyctx
+                .layout_of(rustc_middle::ty::ParamEnvAnd {
+                    param_env: ParamEnv::reveal_all(),
+                    value: coroutine_ty,
+                })
+                .expect("Could not get type layout!");
+            sub_trees.push(set_discr(
+                layout.layout,
+                rustc_target::abi::FIRST_VARIANT,
+                coroutine_getter,
+                &coroutine_dotnet,
+                tyctx,
+                coroutine_ty,
+            ));
+            CILNode::SubTrees(
+                sub_trees.into(),
+                Box::new(place_get(
+                    target_location,
+                    tyctx,
+                    method,
+                    method_instance,
+                    tycache,
+                )),
+            )
+        }
         AggregateKind::RawPtr(ptr, muta) => {
             let fat_ptr = Ty::new_ptr(
                 tyctx,
@@ -352,7 +437,7 @@ fn aggregate_adt<'tyctx>(
                 .nth(variant_idx as usize)
                 .expect("Can't get variant index");
             for (field, field_value) in enum_variant.fields.iter().zip(fields.iter()) {
-                let field_name = format!(
+                let field_name: IString = format!(
                     "{variant_name}_{fname}",
                     fname = crate::r#type::escape_field_name(&field.name.to_string())
                 )
@@ -366,6 +451,16 @@ fn aggregate_adt<'tyctx>(
                 if field_type == Type::Void {
                     continue;
                 }
+                crate::utilis::check_field_offset(
+                    adt_type,
+                    Some(VariantIdx::from_u32(variant_idx)),
+                    field_value.0,
+                    &adt_type_ref,
+                    &field_name,
+                    tyctx,
+                    method_instance,
+                    type_cache,
+                );
 
                 sub_trees.push(CILRoot::SetField {
                     addr: variant_address.clone(),
@@ -9,16 +9,16 @@ use crate::utilis::field_descrptor;
 use crate::{
     access_modifier::AccessModifer, cil::CallSite, codegen_error::CodegenError,
     codegen_error::MethodCodegenError, function_sig::FnSig, method::Method, r#type::TyCache,
-    r#type::Type, r#type::TypeDef, IString,
+    r#type::DotnetTypeRef, r#type::Type, r#type::TypeDef, IString,
 };
-use crate::{call, conv_isize, conv_usize, ldc_u32, ldc_u64};
+use crate::{call, conv_isize, conv_usize, eq, ldc_u32, ldc_u64, size_of};
 use rustc_middle::mir::interpret::Allocation;
 use rustc_middle::mir::{
     interpret::{AllocId, GlobalAlloc},
     mono::MonoItem,
     Local, LocalDecl, Statement, Terminator,
 };
-use rustc_middle::ty::{Instance, ParamEnv, TyCtxt, TyKind};
+use rustc_middle::ty::{Instance, InstanceDef, ParamEnv, TyCtxt, TyKind};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 #[derive(Clone, Copy, Serialize, Deserialize, Debug)]
@@ -48,15 +48,22 @@ pub struct Assembly {
     entrypoint: Option<CallSite>,
     /// List of references to external assemblies
     extern_refs: HashMap<IString, AssemblyExternRef>,
-    extern_fns: HashMap<(IString, FnSig), IString>,
+    /// Functions imported from native libraries: their names, signatures, and if they are variadic, mapped to the library they come from.
+    extern_fns: HashMap<(IString, FnSig, bool), IString>,
     /// List of all static fields within the assembly
     static_fields: HashMap<IString, Type>,
+    /// List of all thread-local static fields(marked with `[ThreadStatic]`) within the assembly
+    thread_locals: HashMap<IString, Type>,
 }
 impl Assembly {
     /// Returns iterator over all global fields
     pub fn globals(&self) -> impl Iterator<Item = (&IString, &Type)> {
         self.static_fields.iter()
     }
+    /// Returns iterator over all thread-local global fields
+    pub fn thread_locals(&self) -> impl Iterator<Item = (&IString, &Type)> {
+        self.thread_locals.iter()
+    }
     /// Returns the `.cctor` function used to initialize static data
     #[must_use]
     pub fn cctor(&self) -> Option<&Method> {
@@ -81,6 +88,7 @@ impl Assembly {
             entrypoint: None,
             extern_refs: HashMap::new(),
             static_fields: HashMap::new(),
+            thread_locals: HashMap::new(),
             extern_fns: HashMap::new(),
         };
         let dotnet_ver = AssemblyExternRef {
@@ -90,6 +98,8 @@ impl Assembly {
         //res.extern_refs.insert("mscorlib".into(),dotnet_ver);
         res.extern_refs
             .insert("System.Runtime.InteropServices".into(), dotnet_ver);
+        res.extern_refs
+            .insert("System.Runtime.Intrinsics".into(), dotnet_ver);
         // Needed to get C-Mode to work
         res.add_cctor();
         res
@@ -99,7 +109,24 @@ impl Assembly {
     pub fn join(self, other: Self) -> Self {
         let static_initializer = link_static_initializers(self.cctor(), other.cctor());
         let mut types = self.types;
-        types.extend(other.types);
+        for (name, tpe) in other.types {
+            match types.get_mut(&name) {
+                // Functions exported by different crates may share a class, so the methods of both definitions must be kept.
+                // Methods defined by both(eg. the ones of builtin types) must be emitted only once.
+                Some(existing) => {
+                    for method in tpe.methods() {
+                        if !existing.methods().any(|existing| {
+                            existing.name() == method.name() && existing.sig() == method.sig()
+                        }) {
+                            existing.add_method(method.clone());
+                        }
+                    }
+                }
+                None => {
+                    types.insert(name, tpe);
+                }
+            }
+        }
         let mut functions = self.functions;
         functions.extend(other.functions);
         if let Some(static_initializer) = static_initializer {
@@ -110,6 +137,8 @@ impl Assembly {
         let mut static_fields = self.static_fields;
         let mut extern_fns = self.extern_fns;
         static_fields.extend(other.static_fields);
+        let mut thread_locals = self.thread_locals;
+        thread_locals.extend(other.thread_locals);
         extern_refs.extend(other.extern_refs);
         extern_fns.extend(other.extern_fns);
         Self {
@@ -119,6 +148,7 @@ impl Assembly {
             extern_refs,
             extern_fns,
             static_fields,
+            thread_locals,
         }
     }
     /// Gets the typdefef at path `path`.
@@ -231,6 +261,7 @@ impl Assembly {
             Ok(success) => success,
             Err(payload) => {
                 cache.recover_from_panic();
+                cache.set_aligned_locals(HashMap::new());
                 if let Some(msg) = payload.downcast_ref::<&str>() {
                     eprintln!("could not compile method {name}. fn_add panicked with unhandled message: {msg:?}");
                     //self.add_method(Method::missing_because(format!("could not compile method {name}. fn_add panicked with unhandled message: {msg:?}")));
@@ -254,19 +285,21 @@ impl Assembly {
         if crate::utilis::is_function_magic(name) {
             return Ok(());
         }
-        if let TyKind::FnDef(_, _) = instance.ty(tyctx, ParamEnv::reveal_all()).kind() {
+        if let InstanceDef::ThreadLocalShim(_) = instance.def {
+            // Shim used by other crates to access a thread local. Its type is the type of the static, and not a function type.
+        } else if let TyKind::FnDef(_, _) = instance.ty(tyctx, ParamEnv::reveal_all()).kind() {
             //ALL OK.
         } else if let TyKind::Closure(_, _) = instance.ty(tyctx, ParamEnv::reveal_all()).kind() {
             //println!("CLOSURE")
+        } else if let TyKind::Coroutine(_, _) = instance.ty(tyctx, ParamEnv::reveal_all()).kind() {
+            // The resume function of a coroutine(its MIR is already lowered into a state machine).
         } else {
             eprintln!("fn item {instance:?} is not a function definition type. Skippping.");
             return Ok(());
         }
         let mir = tyctx.instance_mir(instance.def);
-        // Check if function is public or not.
-        // FIXME: figure out the source of the bug causing visibility to not be read propely.
-        // let access_modifier = AccessModifer::from_visibility(tcx.visibility(instance.def_id()));
-        let access_modifier = AccessModifer::Public;
+        // Rust functions are only visible inside the assembly. Functions exported using `mycorrhiza::export!` get a public wrapper instead.
+        let access_modifier = AccessModifer::Private;
         // Handle the function signature
         let call_site = crate::call_info::CallInfo::sig_from_instance_(instance, tyctx, cache);
         let sig = call_site.sig().clone();
@@ -281,6 +314,21 @@ impl Assembly {
             cache,
             &mir.var_debug_info,
         );
+        // Locals requiring a greater aligement than the one .NET guarantees, and big temporaries, are moved to stack buffers.
+        // The pointers to those buffers are placed after the repacked spread argument.
+        let aligned_locals = crate::place::stack_buffer_locals(mir, tyctx, &instance, cache);
+        let first_aligned = locals.len() + usize::from(mir.spread_arg.is_some());
+        cache.set_aligned_locals(
+            aligned_locals
+                .iter()
+                .enumerate()
+                .map(|(idx, (local, _))| {
+                    let ptr = u32::try_from(first_aligned + idx)
+                        .expect("Method has more than 2^32 local varaibles");
+                    (*local, ptr)
+                })
+                .collect(),
+        );
 
         let blocks = &mir.basic_blocks;
         //let mut trees = Vec::new();
@@ -375,6 +423,70 @@ impl Assembly {
             repack_cil.append(first_bb.trees_mut());
             *first_bb.trees_mut() = repack_cil;
         }
+        // Allocate the stack buffers of over-aligned and big locals.
+        let mut align_cil = Vec::new();
+        for (local, align) in aligned_locals {
+            let ty =
+                crate::utilis::monomorphize(&instance, mir.local_decls[local.into()].ty, tyctx);
+            let tpe = cache.type_from_cache(ty, tyctx, Some(instance));
+            let ptr = cache
+                .aligned_local_ptr(local)
+                .expect("Over-aligned local has no pointer to its storage");
+            debug_assert_eq!(
+                ptr as usize,
+                locals.len(),
+                "The pointer to the aligned storage of local {local} is not where places expect it."
+            );
+            locals.push((
+                Some(format!("aligned_{local}").into()),
+                Type::Ptr(tpe.clone().into()),
+            ));
+            let buffer = match align {
+                Some(align) => CILNode::LocAllocAligned {
+                    tpe: tpe.clone().into(),
+                    align,
+                },
+                // `localloc` already returns a pointer aligned enough for any type .NET supports.
+                None => CILNode::TransmutePtr {
+                    val: CILNode::LocAlloc {
+                        size: conv_usize!(size_of!(tpe.clone())).into(),
+                    }
+                    .into(),
+                    new_ptr: Type::Ptr(tpe.clone().into()).into(),
+                },
+            };
+            align_cil.push(
+                CILRoot::STLoc {
+                    local: ptr,
+                    tree: buffer,
+                }
+                .into(),
+            );
+            // Arguments need to be copied to their aligned storage.
+            if local != 0 && local <= mir.arg_count {
+                align_cil.push(
+                    CILRoot::STObj {
+                        tpe: tpe.into(),
+                        addr_calc: CILNode::LDLoc(ptr),
+                        value_calc: CILNode::LDArg(u32::try_from(local - 1).unwrap()),
+                    }
+                    .into(),
+                );
+            }
+        }
+        if !align_cil.is_empty() {
+            // The first MIR block may be a loop header, so the buffers are allocated in a separate entry block, executed only once.
+            let entry =
+                u32::try_from(mir.basic_blocks.len()).expect("More than 2^32 basic blocks!");
+            align_cil.push(
+                CILRoot::GoTo {
+                    target: 0,
+                    sub_target: 0,
+                }
+                .into(),
+            );
+            normal_bbs.insert(0, BasicBlock::new(align_cil, entry, None));
+        }
         normal_bbs
             .iter_mut()
             .for_each(|bb| bb.resolve_exception_handlers(&cleanup_bbs));
@@ -388,6 +500,8 @@ impl Assembly {
             normal_bbs,
         )
         .with_argnames(arg_names);
+        // The aligned locals belong only to this method.
+        cache.set_aligned_locals(HashMap::new());
         method.resolve_global_allocations(self, tyctx, cache);
         // TODO: Why is this even needed? The temporaries *should* be already allocated, why not all of them are?
         method.allocate_temporaries();
@@ -401,12 +515,86 @@ impl Assembly {
             }
         }
 
-        let adjust = check_align_adjust(&mir.local_decls, tyctx, &instance);
-        method.adjust_aligement(adjust);
+        if let Some((class_path, method_name)) = crate::utilis::exported_as(instance, tyctx) {
+            self.add_export(&class_path, &method_name, &method);
+        }
+        // Non-capturing closures coerced to function pointers point to their `FnOnce` shim, which still takes the closure environment.
+        if let InstanceDef::ClosureOnceShim { .. } = instance.def {
+            self.add_closure_fn_ptr(&method);
+        }
         self.add_method(method);
         Ok(())
         //todo!("Can't add function")
     }
+    /// Exports `target` as a public static method named `method_name`, of the class `class_path`.
+    pub fn add_export(&mut self, class_path: &str, method_name: &str, target: &Method) {
+        let sig = target.sig().clone();
+        let args: Box<[_]> = (0..sig.inputs().len())
+            .map(|arg| CILNode::LDArg(u32::try_from(arg).unwrap()))
+            .collect();
+        let trees = if *sig.output() == Type::Void {
+            vec![
+                CILRoot::Call {
+                    site: target.call_site(),
+                    args,
+                }
+                .into(),
+                CILRoot::VoidRet.into(),
+            ]
+        } else {
+            vec![CILRoot::Ret {
+                tree: call!(target.call_site(), args),
+            }
+            .into()]
+        };
+        let wrapper = Method::new(
+            AccessModifer::Public,
+            MethodType::Static,
+            sig,
+            method_name,
+            vec![],
+            vec![BasicBlock::new(trees, 0, None)],
+        )
+        .with_argnames(target.arg_names().to_vec());
+        self.types
+            .entry(class_path.into())
+            .or_insert_with(|| TypeDef::nameonly(class_path))
+            .add_method(wrapper);
+    }
+    /// Adds the function pointer target of the closure `shim`, which drops the zero-sized closure environment from its signature.
+    /// See [`CallSite::closure_fn_ptr`].
+    fn add_closure_fn_ptr(&mut self, shim: &Method) {
+        let site = CallSite::closure_fn_ptr(&shim.call_site());
+        let sig = site.signature().clone();
+        // The environment is a ZST, so an uninitialized local is as good as any other value.
+        let args: Box<[_]> = std::iter::once(CILNode::LDLoc(0))
+            .chain((0..sig.inputs().len()).map(|arg| CILNode::LDArg(u32::try_from(arg).unwrap())))
+            .collect();
+        let trees = if *sig.output() == Type::Void {
+            vec![
+                CILRoot::Call {
+                    site: shim.call_site(),
+                    args,
+                }
+                .into(),
+                CILRoot::VoidRet.into(),
+            ]
+        } else {
+            vec![CILRoot::Ret {
+                tree: call!(shim.call_site(), args),
+            }
+            .into()]
+        };
+        let fn_ptr = Method::new(
+            AccessModifer::Private,
+            MethodType::Static,
+            sig,
+            site.name(),
+            vec![(Some("env".into()), shim.sig().inputs()[0].clone())],
+            vec![BasicBlock::new(trees, 0, None)],
+        );
+        self.add_method(fn_ptr);
+    }
     /// Adds a global static field named *name* of type *tpe*
     pub fn add_static(&mut self, tpe: Type, name: &str) {
         self.static_fields.insert(name.into(), tpe);
@@ -433,6 +621,27 @@ impl Assembly {
                 )
             })
     }
+    /// Appends `init` to the static constructor, before its return.
+    pub(crate) fn add_static_initializer(&mut self, init: CILRoot) {
+        let cctor = self.add_cctor();
+        let mut blocks = cctor.blocks_mut();
+        if blocks.is_empty() {
+            blocks.push(BasicBlock::new(vec![CILRoot::VoidRet.into()], 0, None));
+        }
+        assert_eq!(
+            blocks.len(),
+            1,
+            "Unexpected number of basic blocks in a static data initializer."
+        );
+        let trees = blocks[0].trees_mut();
+        // Remove return
+        let ret = trees.pop().unwrap();
+        // Append initailzer
+        trees.push(init.into());
+        //trees.push(CILRoot::debug(&format!("Finished initializing allocation {alloc_fld:?}")).into());
+        // Add return again
+        trees.push(ret);
+    }
     /// Adds a static field and initialized for allocation represented by `alloc_id`.
     pub fn add_allocation(
         &mut self,
@@ -448,30 +657,40 @@ impl Assembly {
                     //tcx.reserve_and_set_memory_alloc(alloc)
                     alloc
                 }
-                GlobalAlloc::VTable(..) => {
-                    //TODO: handle VTables
-                    let alloc_fld: IString = format!("alloc_{alloc_id:x}").into();
-                    let field_desc = crate::cil::StaticFieldDescriptor::new(
-                        None,
-                        Type::Ptr(Type::U8.into()),
-                        alloc_fld.clone(),
+                GlobalAlloc::VTable(ty, trait_ref) => {
+                    // `vtable_allocation` is cached per (type, trait) pair, and returns a memory allocation with the drop-in-place, size, align and method entries.
+                    // Function entries are relocations, patched up with `LDFtn` by the allocation initializer.
+                    let vtable_alloc = tcx.vtable_allocation((ty, trait_ref));
+                    return self.add_allocation(
+                        crate::utilis::alloc_id_to_u64(vtable_alloc),
+                        tcx,
+                        tycache,
                     );
-                    self.static_fields
-                        .insert(alloc_fld, Type::Ptr(Type::U8.into()));
-                    return field_desc;
                 }
-                GlobalAlloc::Function(_) => {
-                    //TODO: handle constant functions
-                    let alloc_fld: IString = format!("alloc_{alloc_id:x}").into();
+                GlobalAlloc::Function(finstance) => {
+                    // The address of a function allocation is the address of the function itself.
+                    let call_info =
+                        crate::call_info::CallInfo::sig_from_instance_(finstance, tcx, tycache);
+                    let function_name = crate::utilis::function_name(tcx.symbol_name(finstance));
+                    // Alloc ids are not unique across crates, so the hash of the function name is included too.
+                    let name_hash = calculate_hash(&function_name);
+                    let alloc_fld: IString = format!("alloc_{alloc_id:x}_{name_hash:x}").into();
                     let field_desc = crate::cil::StaticFieldDescriptor::new(
                         None,
                         Type::Ptr(Type::U8.into()),
                         alloc_fld.clone(),
                     );
-                    self.static_fields
-                        .insert(alloc_fld, Type::Ptr(Type::U8.into()));
+                    if !self.static_fields.contains_key(&alloc_fld) {
+                        self.add_static_initializer(CILRoot::SetStaticField {
+                            descr: field_desc.clone(),
+                            value: CILNode::LDFtn(
+                                CallSite::new(None, function_name, call_info.sig().clone(), true)
+                                    .into(),
+                            ),
+                        });
+                        self.add_static(Type::Ptr(Type::U8.into()), &alloc_fld);
+                    }
                     return field_desc;
-                    //todo!("Function/Vtable allocation.");
                 }
             };
 
@@ -492,46 +711,152 @@ impl Assembly {
         if !self.static_fields.contains_key(&alloc_fld) {
             let init_method =
                 allocation_initializer_method(const_allocation, &alloc_fld, tcx, self, tycache);
-            let cctor = self.add_cctor();
-            let mut blocks = cctor.blocks_mut();
-            if blocks.is_empty() {
-                blocks.push(BasicBlock::new(vec![CILRoot::VoidRet.into()], 0, None));
-            }
-            assert_eq!(
-                blocks.len(),
-                1,
-                "Unexpected number of basic blocks in a static data initializer."
-            );
-            let trees = blocks[0].trees_mut();
-            {
-                // Remove return
-                let ret = trees.pop().unwrap();
-                // Append initailzer
-                trees.push(
-                    CILRoot::SetStaticField {
-                        descr: field_desc.clone(),
-                        value: call!(
-                            CallSite::new(
-                                None,
-                                init_method.name().into(),
-                                init_method.sig().clone(),
-                                true,
-                            ),
-                            []
-                        ),
-                    }
-                    .into(),
-                );
-                //trees.push(CILRoot::debug(&format!("Finished initializing allocation {alloc_fld:?}")).into());
-                // Add return again
-                trees.push(ret);
-            }
-            drop(blocks);
+            self.add_static_initializer(CILRoot::SetStaticField {
+                descr: field_desc.clone(),
+                value: call!(
+                    CallSite::new(
+                        None,
+                        init_method.name().into(),
+                        init_method.sig().clone(),
+                        true,
+                    ),
+                    []
+                ),
+            });
             self.add_method(init_method);
             self.add_static(Type::Ptr(Type::U8.into()), &alloc_fld);
         }
         field_desc
     }
+    /// Adds a `[ThreadStatic]` field for the `#[thread_local]` static with allocation `alloc_id`. Each thread lazily initializes its own copy
+    /// of the static from the allocation bytes. Returns a node calling the getter of the current thread's copy.
+    pub fn add_thread_local(
+        &mut self,
+        alloc_id: u64,
+        tcx: TyCtxt<'_>,
+        tycache: &mut TyCache,
+    ) -> CILNode {
+        let GlobalAlloc::Static(def_id) =
+            tcx.global_alloc(AllocId(alloc_id.try_into().expect("0 alloc id?")))
+        else {
+            panic!("Thread local allocation {alloc_id:x} is not a static!");
+        };
+        // The name is based on the symbol of the static, so that all crates refer to the same field.
+        let symbol = crate::utilis::function_name(tcx.symbol_name(Instance::mono(tcx, def_id)));
+        let tls_fld: IString = format!("tls_{:x}", calculate_hash(&symbol)).into();
+        let field_desc = crate::cil::StaticFieldDescriptor::new(
+            None,
+            Type::Ptr(Type::U8.into()),
+            tls_fld.clone(),
+        );
+        let getter_sig = FnSig::new(&[], &Type::Ptr(Type::U8.into()));
+        let getter_name: IString = format!("get_{tls_fld}").into();
+        if !self.thread_locals.contains_key(&tls_fld) {
+            let alloc = tcx
+                .eval_static_initializer(def_id)
+                .expect("No initializer??");
+            let init_method =
+                allocation_initializer_method(alloc.inner(), &tls_fld, tcx, self, tycache);
+            let is_uninit = eq!(
+                CILNode::TransmutePtr {
+                    val: Box::new(CILNode::LDStaticField(field_desc.clone().into())),
+                    new_ptr: Box::new(Type::USize),
+                },
+                conv_usize!(ldc_u32!(0))
+            );
+            // The buffer is owned by a `RustThreadLocal`, stored in another thread static, which frees it once the thread exits.
+            // C has no finalizers, so there the buffer lives until the process exits.
+            let (owner_fld, own_buffer): (Option<IString>, CILTree) = if *crate::config::C_MODE {
+                (None, CILRoot::Nop.into())
+            } else {
+                let owner_fld: IString = format!("{tls_fld}_owner").into();
+                let owner = DotnetTypeRef::thread_local_owner();
+                let own_buffer = CILRoot::SetStaticField {
+                    descr: crate::cil::StaticFieldDescriptor::new(
+                        None,
+                        owner.clone().into(),
+                        owner_fld.clone(),
+                    ),
+                    value: CILNode::NewObj {
+                        site: Box::new(CallSite::new(
+                            Some(owner.clone()),
+                            ".ctor".into(),
+                            FnSig::new(
+                                &[owner.into(), Type::Ptr(Type::Void.into())],
+                                &Type::Void,
+                            ),
+                            false,
+                        )),
+                        args: [CILNode::TransmutePtr {
+                            val: Box::new(CILNode::LDStaticField(field_desc.clone().into())),
+                            new_ptr: Box::new(Type::Ptr(Type::Void.into())),
+                        }]
+                        .into(),
+                    },
+                }
+                .into();
+                (Some(owner_fld), own_buffer)
+            };
+            let getter = Method::new(
+                AccessModifer::Private,
+                MethodType::Static,
+                getter_sig.clone(),
+                &getter_name,
+                vec![],
+                vec![
+                    BasicBlock::new(
+                        vec![
+                            CILRoot::BTrue {
+                                target: 1,
+                                sub_target: 0,
+                                cond: is_uninit,
+                            }
+                            .into(),
+                            CILRoot::Ret {
+                                tree: CILNode::LDStaticField(field_desc.clone().into()),
+                            }
+                            .into(),
+                        ],
+                        0,
+                        None,
+                    ),
+                    BasicBlock::new(
+                        vec![
+                            CILRoot::SetStaticField {
+                                descr: field_desc.clone(),
+                                value: call!(
+                                    CallSite::new(
+                                        None,
+                                        init_method.name().into(),
+                                        init_method.sig().clone(),
+                                        true,
+                                    ),
+                                    []
+                                ),
+                            }
+                            .into(),
+                            own_buffer,
+                            CILRoot::Ret {
+                                tree: CILNode::LDStaticField(field_desc.into()),
+                            }
+                            .into(),
+                        ],
+                        1,
+                        None,
+                    ),
+                ],
+            );
+            self.add_method(init_method);
+            self.add_method(getter);
+            self.thread_locals
+                .insert(tls_fld, Type::Ptr(Type::U8.into()));
+            if let Some(owner_fld) = owner_fld {
+                self.thread_locals
+                    .insert(owner_fld, DotnetTypeRef::thread_local_owner().into());
+            }
+        }
+        call!(CallSite::new(None, getter_name, getter_sig, true), [])
+    }
     /// Returns true if assembly contains function named `name`
     #[must_use]
     pub fn contains_fn_named(&self, name: &str) -> bool {
@@ -666,12 +991,13 @@ impl Assembly {
     }
 
     #[must_use]
-    pub fn extern_fns(&self) -> &HashMap<(IString, FnSig), IString> {
+    pub fn extern_fns(&self) -> &HashMap<(IString, FnSig, bool), IString> {
         &self.extern_fns
     }
 
-    pub fn add_extern_fn(&mut self, name: IString, sig: FnSig, lib: IString) {
-        self.extern_fns.insert((name, sig), lib);
+    /// Adds a function imported from the native library `lib`. The signature of a variadic function contains only its fixed arguments.
+    pub fn add_extern_fn(&mut self, name: IString, sig: FnSig, lib: IString, is_variadic: bool) {
+        self.extern_fns.insert((name, sig, is_variadic), lib);
     }
     fn get_exported_fn(&self) -> HashMap<CallSite, Method> {
         let mut externs = HashMap::new();
@@ -685,6 +1011,18 @@ impl Assembly {
                 cctor.clone(),
             );
         }
+        // Functions called by methods of types(eg. exported functions) are accessible from outside.
+        for call in self
+            .types
+            .values()
+            .flat_map(TypeDef::methods)
+            .flat_map(Method::calls)
+            .filter(|call| call.class().is_none())
+        {
+            if let Some(method) = self.functions.get(&call) {
+                externs.insert(call, method.clone());
+            }
+        }
         externs
     }
     pub fn eliminate_dead_fn(&mut self) {
@@ -959,22 +1297,6 @@ fn link_static_initializers(a: Option<&Method>, b: Option<&Method>) -> Option<Me
 }
 type LocalDefList = Vec<(Option<IString>, Type)>;
 type ArgsDebugInfo = Vec<Option<IString>>;
-fn check_align_adjust<'tyctx>(locals: &rustc_index::IndexVec<Local, LocalDecl<'tyctx>>,
-tyctx: TyCtxt<'tyctx>,method_instance:&Instance<'tyctx>,)->Vec<Option<u64>>{
-    let mut adjusts:Vec<Option<u64>> = Vec::with_capacity(locals.len());
-    for (_, local) in locals.iter().enumerate() {
-        let ty = crate::utilis::monomorphize(method_instance, local.ty, tyctx);
-        let adjust = crate::utilis::requries_align_adjustement(ty,tyctx,);
-        if let Some(adjust) = adjust{
-            eprintln!(
-                "type {ty} requires algiement adjustements. Its algement should be {adjust:?}.",
-            );
-        }
-       
-        adjusts.push(adjust);
-    }
-    adjusts
-}
 /// Returns the list of all local variables within MIR of a function, and converts them to the internal type represenation `Type`
 fn locals_from_mir<'tyctx>(
     locals: &rustc_index::IndexVec<Local, LocalDecl<'tyctx>>,
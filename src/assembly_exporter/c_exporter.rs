@@ -68,6 +68,26 @@ impl CExporter {
         {
             return;
         }
+        // C has no exceptions, so `catch_unwind` uses `setjmp` instead of a typed catch block.
+        if name == "catch_unwind" {
+            writeln!(
+                self.method_defs,
+                "int32_t catch_unwind(void* A0,uint8_t* A1,void* A2);"
+            )
+            .unwrap();
+            writeln!(
+                self.encoded_asm,
+                "int32_t catch_unwind(void* A0,uint8_t* A1,void* A2){{\n\treturn rust_catch_unwind(A0,A1,A2);\n}}"
+            )
+            .unwrap();
+            return;
+        }
+        // Soft-float builtins are provided by libgcc.
+        if crate::builtin::soft_float::SOFT_FLOAT_BUILTINS.contains(&name.as_str())
+            || name.as_str() == crate::builtin::soft_float::F128_ROUND_PACK
+        {
+            return;
+        }
         let output = c_tpe(sig.output());
         let mut inputs: String = "(".into();
         let mut input_iter = sig
@@ -122,7 +142,7 @@ impl AssemblyExporter for CExporter {
 
         write!(
             headers,
-            "#include  <stdint.h>\n#include <stdbool.h>\n#include <stddef.h>\n#include <stdio.h>\n#include <stdlib.h>\n#include <mm_malloc.h>\n#include <sys/syscall.h>\n"
+            "#include  <stdint.h>\n#include <stdbool.h>\n#include <stddef.h>\n#include <stdio.h>\n#include <stdlib.h>\n#include <alloca.h>\n#include <setjmp.h>\n#include <math.h>\n#include <mm_malloc.h>\n#include <sys/syscall.h>\n"
         )
         .expect("Write error!");
         headers.write_all(include_bytes!("c_header.h")).unwrap();
@@ -198,6 +218,10 @@ impl AssemblyExporter for CExporter {
                     field_type = c_tpe(field_type)
                 ));
             }
+            // Makes sure the type is exactly as big as its Rust counterpart.
+            if let Some(size) = tpe.explict_size() {
+                fields.push_str(&format!("\tchar explicit_size[{size}];\n"));
+            }
         } else {
             for (field_name, field_type) in tpe.fields() {
                 if *field_type == Type::Void {
@@ -209,8 +233,11 @@ impl AssemblyExporter for CExporter {
                 ));
             }
         }
-        for method in tpe.methods() {
-            self.add_method_inner(method, Some(&name));
+        // Methods of managed classes(like `RustClosureState`) operate on objects, which don't exist in C. Only their fields are emitted.
+        if tpe.extends().is_none() {
+            for method in tpe.methods() {
+                self.add_method_inner(method, Some(&name));
+            }
         }
         if tpe.explicit_offsets().is_some() {
             writeln!(self.types, "typedef union {name} {name};").unwrap();
@@ -231,7 +258,13 @@ impl AssemblyExporter for CExporter {
         self.add_method_inner(method, None);
     }
 
-    fn add_extern_method(&mut self, _lib_path: &str, name: &str, sig: &crate::function_sig::FnSig) {
+    fn add_extern_method(
+        &mut self,
+        _lib_path: &str,
+        name: &str,
+        sig: &crate::function_sig::FnSig,
+        is_variadic: bool,
+    ) {
         if name == "puts"
             || name == "malloc"
             || name == "printf"
@@ -255,6 +288,12 @@ impl AssemblyExporter for CExporter {
         for (idx, input) in input_iter {
             inputs.push_str(&format!(",{input} A{idx} ", input = c_tpe(input)));
         }
+        if is_variadic {
+            if inputs.len() > 1 {
+                inputs.push(',');
+            }
+            inputs.push_str("...");
+        }
         inputs.push(')');
         writeln!(self.method_defs, "extern {output} {name} {inputs};").unwrap();
     }
@@ -299,10 +338,19 @@ impl AssemblyExporter for CExporter {
     fn add_global(&mut self, tpe: &crate::r#type::Type, name: &str) {
         writeln!(self.static_defs, "static {tpe} {name};", tpe = c_tpe(tpe)).unwrap();
     }
+    fn add_thread_local(&mut self, tpe: &crate::r#type::Type, name: &str) {
+        writeln!(
+            self.static_defs,
+            "static _Thread_local {tpe} {name};",
+            tpe = c_tpe(tpe)
+        )
+        .unwrap();
+    }
 }
 fn node_string(tree: &CILNode, method: &Method) -> String {
     match tree {
-        CILNode::LocAllocAligned { tpe, align }=>format!("(((uintptr_t)alloca(sizeof({tpe})) + {align})) + ({align} - 1)) & (-{align}))",tpe = c_tpe(tpe)),
+        CILNode::LocAlloc { size } => format!("((uintptr_t)alloca({size}))", size = node_string(size, method)),
+        CILNode::LocAllocAligned { tpe, align }=>format!("((((uintptr_t)alloca(sizeof({tpe}) + {align} - 1)) + ({align} - 1)) & ~((uintptr_t){align} - 1))",tpe = c_tpe(tpe)),
         CILNode::PointerToConstValue(_value) => {
             panic!("ERROR: const values must be allocated before CIL export phase.")
         }
@@ -484,6 +532,24 @@ fn node_string(tree: &CILNode, method: &Method) -> String {
             )
         }
         CILNode::RawOpsParrentless { .. } => todo!(),
+        // Vector constructors and reinterpret casts need to know the resulting vector type, so they can't be macros.
+        CILNode::Call { args, site }
+            if site
+                .class()
+                .is_some_and(|class| class.asm() == Some("System.Runtime.Intrinsics"))
+                && (site.name() == "Create" || site.name().starts_with("As")) =>
+        {
+            let output = c_tpe(site.signature().output());
+            let args: Vec<_> = args
+                .iter()
+                .map(|arg| node_string(arg, method).to_string())
+                .collect();
+            if site.name() == "Create" {
+                format!("(({output}){{{args}}})", args = args.join(","))
+            } else {
+                format!("(({output})({arg}))", arg = args[0])
+            }
+        }
         CILNode::Call { args, site } | CILNode::CallVirt { args, site } => {
             let name = site.name();
             let mut input_iter = args
@@ -511,6 +577,9 @@ fn node_string(tree: &CILNode, method: &Method) -> String {
         CILNode::LdcF64(value) => format!("{value}"),
         CILNode::LdcF32(value) => format!("{value}"),
         CILNode::LoadGlobalAllocPtr { .. } => todo!(),
+        CILNode::LoadThreadLocalPtr { .. } => unreachable!(
+            "Thread locals are resolved to calls to their getters before being exported!"
+        ),
         CILNode::ConvU8(inner) => format!("((uint8_t){inner})", inner = node_string(inner, method)),
         CILNode::ConvU16(inner) => {
             format!("((uint16_t){inner})", inner = node_string(inner, method))
@@ -609,6 +678,9 @@ fn node_string(tree: &CILNode, method: &Method) -> String {
         CILNode::CallI(_sig_ptr_args) => todo!(),
         CILNode::LDLen { arr } => todo!("arr:{arr:?}"),
         CILNode::LDElelemRef { arr, idx } => todo!("arr:{arr:?} idx:{idx:?}"),
+        CILNode::CheckedCast(val_tpe) => todo!("Can't cast managed objects in C. val_tpe:{val_tpe:?}"),
+        CILNode::NewArr { tpe, size } => todo!("Can't allocate managed arrays in C. tpe:{tpe:?} size:{size:?}"),
+        CILNode::LDElemAdress { tpe, arr, idx } => todo!("tpe:{tpe:?} arr:{arr:?} idx:{idx:?}"),
         CILNode::GetStackTop => todo!(),
         CILNode::InspectValue { val, inspect: _ } => node_string(val, method),
         CILNode::TransmutePtr { val, new_ptr } => format!(
@@ -818,6 +890,15 @@ fn tree_string(tree: &CILTree, method: &Method) -> String {
             format!("\t{ops};", ops = node_string(tree, method))
         }
         CILRoot::VoidRet => "return;".into(),
+        // Rust panics, raised by `_Unwind_RaiseException`.
+        CILRoot::Throw(CILNode::NewObj { site, args })
+            if site.class() == Some(&crate::r#type::DotnetTypeRef::rust_exception()) =>
+        {
+            format!(
+                "rust_unwind_raise((uintptr_t){payload});",
+                payload = node_string(&args[0], method)
+            )
+        }
         CILRoot::Throw(_) => "abort();".to_string(),
         CILRoot::ReThrow => todo!(),
         CILRoot::CallI { sig, fn_ptr, args } => todo!(
@@ -861,6 +942,8 @@ fn c_tpe(tpe: &Type) -> Cow<'static, str> {
         Type::U32 => "uint32_t".into(),
         Type::F64 => "float".into(),
         Type::F32 => "double".into(),
+        Type::F16 => "_Float16".into(),
+        Type::F128 => "__float128".into(),
         Type::I16 => "int16_t".into(),
         Type::U16 => "uint16_t".into(),
         Type::I8 => "int8_t".into(),
@@ -871,6 +954,15 @@ fn c_tpe(tpe: &Type) -> Cow<'static, str> {
                 match (asm, tref.name_path()) {
                     ("System.Runtime", "System.UInt128") => return c_tpe(&Type::U128),
                     ("System.Runtime", "System.Int128") => return c_tpe(&Type::I128),
+                    ("System.Runtime.Intrinsics", name)
+                        if name.starts_with("System.Runtime.Intrinsics.Vector") =>
+                    {
+                        let bits = name
+                            .trim_start_matches("System.Runtime.Intrinsics.Vector")
+                            .trim_end_matches("`1");
+                        return format!("Vector{bits}_{elem}", elem = c_tpe(&tref.generics()[0]))
+                            .into();
+                    }
                     _ => println!("Type {tref:?} is not supported in C"),
                 }
             }
@@ -2,6 +2,33 @@
 
 static char* exec_fname;
 
+// Unwinding
+
+// Rust panics are unwound using `setjmp` and `longjmp`. Cleanup blocks are not run, only `catch_unwind` is.
+static _Thread_local jmp_buf* rust_unwind_target = NULL;
+static _Thread_local uintptr_t rust_unwind_payload;
+static void rust_unwind_raise(uintptr_t payload){
+    if(rust_unwind_target == NULL){
+        fprintf(stderr,"A Rust panic was not caught.\n");
+        abort();
+    }
+    rust_unwind_payload = payload;
+    longjmp(*rust_unwind_target,1);
+}
+static int32_t rust_catch_unwind(void (*try_fn)(uint8_t*),uint8_t* data,void (*catch_fn)(uint8_t*,uint8_t*)){
+    jmp_buf target;
+    jmp_buf* prev = rust_unwind_target;
+    rust_unwind_target = &target;
+    if(setjmp(target) == 0){
+        try_fn(data);
+        rust_unwind_target = prev;
+        return 0;
+    }
+    rust_unwind_target = prev;
+    catch_fn(data,(uint8_t*)rust_unwind_payload);
+    return 1;
+}
+
 // Functions
 
 // 128 bit
@@ -63,8 +90,185 @@ static char* exec_fname;
 #define System_Numerics_BitOperationsTrailingZeroCount(arg) __builtin_ctz(arg)
 #define System_TypeGetTypeFromHandle(handle) handle
 #define System_ObjectGetHashCode(object) object
+//SIMD
+// Vectors use GCC vector extensions, which the C compiler lowers to scalar code on targets without SIMD support.
+#define SIMD_VECTORS(elem) \
+    typedef elem Vector64_##elem __attribute__((vector_size(8))); \
+    typedef elem Vector128_##elem __attribute__((vector_size(16))); \
+    typedef elem Vector256_##elem __attribute__((vector_size(32)));
+SIMD_VECTORS(int8_t) SIMD_VECTORS(uint8_t) SIMD_VECTORS(int16_t) SIMD_VECTORS(uint16_t)
+SIMD_VECTORS(int32_t) SIMD_VECTORS(uint32_t) SIMD_VECTORS(int64_t) SIMD_VECTORS(uint64_t)
+SIMD_VECTORS(ptrdiff_t) SIMD_VECTORS(uintptr_t) SIMD_VECTORS(float) SIMD_VECTORS(double)
+#define simd_lanes(v) (sizeof(v) / sizeof((v)[0]))
+#define simd_map(a, expr) ({typeof(a) _a = (a); for(size_t _i = 0; _i < simd_lanes(_a); _i++) _a[_i] = (expr); _a;})
+#define simd_map2(a, b, expr) ({typeof(a) _a = (a); typeof(b) _b = (b); for(size_t _i = 0; _i < simd_lanes(_a); _i++) _a[_i] = (expr); _a;})
+#define simd_add(a,b) ((a) + (b))
+#define simd_sub(a,b) ((a) - (b))
+#define simd_mul(a,b) ((a) * (b))
+#define simd_div(a,b) ((a) / (b))
+#define simd_and(a,b) ((a) & (b))
+#define simd_or(a,b) ((a) | (b))
+#define simd_xor(a,b) ((a) ^ (b))
+#define simd_not(a) (~(a))
+#define simd_neg(a) (-(a))
+#define simd_abs(a) simd_map(a, _a[_i] < 0 ? -_a[_i] : _a[_i])
+#define simd_sqrt(a) simd_map(a, sqrt(_a[_i]))
+#define simd_min(a,b) simd_map2(a, b, _a[_i] < _b[_i] ? _a[_i] : _b[_i])
+#define simd_max(a,b) simd_map2(a, b, _a[_i] > _b[_i] ? _a[_i] : _b[_i])
+// Comparisons return a mask, which is always reinterpreted by the caller.
+#define simd_eq(a,b) ((a) == (b))
+#define simd_lt(a,b) ((a) < (b))
+#define simd_le(a,b) ((a) <= (b))
+#define simd_gt(a,b) ((a) > (b))
+#define simd_ge(a,b) ((a) >= (b))
+#define simd_get(v,idx) ((v)[idx])
+#define simd_with(v,idx,val) ({typeof(v) _v = (v); _v[idx] = (val); _v;})
+#define simd_msb(v) ({typeof(v) _v = (v); uint32_t _bits = 0; for(size_t _i = 0; _i < simd_lanes(_v); _i++) _bits |= ((uint32_t)(_v[_i] < 0)) << _i; _bits;})
+// Selects bits of `a` where the mask is set, and bits of `b` elsewhere. Casts between vectors of the same size are bitcasts.
+#define simd_select(bits,mask,a,b) ((typeof(a))((((bits)(mask)) & ((bits)(a))) | (~((bits)(mask)) & ((bits)(b)))))
+#define System_Runtime_Intrinsics_Vector64Add simd_add
+#define System_Runtime_Intrinsics_Vector64Subtract simd_sub
+#define System_Runtime_Intrinsics_Vector64Multiply simd_mul
+#define System_Runtime_Intrinsics_Vector64Divide simd_div
+#define System_Runtime_Intrinsics_Vector64BitwiseAnd simd_and
+#define System_Runtime_Intrinsics_Vector64BitwiseOr simd_or
+#define System_Runtime_Intrinsics_Vector64Xor simd_xor
+#define System_Runtime_Intrinsics_Vector64OnesComplement simd_not
+#define System_Runtime_Intrinsics_Vector64Negate simd_neg
+#define System_Runtime_Intrinsics_Vector64Abs simd_abs
+#define System_Runtime_Intrinsics_Vector64Sqrt simd_sqrt
+#define System_Runtime_Intrinsics_Vector64Min simd_min
+#define System_Runtime_Intrinsics_Vector64Max simd_max
+#define System_Runtime_Intrinsics_Vector64Equals simd_eq
+#define System_Runtime_Intrinsics_Vector64LessThan simd_lt
+#define System_Runtime_Intrinsics_Vector64LessThanOrEqual simd_le
+#define System_Runtime_Intrinsics_Vector64GreaterThan simd_gt
+#define System_Runtime_Intrinsics_Vector64GreaterThanOrEqual simd_ge
+#define System_Runtime_Intrinsics_Vector64GetElement simd_get
+#define System_Runtime_Intrinsics_Vector64WithElement simd_with
+#define System_Runtime_Intrinsics_Vector64ExtractMostSignificantBits simd_msb
+#define System_Runtime_Intrinsics_Vector64ConditionalSelect(mask,a,b) simd_select(Vector64_int64_t,mask,a,b)
+#define System_Runtime_Intrinsics_Vector128Add simd_add
+#define System_Runtime_Intrinsics_Vector128Subtract simd_sub
+#define System_Runtime_Intrinsics_Vector128Multiply simd_mul
+#define System_Runtime_Intrinsics_Vector128Divide simd_div
+#define System_Runtime_Intrinsics_Vector128BitwiseAnd simd_and
+#define System_Runtime_Intrinsics_Vector128BitwiseOr simd_or
+#define System_Runtime_Intrinsics_Vector128Xor simd_xor
+#define System_Runtime_Intrinsics_Vector128OnesComplement simd_not
+#define System_Runtime_Intrinsics_Vector128Negate simd_neg
+#define System_Runtime_Intrinsics_Vector128Abs simd_abs
+#define System_Runtime_Intrinsics_Vector128Sqrt simd_sqrt
+#define System_Runtime_Intrinsics_Vector128Min simd_min
+#define System_Runtime_Intrinsics_Vector128Max simd_max
+#define System_Runtime_Intrinsics_Vector128Equals simd_eq
+#define System_Runtime_Intrinsics_Vector128LessThan simd_lt
+#define System_Runtime_Intrinsics_Vector128LessThanOrEqual simd_le
+#define System_Runtime_Intrinsics_Vector128GreaterThan simd_gt
+#define System_Runtime_Intrinsics_Vector128GreaterThanOrEqual simd_ge
+#define System_Runtime_Intrinsics_Vector128GetElement simd_get
+#define System_Runtime_Intrinsics_Vector128WithElement simd_with
+#define System_Runtime_Intrinsics_Vector128ExtractMostSignificantBits simd_msb
+#define System_Runtime_Intrinsics_Vector128ConditionalSelect(mask,a,b) simd_select(Vector128_int64_t,mask,a,b)
+#define System_Runtime_Intrinsics_Vector256Add simd_add
+#define System_Runtime_Intrinsics_Vector256Subtract simd_sub
+#define System_Runtime_Intrinsics_Vector256Multiply simd_mul
+#define System_Runtime_Intrinsics_Vector256Divide simd_div
+#define System_Runtime_Intrinsics_Vector256BitwiseAnd simd_and
+#define System_Runtime_Intrinsics_Vector256BitwiseOr simd_or
+#define System_Runtime_Intrinsics_Vector256Xor simd_xor
+#define System_Runtime_Intrinsics_Vector256OnesComplement simd_not
+#define System_Runtime_Intrinsics_Vector256Negate simd_neg
+#define System_Runtime_Intrinsics_Vector256Abs simd_abs
+#define System_Runtime_Intrinsics_Vector256Sqrt simd_sqrt
+#define System_Runtime_Intrinsics_Vector256Min simd_min
+#define System_Runtime_Intrinsics_Vector256Max simd_max
+#define System_Runtime_Intrinsics_Vector256Equals simd_eq
+#define System_Runtime_Intrinsics_Vector256LessThan simd_lt
+#define System_Runtime_Intrinsics_Vector256LessThanOrEqual simd_le
+#define System_Runtime_Intrinsics_Vector256GreaterThan simd_gt
+#define System_Runtime_Intrinsics_Vector256GreaterThanOrEqual simd_ge
+#define System_Runtime_Intrinsics_Vector256GetElement simd_get
+#define System_Runtime_Intrinsics_Vector256WithElement simd_with
+#define System_Runtime_Intrinsics_Vector256ExtractMostSignificantBits simd_msb
+#define System_Runtime_Intrinsics_Vector256ConditionalSelect(mask,a,b) simd_select(Vector256_int64_t,mask,a,b)
 //Math
-#define System_MathFSqrt(flot) sqrtf(flot)
+#define System_MathFSqrt(a) sqrtf(a)
+#define System_MathFSin(a) sinf(a)
+#define System_MathFCos(a) cosf(a)
+#define System_MathFPow(a,b) powf(a,b)
+#define System_MathFExp(a) expf(a)
+#define System_MathFLog(a) logf(a)
+#define System_MathFLog10(a) log10f(a)
+#define System_MathFLog2(a) log2f(a)
+#define System_MathFFusedMultiplyAdd(a,b,c) fmaf(a,b,c)
+#define System_MathFAbs(a) fabsf(a)
+#define System_MathFMin(a,b) fminf(a,b)
+#define System_MathFMax(a,b) fmaxf(a,b)
+#define System_MathFCopySign(a,b) copysignf(a,b)
+#define System_MathFFloor(a) floorf(a)
+#define System_MathFCeiling(a) ceilf(a)
+#define System_MathFTruncate(a) truncf(a)
+// `mode` is a `System.MidpointRounding`: 0 rounds half-way cases to even, 1 away from zero.
+#define System_MathFRound(a,mode) ((mode) ? roundf(a) : rintf(a))
+#define System_MathSqrt(a) sqrt(a)
+#define System_MathSin(a) sin(a)
+#define System_MathCos(a) cos(a)
+#define System_MathPow(a,b) pow(a,b)
+#define System_MathExp(a) exp(a)
+#define System_MathLog(a) log(a)
+#define System_MathLog10(a) log10(a)
+#define System_MathLog2(a) log2(a)
+#define System_MathFusedMultiplyAdd(a,b,c) fma(a,b,c)
+#define System_MathAbs(a) fabs(a)
+#define System_MathMin(a,b) fmin(a,b)
+#define System_MathMax(a,b) fmax(a,b)
+#define System_MathCopySign(a,b) copysign(a,b)
+#define System_MathFloor(a) floor(a)
+#define System_MathCeiling(a) ceil(a)
+#define System_MathTruncate(a) trunc(a)
+#define System_MathRound(a,mode) ((mode) ? round(a) : rint(a))
+#define System_SingleMinNumber(a,b) fminf(a,b)
+#define System_SingleMaxNumber(a,b) fmaxf(a,b)
+#define System_DoubleMinNumber(a,b) fmin(a,b)
+#define System_DoubleMaxNumber(a,b) fmax(a,b)
+// f16
+#define System_Halfop_Addition(a,b) ((a) + (b))
+#define System_Halfop_Subtraction(a,b) ((a) - (b))
+#define System_Halfop_Multiply(a,b) ((a) * (b))
+#define System_Halfop_Division(a,b) ((a) / (b))
+#define System_Halfop_Modulus(a,b) ((_Float16)fmodf((a),(b)))
+#define System_Halfop_UnaryNegation(a) (-(a))
+#define System_Halfop_Equality(a,b) ((a) == (b))
+#define System_Halfop_LessThan(a,b) ((a) < (b))
+#define System_Halfop_LessThanOrEqual(a,b) ((a) <= (b))
+#define System_Halfop_GreaterThan(a,b) ((a) > (b))
+#define System_Halfop_GreaterThanOrEqual(a,b) ((a) >= (b))
+// Conversions between `_Float16` and other floats are implicit.
+#define System_Halfop_Explicit(a) (a)
+// f128, implemented by libgcc
+__float128 __extenddftf2(double);
+double __trunctfdf2(__float128);
+float __trunctfsf2(__float128);
+_Float16 __trunctfhf2(__float128);
+__float128 __floatditf(int64_t);
+__float128 __floatunditf(uint64_t);
+int64_t __fixtfdi(__float128);
+uint64_t __fixunstfdi(__float128);
+__float128 __floattitf(__int128);
+__float128 __floatuntitf(unsigned __int128);
+__int128 __fixtfti(__float128);
+unsigned __int128 __fixunstfti(__float128);
+__float128 __addtf3(__float128, __float128);
+__float128 __subtf3(__float128, __float128);
+__float128 __multf3(__float128, __float128);
+__float128 __divtf3(__float128, __float128);
+__float128 __negtf2(__float128);
+int32_t __eqtf2(__float128, __float128);
+int32_t __lttf2(__float128, __float128);
+int32_t __letf2(__float128, __float128);
+int32_t __gttf2(__float128, __float128);
+int32_t __getf2(__float128, __float128);
 //Types
 
 typedef char* System_String;
@@ -11,6 +11,7 @@ use crate::{
     r#type::TypeDef,
 };
 use lazy_static::lazy_static;
+use std::borrow::Cow;
 pub enum IlasmFlavour{
     Clasic,
     Modern,
@@ -48,6 +49,14 @@ impl AssemblyExporter for ILASMExporter {
         )
         .expect("Could not write global!");
     }
+    fn add_thread_local(&mut self, tpe: &Type, name: &str) {
+        writeln!(
+            self.methods,
+            ".field static {tpe} '{name}'\n.custom instance void [System.Runtime]System.ThreadStaticAttribute::.ctor() = (01 00 00 00)",
+            tpe = non_void_type_cil(tpe)
+        )
+        .expect("Could not write thread local!");
+    }
     fn init(asm_name: &str) -> Self {
         let mut encoded_asm = Vec::with_capacity(0x1_00);
         let mut methods = Vec::with_capacity(0x1_00);
@@ -143,13 +152,19 @@ impl AssemblyExporter for ILASMExporter {
         Ok(())
     }
 
-    fn add_extern_method(&mut self, lib_path: &str, name: &str, sig: &crate::function_sig::FnSig) {
+    fn add_extern_method(
+        &mut self,
+        lib_path: &str,
+        name: &str,
+        sig: &crate::function_sig::FnSig,
+        is_variadic: bool,
+    ) {
         // /lib64/libc.so.6
         let output = type_cil(sig.output());
-
+        let vararg = if is_variadic { "vararg " } else { "" };
         writeln!(
             self.methods,
-            ".method private hidebysig static pinvokeimpl(\"{lib_path}\" cdecl) {output} '{name}' ("
+            ".method private hidebysig static pinvokeimpl(\"{lib_path}\" cdecl) {vararg}{output} '{name}' ("
         )
         .unwrap();
         let mut input_iter = sig.inputs().iter();
@@ -178,10 +193,15 @@ fn type_def_cli(
         tpe.gargc() == 0,
         "Generic typedefs not supported yet. tpe:{tpe:?}"
     );
-    let extends = if let Some(extended) = tpe.extends() {
-        todo!("Can't handle inheretence yet. Typedef inherits from {extended:?}!");
+    let extends: Cow<str> = if let Some(extended) = tpe.extends() {
+        // Managed classes, eg. the ones used by the codegen itself.
+        let asm = extended
+            .asm()
+            .map(|asm| format!("[{asm}]"))
+            .unwrap_or_default();
+        format!("{asm}'{name}'", name = extended.name_path()).into()
     } else {
-        "[System.Runtime]System.ValueType"
+        "[System.Runtime]System.ValueType".into()
     };
     let access = if let AccessModifer::Public = tpe.access_modifier() {
         "public"
@@ -233,8 +253,9 @@ fn type_def_cli(
     Ok(())
 }
 fn method_cil(w: &mut impl Write, method: &Method) -> std::io::Result<()> {
+    // Private methods must still be callable from other types within the assembly(eg. exported wrappers), so they are `assembly` and not `private`.
     let access = if let AccessModifer::Private = method.access() {
-        "private"
+        "assembly"
     } else {
         "public"
     };
@@ -245,9 +266,14 @@ fn method_cil(w: &mut impl Write, method: &Method) -> std::io::Result<()> {
     };
     let output = type_cil(method.sig().output());
     let name = method.name();
+    let special = if name == ".ctor" {
+        "specialname rtspecialname "
+    } else {
+        ""
+    };
     write!(
         w,
-        ".method {access} hidebysig {static_inst} {output} '{name}'("
+        ".method {access} hidebysig {special}{static_inst} {output} '{name}'("
     )?;
     let mut input_iter = method.explicit_inputs().iter();
     if method.arg_names().is_empty() || method.arg_names().len() != method.explicit_inputs().len() {
@@ -17,6 +17,7 @@ pub fn op_cli(op: &crate::cil::CILOp, method: &Method) -> Cow<'static, str> {
         CILOp::Leave(target) => format!("leave bb_{target}_0").into(),
         CILOp::BeginTry => ".try{".into(),
         CILOp::BeginCatch=>"}catch [System.Runtime]System.Exception{".into(),
+        CILOp::BeginTypedCatch(tpe)=>format!("}}catch {tpe}{{",tpe = dotnet_type_ref_cli(tpe).trim_start_matches(tpe.tpe_prefix()).trim_start()).into(),
         CILOp::EndTry => "}".into(),
         CILOp::CustomLabel(label)=>format!("{label}:").into(),
         CILOp::EHClause { start, end, hstart }=>
@@ -45,14 +46,19 @@ pub fn op_cli(op: &crate::cil::CILOp, method: &Method) -> Cow<'static, str> {
                 if let Some(firts_arg) = inputs_iter.next() {
                     input_string.push_str(&non_void_type_cil(firts_arg));
                 }
-                for arg in inputs_iter {
+                for (idx, arg) in inputs_iter.enumerate() {
                     input_string.push(',');
+                    // Vararg call sites need a sentinel between the fixed and the variadic arguments.
+                    if call_site.fixed_args() == Some(idx + 1) {
+                        input_string.push_str("...,");
+                    }
                     input_string.push_str(&non_void_type_cil(arg));
                 }
-                let prefix = if call_site.is_static() {
-                    ""
-                } else {
-                    "instance"
+                let prefix = match (call_site.is_static(), call_site.fixed_args().is_some()) {
+                    (true, false) => "",
+                    (true, true) => "vararg",
+                    (false, false) => "instance",
+                    (false, true) => "instance vararg",
                 };
                 let generics = if call_site.generics().is_empty(){
                     String::new()
@@ -557,6 +563,9 @@ pub fn op_cli(op: &crate::cil::CILOp, method: &Method) -> Cow<'static, str> {
         CILOp::LDTypeToken(tpe)=>format!("ldtoken {tpe}",tpe = non_void_type_cil(tpe)).into(),
         CILOp::LDLen=>"ldlen".into(),
         CILOp::LDElelemRef => "ldelem.ref".into(),
+        CILOp::CastClass(tpe) => format!("castclass {tpe}", tpe = dotnet_type_ref_cli(tpe)).into(),
+        CILOp::NewArr(tpe) => format!("newarr {tpe}", tpe = non_void_type_cil(tpe)).into(),
+        CILOp::LDElemAdress(tpe) => format!("ldelema {tpe}", tpe = non_void_type_cil(tpe)).into(),
     }
 }
 pub fn non_void_type_cil(tpe: &Type) -> Cow<'static, str> {
@@ -587,17 +596,18 @@ pub fn type_cil(tpe: &Type) -> Cow<'static, str> {
         Type::Void => "void".into(),
         Type::I8 => "int8".into(),
         Type::U8 => "uint8".into(),
-        Type::F16 => "valuetype [System.Runtime]System.Numerics.Half".into(),
+        Type::F16 => "valuetype [System.Runtime]System.Half".into(),
         Type::I16 => "int16".into(),
         Type::U16 => "uint16".into(),
         Type::F32 => "float32".into(),
         Type::I32 => "int32".into(),
         Type::U32 => "uint32".into(),
         Type::F64 => "float64".into(),
+        Type::F128 => dotnet_type_ref_cli(&DotnetTypeRef::uint_128()).into(),
         Type::I64 => "int64".into(),
         Type::U64 => "uint64".into(),
-        Type::I128 => "valuetype [System.Runtime]System.Int128".into(),
-        Type::U128 => "valuetype [System.Runtime]System.UInt128".into(),
+        Type::I128 => dotnet_type_ref_cli(&DotnetTypeRef::int_128()).into(),
+        Type::U128 => dotnet_type_ref_cli(&DotnetTypeRef::uint_128()).into(),
         Type::ISize => "native int".into(),
         Type::USize => "native uint".into(),
         Type::Ptr(inner) => format!("{inner}*", inner = type_cil(inner)).into(),
@@ -659,3 +669,33 @@ fn generics_str(generics: &[Type]) -> Cow<'static, str> {
         format!("<{garg_string}>").into()
     }
 }
+#[test]
+fn vararg_call() {
+    use crate::{
+        access_modifier::AccessModifer,
+        cil::{CILOp, CallSite},
+        function_sig::FnSig,
+        method::MethodType,
+    };
+    let mut printf = CallSite::new(
+        None,
+        "printf".into(),
+        FnSig::new(&[Type::Ptr(Type::I8.into()), Type::I32, Type::U64], &Type::I32),
+        true,
+    );
+    printf.set_vararg(1);
+    let caller = Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[], &Type::Void),
+        "caller",
+        vec![],
+        vec![],
+    );
+    let op = op_cli(&CILOp::Call(printf.into()), &caller);
+    // The fixed arguments must be separated from the variadic ones by a sentinel.
+    assert_eq!(
+        op.trim(),
+        "call vararg int32 RustModule::'printf'(int8*,...,int32,uint64)"
+    );
+}
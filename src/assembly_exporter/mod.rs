@@ -23,7 +23,8 @@ pub trait AssemblyExporter: Sized {
     fn add_type(&mut self, tpe: &TypeDef);
     /// Adds method to assembly.
     fn add_method(&mut self, method: &Method);
-    fn add_extern_method(&mut self, lib_path: &str, name: &str, sig: &FnSig);
+    /// Adds a method imported from the native library `lib_path`. If the method is variadic, `sig` contains only its fixed arguments.
+    fn add_extern_method(&mut self, lib_path: &str, name: &str, sig: &FnSig, is_variadic: bool);
     //fn extern_asm(&mut self,asm:&str);
     /// Finishes exporting the assembly.
     fn finalize(self, final_path: &Path, is_dll: bool) -> Result<(), AssemblyExportError>;
@@ -31,6 +32,8 @@ pub trait AssemblyExporter: Sized {
     fn add_extern_ref(&mut self, asm_name: &str, info: &crate::assembly::AssemblyExternRef);
     /// Adds a global field
     fn add_global(&mut self, tpe: &Type, name: &str);
+    /// Adds a thread-local global field
+    fn add_thread_local(&mut self, tpe: &Type, name: &str);
     /// Handles the whole assembly export process all at once.
     fn export_assembly(
         asm: &Assembly,
@@ -57,12 +60,15 @@ pub trait AssemblyExporter: Sized {
                 asm_exporter.add_method(&method);
             }
         }
-        for ((name, sig), lib) in asm.extern_fns() {
-            asm_exporter.add_extern_method(lib, name, sig);
+        for ((name, sig, is_variadic), lib) in asm.extern_fns() {
+            asm_exporter.add_extern_method(lib, name, sig, *is_variadic);
         }
         for global in asm.globals() {
             asm_exporter.add_global(global.1, global.0);
         }
+        for thread_local in asm.thread_locals() {
+            asm_exporter.add_thread_local(thread_local.1, thread_local.0);
+        }
         /*
         crate::libc::insert_libc(&mut asm_exporter);
         if let Some(entrypoint) = asm.entrypoint() {
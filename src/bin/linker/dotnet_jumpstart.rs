@@ -55,5 +55,6 @@ fn main(){{
     let args:Vec<String> = std::env::args().collect();
     let args = &args[1..];
     println!("std args:{{args:?}}");
-    std::process::Command::new("dotnet").arg(dll_path).args(args).status().expect("Could not start the .NET runtime.");
+    let status = std::process::Command::new("dotnet").arg(dll_path).args(args).status().expect("Could not start the .NET runtime.");
+    std::process::exit(status.code().unwrap_or(1));
 }}
\ No newline at end of file
@@ -109,6 +109,38 @@ fn patch_missing_method(call_site: &cil::CallSite) -> method::Method {
     );
     method
 }
+/// Replaces `_Unwind_RaiseException` with throwing a `RustException`, which carries the pointer to the exception object.
+fn override_unwind_raise(patched: &mut HashMap<CallSite, Method>, call: &CallSite) {
+    let rust_exception = DotnetTypeRef::rust_exception();
+    patched.insert(
+        call.clone(),
+        Method::new(
+            access_modifier::AccessModifer::Private,
+            MethodType::Static,
+            call.signature().clone(),
+            "_Unwind_RaiseException",
+            vec![],
+            vec![BasicBlock::new(
+                vec![CILRoot::Throw(CILNode::NewObj {
+                    site: CallSite::boxed(
+                        Some(rust_exception.clone()),
+                        ".ctor".into(),
+                        FnSig::new(&[rust_exception.into(), Type::USize], &Type::Void),
+                        false,
+                    ),
+                    args: [CILNode::TransmutePtr {
+                        val: Box::new(CILNode::LDArg(0)),
+                        new_ptr: Box::new(Type::USize),
+                    }]
+                    .into(),
+                })
+                .into()],
+                0,
+                None,
+            )],
+        ),
+    );
+}
 /// Replaces `malloc` with a direct call to `AllocHGlobal`
 fn override_malloc(patched: &mut HashMap<CallSite, Method>, call: &CallSite) {
     patched.insert(
@@ -218,6 +250,10 @@ fn autopatch(asm: &mut Assembly, native_pastrough: &NativePastroughInfo) {
             override_realloc(&mut patched, call);
             continue;
         }
+        if name == "_Unwind_RaiseException" {
+            override_unwind_raise(&mut patched, call);
+            continue;
+        }
         //#[cfg(not(target_os = "linux"))]
         if rustc_codegen_clr::native_pastrough::LIBC_FNS
             .iter()
@@ -225,16 +261,18 @@ fn autopatch(asm: &mut Assembly, native_pastrough: &NativePastroughInfo) {
         {
             externs.push((
                 call.name().into(),
-                call.signature().to_owned(),
+                call.fixed_signature(),
                 get_libc().into(),
+                call.fixed_args().is_some(),
             ));
             continue;
         }
         if let Some(lib) = native_pastrough.get(name) {
             externs.push((
                 call.name().into(),
-                call.signature().to_owned(),
+                call.fixed_signature(),
                 lib.as_ref().clone(),
+                call.fixed_args().is_some(),
             ));
             continue;
         }
@@ -244,7 +282,7 @@ fn autopatch(asm: &mut Assembly, native_pastrough: &NativePastroughInfo) {
     }
     externs
         .into_iter()
-        .for_each(|(name, sig, lib)| asm.add_extern_fn(name, sig, lib));
+        .for_each(|(name, sig, lib, is_variadic)| asm.add_extern_fn(name, sig, lib, is_variadic));
     patched
         .values()
         .for_each(|method| asm.add_method(method.clone()));
@@ -455,12 +493,21 @@ fn main() {
         .expect("No output file!")];
     // Configs
     let aot_compile_mode = aot_compile_mode(args);
+    // Mono lacks `System.Int128` and `System.UInt128`, so any assembly using them would fail to load.
+    assert!(
+        matches!(aot_compile_mode, AOTCompileMode::NoAOT) || *config::EMULATE_INT128,
+        "ERROR: Mono AOT requested, but 128 bit integers are not emulated. Rebuild with `EMULATE_INT128=1`."
+    );
     let cargo_support = args.iter().any(|arg| arg.contains("--cargo-support"));
 
     // Load assemblies from files
 
     let (mut final_assembly, linkables) =
         load::load_assemblies(to_link.as_slice(), ar_to_link.as_slice());
+    // Must run before the patches, which split the static constructor into multiple blocks.
+    if *config::VERIFY_LAYOUT {
+        rustc_codegen_clr::verify::add_layout_verification(&mut final_assembly);
+    }
     // Aplly certain fixes/workarounds to the final assembly
     patch::patch_all(&mut final_assembly);
     let mut native_pastrough = NativePastroughInfo::new();
@@ -37,6 +37,9 @@ pub fn eq_unchecked(ty_a: Ty<'_>, operand_a: CILNode, operand_b: CILNode) -> CIL
             ),
             _ => eq!(operand_a, operand_b),
         },
+        TyKind::Float(float) if super::float::is_non_native(*float) => {
+            super::float::cmp(*float, "op_Equality", operand_a, operand_b)
+        }
         TyKind::Bool | TyKind::Char | TyKind::Float(_) | TyKind::RawPtr(_, _) => {
             eq!(operand_a, operand_b)
         }
@@ -70,6 +73,9 @@ pub fn lt_unchecked(ty_a: Ty<'_>, operand_a: CILNode, operand_b: CILNode) -> CIL
             ),
             _ => lt!(operand_a, operand_b),
         },
+        TyKind::Float(float) if super::float::is_non_native(*float) => {
+            super::float::cmp(*float, "op_LessThan", operand_a, operand_b)
+        }
         // TODO: are chars considered signed or unsigned?
         TyKind::Bool | TyKind::Char | TyKind::Float(_) => lt!(operand_a, operand_b),
         TyKind::RawPtr(_, _) => lt_un!(operand_a, operand_b),
@@ -102,6 +108,9 @@ pub fn gt_unchecked(ty_a: Ty<'_>, operand_a: CILNode, operand_b: CILNode) -> CIL
             ),
             _ => gt!(operand_a, operand_b),
         },
+        TyKind::Float(float) if super::float::is_non_native(*float) => {
+            super::float::cmp(*float, "op_GreaterThan", operand_a, operand_b)
+        }
         // TODO: are chars considered signed or unsigned?
         TyKind::Bool | TyKind::Char | TyKind::Float(_) => gt!(operand_a, operand_b),
         TyKind::RawPtr(_, _) => gt_un!(operand_a, operand_b),
@@ -0,0 +1,67 @@
+//! Operations on floats with no native .NET support: `f16`(implemented using `System.Half`), and `f128`(implemented using soft-float builtins).
+use rustc_middle::ty::FloatTy;
+
+use crate::builtin::soft_float::soft_float_builtin;
+use crate::cil::CallSite;
+use crate::cil_tree::cil_node::CILNode;
+use crate::function_sig::FnSig;
+use crate::r#type::{DotnetTypeRef, Type};
+use crate::{call, eq, gt, ldc_i32, lt};
+/// Checks if operations on `float` can't be preformed using CIL instructions.
+pub fn is_non_native(float: FloatTy) -> bool {
+    matches!(float, FloatTy::F16 | FloatTy::F128)
+}
+/// Calls the operator `op` of `System.Half`.
+fn half_op(op: &str, inputs: &[Type], output: &Type, args: Vec<CILNode>) -> CILNode {
+    call!(
+        CallSite::new_extern(
+            DotnetTypeRef::half(),
+            op.into(),
+            FnSig::new(inputs, output),
+            true
+        ),
+        args
+    )
+}
+/// Preforms the arithmetic operation `op`(named after the matching .NET operator, e.g. `op_Addition`) on two non-native floats.
+pub fn arith(float: FloatTy, op: &str, a: CILNode, b: CILNode) -> CILNode {
+    match float {
+        FloatTy::F16 => half_op(op, &[Type::F16, Type::F16], &Type::F16, vec![a, b]),
+        FloatTy::F128 => match op {
+            "op_Addition" => call!(soft_float_builtin("__addtf3"), [a, b]),
+            "op_Subtraction" => call!(soft_float_builtin("__subtf3"), [a, b]),
+            "op_Multiply" => call!(soft_float_builtin("__multf3"), [a, b]),
+            "op_Division" => call!(soft_float_builtin("__divtf3"), [a, b]),
+            "op_Modulus" => panic!("The remainder of f128 division is not supported yet!"),
+            _ => unreachable!("{op} is not an arithmetic float operation!"),
+        },
+        _ => panic!("{float:?} is supported natively!"),
+    }
+}
+/// Compares two non-native floats, using the comparison `op`(named after the matching .NET operator, e.g. `op_LessThan`).
+pub fn cmp(float: FloatTy, op: &str, a: CILNode, b: CILNode) -> CILNode {
+    match float {
+        FloatTy::F16 => half_op(op, &[Type::F16, Type::F16], &Type::Bool, vec![a, b]),
+        FloatTy::F128 => match op {
+            "op_Equality" => eq!(call!(soft_float_builtin("__eqtf2"), [a, b]), ldc_i32!(0)),
+            "op_LessThan" => lt!(call!(soft_float_builtin("__lttf2"), [a, b]), ldc_i32!(0)),
+            "op_LessThanOrEqual" => {
+                lt!(call!(soft_float_builtin("__letf2"), [a, b]), ldc_i32!(1))
+            }
+            "op_GreaterThan" => gt!(call!(soft_float_builtin("__gttf2"), [a, b]), ldc_i32!(0)),
+            "op_GreaterThanOrEqual" => {
+                gt!(call!(soft_float_builtin("__getf2"), [a, b]), ldc_i32!(-1))
+            }
+            _ => unreachable!("{op} is not a float comparison!"),
+        },
+        _ => panic!("{float:?} is supported natively!"),
+    }
+}
+/// Negates a non-native float.
+pub fn neg(float: FloatTy, a: CILNode) -> CILNode {
+    match float {
+        FloatTy::F16 => half_op("op_UnaryNegation", &[Type::F16], &Type::F16, vec![a]),
+        FloatTy::F128 => call!(soft_float_builtin("__negtf2"), [a]),
+        _ => panic!("{float:?} is supported natively!"),
+    }
+}
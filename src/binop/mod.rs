@@ -11,6 +11,7 @@ use crate::r#type::{DotnetTypeRef, TyCache, Type};
 pub mod bitop;
 pub mod checked;
 pub mod cmp;
+pub mod float;
 pub mod shift;
 use crate::{
     call, conv_isize, conv_u16, conv_u32, conv_u64, conv_u8, div, eq, gt_un, ldc_i32, lt_un, rem,
@@ -81,11 +82,17 @@ pub(crate) fn binop<'tyctx>(
         BinOp::Div => div_unchecked(ty_a, ty_b, tycache, &method_instance, tyctx, ops_a, ops_b),
 
         BinOp::Ge => match ty_a.kind() {
+            TyKind::Float(float) if float::is_non_native(*float) => {
+                float::cmp(*float, "op_GreaterThanOrEqual", ops_a, ops_b)
+            }
             // Unordered, to handle NaNs propely
             TyKind::Float(_) => eq!(lt_un!(ops_a, ops_b), ldc_i32!(0)),
             _ => eq!(lt_unchecked(ty_a, ops_a, ops_b), ldc_i32!(0)),
         },
         BinOp::Le => match ty_a.kind() {
+            TyKind::Float(float) if float::is_non_native(*float) => {
+                float::cmp(*float, "op_LessThanOrEqual", ops_a, ops_b)
+            }
             // Unordered, to handle NaNs propely
             TyKind::Float(_) => eq!(gt_un!(ops_a, ops_b), ldc_i32!(0)),
             _ => eq!(gt_unchecked(ty_a, ops_a, ops_b), ldc_i32!(0)),
@@ -183,6 +190,9 @@ pub fn add_unchecked<'tyctx>(
                 }
             }
         }
+        TyKind::Float(float) if float::is_non_native(*float) => {
+            float::arith(*float, "op_Addition", ops_a, ops_b)
+        }
         TyKind::Float(_) => ops_a + ops_b,
         _ => todo!("can't add numbers of types {ty_a} and {ty_b}"),
     }
@@ -232,6 +242,9 @@ fn sub_unchecked<'tyctx>(
                 sub!(ops_a, ops_b)
             }
         }
+        TyKind::Float(float) if float::is_non_native(*float) => {
+            float::arith(*float, "op_Subtraction", ops_a, ops_b)
+        }
         TyKind::Float(_) => sub!(ops_a, ops_b),
         _ => todo!("can't sub numbers of types {ty_a} and {ty_b}"),
     }
@@ -273,6 +286,9 @@ fn rem_unchecked<'tyctx>(
                 [ops_a, ops_b]
             )
         }
+        TyKind::Float(float) if float::is_non_native(*float) => {
+            float::arith(*float, "op_Modulus", ops_a, ops_b)
+        }
         TyKind::Int(_) | TyKind::Char | TyKind::Float(_) => rem!(ops_a, ops_b),
         TyKind::Uint(_) => rem_un!(ops_a, ops_b),
 
@@ -316,6 +332,9 @@ fn mul_unchecked<'tyctx>(
                 [operand_a, operand_b]
             )
         }
+        TyKind::Float(float) if float::is_non_native(*float) => {
+            float::arith(*float, "op_Multiply", operand_a, operand_b)
+        }
         _ => operand_a * operand_b,
     }
 }
@@ -356,6 +375,9 @@ fn div_unchecked<'tyctx>(
             )
         }
         TyKind::Uint(_) => CILNode::DivUn(operand_a.into(), operand_b.into()),
+        TyKind::Float(float) if float::is_non_native(*float) => {
+            float::arith(*float, "op_Division", operand_a, operand_b)
+        }
         TyKind::Int(_) | TyKind::Char | TyKind::Float(_) => div!(operand_a, operand_b),
         _ => todo!(),
     }
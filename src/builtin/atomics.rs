@@ -0,0 +1,414 @@
+//! Atomic operations, implemented on top of `System.Threading.Interlocked`.
+//! `Interlocked` operations are full fences, so they satisfy every ordering Rust may request.
+use crate::{
+    access_modifier::AccessModifer,
+    assembly::Assembly,
+    basic_block::BasicBlock,
+    cil::CallSite,
+    cil_tree::{cil_node::CILNode, cil_root::CILRoot},
+    conv_isize, conv_usize, eq,
+    function_sig::FnSig,
+    ldc_i32, ldc_u32,
+    method::{Method, MethodType},
+    r#type::{DotnetTypeRef, Type},
+};
+use crate::{call, IString};
+/// Integer types atomic builtins are generated for. Other types(pointers, bools) use the builtins of the integer type with the same size.
+const ATOMIC_TYPES: [Type; 10] = [
+    Type::U8,
+    Type::I8,
+    Type::U16,
+    Type::I16,
+    Type::U32,
+    Type::I32,
+    Type::U64,
+    Type::I64,
+    Type::USize,
+    Type::ISize,
+];
+/// Read-modify-write operations implemented using a compare-exchange loop.
+const RMW_OPS: [&str; 10] = [
+    "xadd", "xsub", "and", "or", "xor", "nand", "max", "min", "umax", "umin",
+];
+/// Returns the integer type atomic operations on `tpe` should use.
+pub(crate) fn atomic_type(tpe: &Type) -> Type {
+    match tpe {
+        Type::Ptr(_) | Type::DelegatePtr(_) => Type::USize,
+        Type::Bool => Type::U8,
+        _ => tpe.clone(),
+    }
+}
+/// Name of the builtin implementing the atomic operation `op` on values of type `tpe`.
+pub(crate) fn atomic_builtin_name(op: &str, tpe: &Type) -> IString {
+    format!("atomic_{op}_{}", crate::r#type::mangle(&atomic_type(tpe))).into()
+}
+/// Signature of the builtin implementing the atomic operation `op` on values of type `tpe`.
+pub(crate) fn atomic_builtin_sig(op: &str, tpe: &Type) -> FnSig {
+    let tpe = atomic_type(tpe);
+    if op == "cxchg" {
+        FnSig::new(
+            &[Type::Ptr(tpe.clone().into()), tpe.clone(), tpe.clone()],
+            &tpe,
+        )
+    } else {
+        FnSig::new(&[Type::Ptr(tpe.clone().into()), tpe.clone()], &tpe)
+    }
+}
+fn atomic_builtin(op: &str, tpe: &Type) -> CallSite {
+    CallSite::builtin(
+        atomic_builtin_name(op, tpe),
+        atomic_builtin_sig(op, tpe),
+        true,
+    )
+}
+/// The type `Interlocked` operates on for `tpe`. Unsigned types are passed as signed ones, since those overloads are available on all .NET versions.
+fn interlocked_type(tpe: &Type) -> Type {
+    match tpe {
+        Type::U32 => Type::I32,
+        Type::U64 => Type::I64,
+        Type::USize => Type::ISize,
+        _ => tpe.clone(),
+    }
+}
+fn builtin_method(
+    op: &str,
+    tpe: &Type,
+    locals: Vec<(Option<IString>, Type)>,
+    blocks: Vec<BasicBlock>,
+) -> Method {
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        atomic_builtin_sig(op, tpe),
+        &atomic_builtin_name(op, tpe),
+        locals,
+        blocks,
+    )
+}
+/// `atomic_cxchg_T(ptr, expected, new) -> T`: returns the old value.
+fn cmpxchg(tpe: &Type) -> Method {
+    match tpe {
+        Type::U8 | Type::I8 | Type::U16 | Type::I16 => cmpxchg_subword(tpe),
+        _ => {
+            let itpe = interlocked_type(tpe);
+            let compare_exchange = CallSite::new(
+                Some(DotnetTypeRef::interlocked()),
+                "CompareExchange".into(),
+                FnSig::new(
+                    &[
+                        Type::ManagedReference(itpe.clone().into()),
+                        itpe.clone(),
+                        itpe.clone(),
+                    ],
+                    &itpe,
+                ),
+                true,
+            );
+            builtin_method(
+                "cxchg",
+                tpe,
+                vec![],
+                vec![BasicBlock::new(
+                    vec![CILRoot::Ret {
+                        tree: call!(
+                            compare_exchange,
+                            [CILNode::LDArg(0), CILNode::LDArg(2), CILNode::LDArg(1)]
+                        ),
+                    }
+                    .into()],
+                    0,
+                    None,
+                )],
+            )
+        }
+    }
+}
+/// `Interlocked` can't operate on 8 and 16 bit values, so compare-exchange is emulated by exchanging the aligned 32 bit word containing the value.
+fn cmpxchg_subword(tpe: &Type) -> Method {
+    let low_mask = match tpe {
+        Type::U8 | Type::I8 => 0xFF,
+        _ => 0xFFFF,
+    };
+    let compare_exchange = CallSite::new(
+        Some(DotnetTypeRef::interlocked()),
+        "CompareExchange".into(),
+        FnSig::new(
+            &[
+                Type::ManagedReference(Type::I32.into()),
+                Type::I32,
+                Type::I32,
+            ],
+            &Type::I32,
+        ),
+        true,
+    );
+    let addr = CILNode::TransmutePtr {
+        val: Box::new(CILNode::LDArg(0)),
+        new_ptr: Box::new(Type::USize),
+    };
+    let aligned = CILNode::TransmutePtr {
+        val: Box::new(CILNode::And(
+            addr.clone().into(),
+            conv_isize!(ldc_i32!(-4)).into(),
+        )),
+        new_ptr: Box::new(Type::Ptr(Type::U32.into())),
+    };
+    // Rust only supports little endian .NET targets, so the lowest address holds the lowest bits.
+    let shift = CILNode::ConvI32(
+        (CILNode::And(addr.into(), conv_usize!(ldc_u32!(3)).into()) * conv_usize!(ldc_u32!(8)))
+            .into(),
+    );
+    let old_bits = CILNode::And(
+        CILNode::ShrUn(CILNode::LDLoc(2).into(), CILNode::LDLoc(1).into()).into(),
+        ldc_u32!(low_mask).into(),
+    );
+    let expected_bits = CILNode::And(CILNode::LDArg(1).into(), ldc_u32!(low_mask).into());
+    let new_bits = CILNode::Shl(
+        CILNode::And(CILNode::LDArg(2).into(), ldc_u32!(low_mask).into()).into(),
+        CILNode::LDLoc(1).into(),
+    );
+    let other_bits = CILNode::And(
+        CILNode::LDLoc(2).into(),
+        CILNode::Not(CILNode::Shl(ldc_u32!(low_mask).into(), CILNode::LDLoc(1).into()).into())
+            .into(),
+    );
+    let ret_old = CILRoot::Ret {
+        tree: crate::casts::int_to_int(Type::U32, tpe, CILNode::LDLoc(3)),
+    };
+    builtin_method(
+        "cxchg",
+        tpe,
+        vec![
+            (Some("aligned".into()), Type::Ptr(Type::U32.into())),
+            (Some("shift".into()), Type::I32),
+            (Some("word".into()), Type::U32),
+            (Some("old".into()), Type::U32),
+        ],
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::STLoc {
+                        local: 0,
+                        tree: aligned,
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 1,
+                        tree: shift,
+                    }
+                    .into(),
+                    CILRoot::GoTo {
+                        target: 1,
+                        sub_target: 0,
+                    }
+                    .into(),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(
+                vec![
+                    CILRoot::STLoc {
+                        local: 2,
+                        tree: CILNode::LDIndU32 {
+                            ptr: CILNode::LDLoc(0).into(),
+                        },
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 3,
+                        tree: old_bits,
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 2,
+                        sub_target: 0,
+                        cond: eq!(CILNode::LDLoc(3), expected_bits),
+                    }
+                    .into(),
+                    // The value is not equal to `expected`, so the exchange fails.
+                    ret_old.clone().into(),
+                ],
+                1,
+                None,
+            ),
+            BasicBlock::new(
+                vec![
+                    CILRoot::BTrue {
+                        target: 3,
+                        sub_target: 0,
+                        cond: eq!(
+                            call!(
+                                compare_exchange,
+                                [
+                                    CILNode::LDLoc(0),
+                                    CILNode::Or(other_bits.into(), new_bits.into()),
+                                    CILNode::LDLoc(2)
+                                ]
+                            ),
+                            CILNode::LDLoc(2)
+                        ),
+                    }
+                    .into(),
+                    // Some other part of the word changed, try again.
+                    CILRoot::GoTo {
+                        target: 1,
+                        sub_target: 0,
+                    }
+                    .into(),
+                ],
+                2,
+                None,
+            ),
+            BasicBlock::new(vec![ret_old.into()], 3, None),
+        ],
+    )
+}
+/// `atomic_xchg_T(ptr, val) -> T`: returns the old value.
+fn xchg(tpe: &Type) -> Method {
+    match tpe {
+        Type::U8 | Type::I8 | Type::U16 | Type::I16 => rmw("xchg", tpe),
+        _ => {
+            let itpe = interlocked_type(tpe);
+            let exchange = CallSite::new(
+                Some(DotnetTypeRef::interlocked()),
+                "Exchange".into(),
+                FnSig::new(
+                    &[Type::ManagedReference(itpe.clone().into()), itpe.clone()],
+                    &itpe,
+                ),
+                true,
+            );
+            builtin_method(
+                "xchg",
+                tpe,
+                vec![],
+                vec![BasicBlock::new(
+                    vec![CILRoot::Ret {
+                        tree: call!(exchange, [CILNode::LDArg(0), CILNode::LDArg(1)]),
+                    }
+                    .into()],
+                    0,
+                    None,
+                )],
+            )
+        }
+    }
+}
+/// `atomic_{op}_T(ptr, val) -> T`: atomically replaces the value behind `ptr` with `op(old, val)` using a compare-exchange loop, and returns the old value.
+fn rmw(op: &str, tpe: &Type) -> Method {
+    let old = || CILNode::LDLoc(0);
+    let val = || CILNode::LDArg(1);
+    // Computes the new value. For min/max, `keep_old` is the condition under which the old value is kept.
+    let (new, keep_old) = match op {
+        "xchg" => (val(), None),
+        "xadd" => (old() + val(), None),
+        "xsub" => (old() - val(), None),
+        "and" => (CILNode::And(old().into(), val().into()), None),
+        "or" => (old() | val(), None),
+        "xor" => (CILNode::XOr(old().into(), val().into()), None),
+        "nand" => (
+            CILNode::Not(CILNode::And(old().into(), val().into()).into()),
+            None,
+        ),
+        "max" => (val(), Some(CILNode::Gt(old().into(), val().into()))),
+        "min" => (val(), Some(CILNode::Lt(old().into(), val().into()))),
+        "umax" => (val(), Some(CILNode::GtUn(old().into(), val().into()))),
+        "umin" => (val(), Some(CILNode::LtUn(old().into(), val().into()))),
+        _ => panic!("Unknown atomic operation {op}"),
+    };
+    let mut load = vec![
+        CILRoot::STLoc {
+            local: 0,
+            tree: CILNode::LdObj {
+                ptr: CILNode::LDArg(0).into(),
+                obj: tpe.clone().into(),
+            },
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: 1,
+            tree: new,
+        }
+        .into(),
+    ];
+    if let Some(keep_old) = keep_old {
+        load.push(
+            CILRoot::BTrue {
+                target: 1,
+                sub_target: 0,
+                cond: keep_old,
+            }
+            .into(),
+        );
+    }
+    load.push(
+        CILRoot::GoTo {
+            target: 2,
+            sub_target: 0,
+        }
+        .into(),
+    );
+    builtin_method(
+        op,
+        tpe,
+        vec![
+            (Some("old".into()), tpe.clone()),
+            (Some("new".into()), tpe.clone()),
+        ],
+        vec![
+            BasicBlock::new(load, 0, None),
+            BasicBlock::new(
+                vec![
+                    CILRoot::STLoc {
+                        local: 1,
+                        tree: old(),
+                    }
+                    .into(),
+                    CILRoot::GoTo {
+                        target: 2,
+                        sub_target: 0,
+                    }
+                    .into(),
+                ],
+                1,
+                None,
+            ),
+            BasicBlock::new(
+                vec![
+                    CILRoot::BTrue {
+                        target: 3,
+                        sub_target: 0,
+                        cond: eq!(
+                            call!(
+                                atomic_builtin("cxchg", tpe),
+                                [CILNode::LDArg(0), old(), CILNode::LDLoc(1)]
+                            ),
+                            old()
+                        ),
+                    }
+                    .into(),
+                    // The value changed since it was loaded, try again.
+                    CILRoot::GoTo {
+                        target: 0,
+                        sub_target: 0,
+                    }
+                    .into(),
+                ],
+                2,
+                None,
+            ),
+            BasicBlock::new(vec![CILRoot::Ret { tree: old() }.into()], 3, None),
+        ],
+    )
+}
+/// Adds the builtins implementing atomic operations on all integer types.
+pub fn add_atomics(asm: &mut Assembly) {
+    for tpe in &ATOMIC_TYPES {
+        asm.add_method(cmpxchg(tpe));
+        asm.add_method(xchg(tpe));
+        for op in RMW_OPS {
+            asm.add_method(rmw(op, tpe));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Managed objects owning the state of Rust closures wrapped in delegates.
+use crate::{
+    access_modifier::AccessModifer,
+    assembly::Assembly,
+    basic_block::BasicBlock,
+    cil::{CallSite, FieldDescriptor},
+    cil_tree::{cil_node::CILNode, cil_root::CILRoot},
+    conv_isize,
+    function_sig::FnSig,
+    method::{Method, MethodType},
+    r#type::{DotnetTypeRef, Type, TypeDef},
+};
+/// Adds the `RustClosureState` class, used as the target of delegates created from Rust closures.
+/// It owns a copy of the closure, placed in memory allocated with `Marshal.AllocHGlobal`.
+/// Once the delegate(and with it, the state object) gets collected, the finalizer drops the closure and frees that memory.
+pub fn closure_state(asm: &mut Assembly) {
+    let this: Type = DotnetTypeRef::closure_state().into();
+    let void_ptr = Type::Ptr(Type::Void.into());
+    let state = FieldDescriptor::new(
+        DotnetTypeRef::closure_state(),
+        void_ptr.clone(),
+        "state".into(),
+    );
+    let drop = FieldDescriptor::new(DotnetTypeRef::closure_state(), Type::ISize, "drop".into());
+    let ld_state = CILNode::LDField {
+        addr: Box::new(CILNode::LDArg(0)),
+        field: Box::new(state.clone()),
+    };
+    // Takes the pointer to the closure and a pointer to its `drop_in_place`.
+    let ctor = Method::new(
+        AccessModifer::Public,
+        MethodType::Instance,
+        FnSig::new(&[this.clone(), void_ptr.clone(), Type::ISize], &Type::Void),
+        ".ctor",
+        vec![],
+        vec![BasicBlock::new(
+            vec![
+                CILRoot::Call {
+                    site: CallSite::new(
+                        Some(DotnetTypeRef::object_type()),
+                        ".ctor".into(),
+                        FnSig::new(&[DotnetTypeRef::object_type().into()], &Type::Void),
+                        false,
+                    ),
+                    args: [CILNode::LDArg(0)].into(),
+                }
+                .into(),
+                CILRoot::SetField {
+                    addr: CILNode::LDArg(0),
+                    value: CILNode::LDArg(1),
+                    desc: state.clone(),
+                }
+                .into(),
+                CILRoot::SetField {
+                    addr: CILNode::LDArg(0),
+                    value: CILNode::LDArg(2),
+                    desc: drop.clone(),
+                }
+                .into(),
+                CILRoot::VoidRet.into(),
+            ],
+            0,
+            None,
+        )],
+    );
+    let get_state = Method::new(
+        AccessModifer::Public,
+        MethodType::Instance,
+        FnSig::new(&[this.clone()], &void_ptr),
+        "get_State",
+        vec![],
+        vec![BasicBlock::new(
+            vec![CILRoot::Ret {
+                tree: ld_state.clone(),
+            }
+            .into()],
+            0,
+            None,
+        )],
+    );
+    // Overrides `System.Object.Finalize`.
+    let finalize = Method::new(
+        AccessModifer::Public,
+        MethodType::Virtual,
+        FnSig::new(&[this], &Type::Void),
+        "Finalize",
+        vec![],
+        vec![BasicBlock::new(
+            vec![
+                CILRoot::CallI {
+                    sig: FnSig::new(&[void_ptr], &Type::Void),
+                    fn_ptr: CILNode::LDField {
+                        addr: Box::new(CILNode::LDArg(0)),
+                        field: Box::new(drop),
+                    },
+                    args: [ld_state.clone()].into(),
+                }
+                .into(),
+                CILRoot::Call {
+                    site: CallSite::new_extern(
+                        DotnetTypeRef::marshal(),
+                        "FreeHGlobal".into(),
+                        FnSig::new(&[Type::ISize], &Type::Void),
+                        true,
+                    ),
+                    args: [conv_isize!(ld_state)].into(),
+                }
+                .into(),
+                CILRoot::VoidRet.into(),
+            ],
+            0,
+            None,
+        )],
+    );
+    asm.add_typedef(TypeDef::new(
+        AccessModifer::Public,
+        DotnetTypeRef::closure_state().name_path().into(),
+        vec![],
+        vec![
+            ("state".into(), Type::Ptr(Type::Void.into())),
+            ("drop".into(), Type::ISize),
+        ],
+        vec![ctor, get_state, finalize],
+        None,
+        0,
+        Some(DotnetTypeRef::object_type()),
+        None,
+    ));
+}
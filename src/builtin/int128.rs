@@ -0,0 +1,1110 @@
+//! A builtin implementation of 128 bit integers, for runtimes(like Mono) which lack `System.Int128` and `System.UInt128`.
+//! Each integer is stored as a pair of 64 bit halves, in a builtin type. Those types expose the same operators
+//! (with the same names and signatures) as their .NET counterparts, so the rest of the codegen does not need to know
+//! if 128 bit integers are emulated or not: [`DotnetTypeRef::int_128`] and [`DotnetTypeRef::uint_128`] just refer to a different type.
+use crate::{
+    access_modifier::AccessModifer,
+    and,
+    assembly::Assembly,
+    basic_block::BasicBlock,
+    call,
+    cil::{CallSite, FieldDescriptor},
+    cil_tree::{cil_node::CILNode, cil_root::CILRoot, CILTree},
+    conv_f32, conv_f64, conv_f_un, conv_i64, conv_u64, eq,
+    function_sig::FnSig,
+    gt, gt_un, ld_field, ldc_i32, ldc_i64, ldc_u64, lt, lt_un,
+    method::{Method, MethodType},
+    or,
+    r#type::{DotnetTypeRef, Type, TypeDef},
+    shl, shr, shr_un,
+};
+/// Name of the builtin type used in place of `System.Int128`.
+pub const INT128: &str = "RustInt128";
+/// Name of the builtin type used in place of `System.UInt128`.
+pub const UINT128: &str = "RustUInt128";
+/// Integer types which can be converted to and from 128 bit integers.
+const CONVERTIBLE_INTS: [Type; 10] = [
+    Type::I8,
+    Type::I16,
+    Type::I32,
+    Type::I64,
+    Type::ISize,
+    Type::U8,
+    Type::U16,
+    Type::U32,
+    Type::U64,
+    Type::USize,
+];
+/// 2^64, as a `f64`.
+const TWO_POW_64: f64 = 18_446_744_073_709_551_616.0;
+/// 2^127, as a `f64`.
+const TWO_POW_127: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0;
+/// 2^128, as a `f64`.
+const TWO_POW_128: f64 = 340_282_366_920_938_463_463_374_607_431_768_211_456.0;
+/// Adds the builtin 128 bit integer types to `asm`.
+pub fn add_int128(asm: &mut Assembly) {
+    asm.add_typedef(int128_typedef(true));
+    asm.add_typedef(int128_typedef(false));
+}
+fn int128_typedef(signed: bool) -> TypeDef {
+    let mut methods = vec![ctor(signed), min_value(signed), max_value(signed)];
+    for op in [
+        "op_Addition",
+        "op_Subtraction",
+        "op_Multiply",
+        "op_BitwiseAnd",
+        "op_BitwiseOr",
+        "op_ExclusiveOr",
+    ] {
+        methods.push(binop(signed, op));
+    }
+    for op in ["op_Equality", "op_LessThan", "op_GreaterThan"] {
+        methods.push(cmp(signed, op));
+    }
+    methods.push(not(signed));
+    methods.push(neg(signed));
+    methods.push(shift(signed, true));
+    methods.push(shift(signed, false));
+    if signed {
+        methods.push(signed_div_rem(true));
+        methods.push(signed_div_rem(false));
+        methods.push(unsigned_abs());
+    } else {
+        methods.push(unsigned_div_rem(true));
+        methods.push(unsigned_div_rem(false));
+        methods.push(mul_high());
+    }
+    // `System.Int128` and `System.UInt128` provide some conversions as implicit, and some as explicit.
+    // It is simpler to provide both variants of each one.
+    for name in ["op_Implicit", "op_Explicit"] {
+        for int in CONVERTIBLE_INTS {
+            methods.push(from_int(signed, name, int.clone()));
+            methods.push(to_int(signed, name, int));
+        }
+        methods.push(from_f64(signed, name));
+        methods.push(from_f32(signed, name));
+        methods.push(to_f64(signed, name));
+        methods.push(to_f32(signed, name));
+        methods.push(from_other(signed, name));
+        methods.push(to_other(signed, name));
+    }
+    TypeDef::new(
+        AccessModifer::Public,
+        if signed { INT128 } else { UINT128 }.into(),
+        vec![],
+        vec![("low".into(), Type::U64), ("high".into(), Type::U64)],
+        methods,
+        Some(vec![0, 8]),
+        0,
+        None,
+        Some(16),
+    )
+}
+fn class(signed: bool) -> DotnetTypeRef {
+    if signed {
+        DotnetTypeRef::int_128()
+    } else {
+        DotnetTypeRef::uint_128()
+    }
+}
+fn int_type(signed: bool) -> Type {
+    if signed {
+        Type::I128
+    } else {
+        Type::U128
+    }
+}
+fn field(signed: bool, name: &str) -> FieldDescriptor {
+    FieldDescriptor::new(class(signed), Type::U64, name.into())
+}
+/// Loads the lower 64 bits of the integer at `addr`.
+fn low(signed: bool, addr: CILNode) -> CILNode {
+    ld_field!(addr, field(signed, "low"))
+}
+/// Loads the upper 64 bits of the integer at `addr`.
+fn high(signed: bool, addr: CILNode) -> CILNode {
+    ld_field!(addr, field(signed, "high"))
+}
+/// Sets the halves of the local `local`, and returns it.
+fn ret_halves(signed: bool, local: u32, low: CILNode, high: CILNode) -> Vec<CILTree> {
+    vec![
+        CILRoot::SetField {
+            addr: CILNode::LDLocA(local),
+            value: low,
+            desc: field(signed, "low"),
+        }
+        .into(),
+        CILRoot::SetField {
+            addr: CILNode::LDLocA(local),
+            value: high,
+            desc: field(signed, "high"),
+        }
+        .into(),
+        CILRoot::Ret {
+            tree: CILNode::LDLoc(local),
+        }
+        .into(),
+    ]
+}
+/// Calls the operator `name` of the builtin integer.
+fn call_op(
+    signed: bool,
+    name: &str,
+    inputs: &[Type],
+    output: &Type,
+    args: Vec<CILNode>,
+) -> CILNode {
+    call!(
+        CallSite::boxed(
+            Some(class(signed)),
+            name.into(),
+            FnSig::new(inputs, output),
+            true
+        ),
+        args
+    )
+}
+/// Reinterprets the bits of an integer as an integer of the other signedness.
+fn cast_sign(signed: bool, val: CILNode) -> CILNode {
+    call_op(
+        signed,
+        "op_Explicit",
+        &[int_type(signed)],
+        &int_type(!signed),
+        vec![val],
+    )
+}
+fn negate(signed: bool, val: CILNode) -> CILNode {
+    call_op(
+        signed,
+        "op_UnaryNegation",
+        &[int_type(signed)],
+        &int_type(signed),
+        vec![val],
+    )
+}
+/// A static method with a single local, `res`, which holds the result.
+fn op_method(signed: bool, name: &str, sig: FnSig, blocks: Vec<BasicBlock>) -> Method {
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        sig,
+        name,
+        vec![(Some("res".into()), int_type(signed))],
+        blocks,
+    )
+}
+fn single_block(trees: Vec<CILTree>) -> Vec<BasicBlock> {
+    vec![BasicBlock::new(trees, 0, None)]
+}
+/// `.ctor(uint64 upper, uint64 lower)`, used to load constants.
+fn ctor(signed: bool) -> Method {
+    let this = || CILNode::LDArg(0);
+    Method::new(
+        AccessModifer::Public,
+        MethodType::Instance,
+        FnSig::new(
+            &[
+                Type::ManagedReference(int_type(signed).into()),
+                Type::U64,
+                Type::U64,
+            ],
+            &Type::Void,
+        ),
+        ".ctor",
+        vec![],
+        single_block(vec![
+            CILRoot::SetField {
+                addr: this(),
+                value: CILNode::LDArg(2),
+                desc: field(signed, "low"),
+            }
+            .into(),
+            CILRoot::SetField {
+                addr: this(),
+                value: CILNode::LDArg(1),
+                desc: field(signed, "high"),
+            }
+            .into(),
+            CILRoot::VoidRet.into(),
+        ]),
+    )
+}
+fn min_value(signed: bool) -> Method {
+    let high_bits = if signed { 1 << 63 } else { 0 };
+    op_method(
+        signed,
+        "get_MinValue",
+        FnSig::new(&[], &int_type(signed)),
+        single_block(ret_halves(signed, 0, ldc_u64!(0), ldc_u64!(high_bits))),
+    )
+}
+fn max_value(signed: bool) -> Method {
+    let high_bits = if signed { i64::MAX as u64 } else { u64::MAX };
+    op_method(
+        signed,
+        "get_MaxValue",
+        FnSig::new(&[], &int_type(signed)),
+        single_block(ret_halves(
+            signed,
+            0,
+            ldc_u64!(u64::MAX),
+            ldc_u64!(high_bits),
+        )),
+    )
+}
+/// Arithmetic and bitwise operators, which work the same for signed and unsigned integers.
+fn binop(signed: bool, name: &str) -> Method {
+    let tpe = int_type(signed);
+    let a_low = || low(signed, CILNode::LDArgA(0));
+    let a_high = || high(signed, CILNode::LDArgA(0));
+    let b_low = || low(signed, CILNode::LDArgA(1));
+    let b_high = || high(signed, CILNode::LDArgA(1));
+    let (res_low, res_high) = match name {
+        // The carry is set if the lower half of the result wrapped around.
+        "op_Addition" => (
+            a_low() + b_low(),
+            a_high() + b_high() + conv_u64!(lt_un!(low(signed, CILNode::LDLocA(0)), a_low())),
+        ),
+        "op_Subtraction" => (
+            a_low() - b_low(),
+            a_high() - b_high() - conv_u64!(lt_un!(a_low(), b_low())),
+        ),
+        // The upper halves of the product of the upper halves are always shifted out.
+        "op_Multiply" => (
+            a_low() * b_low(),
+            call_op(
+                false,
+                "MultiplyHigh",
+                &[Type::U64, Type::U64],
+                &Type::U64,
+                vec![a_low(), b_low()],
+            ) + a_low() * b_high()
+                + a_high() * b_low(),
+        ),
+        "op_BitwiseAnd" => (and!(a_low(), b_low()), and!(a_high(), b_high())),
+        "op_BitwiseOr" => (or!(a_low(), b_low()), or!(a_high(), b_high())),
+        "op_ExclusiveOr" => (
+            CILNode::XOr(a_low().into(), b_low().into()),
+            CILNode::XOr(a_high().into(), b_high().into()),
+        ),
+        _ => panic!("{name} is not a binary 128 bit integer operator!"),
+    };
+    op_method(
+        signed,
+        name,
+        FnSig::new(&[tpe.clone(), tpe.clone()], &tpe),
+        single_block(ret_halves(signed, 0, res_low, res_high)),
+    )
+}
+fn cmp(signed: bool, name: &str) -> Method {
+    let tpe = int_type(signed);
+    let a_low = || low(signed, CILNode::LDArgA(0));
+    let a_high = || high(signed, CILNode::LDArgA(0));
+    let b_low = || low(signed, CILNode::LDArgA(1));
+    let b_high = || high(signed, CILNode::LDArgA(1));
+    // Only the upper halves are compared as signed.
+    let res = match (name, signed) {
+        ("op_Equality", _) => and!(eq!(a_low(), b_low()), eq!(a_high(), b_high())),
+        ("op_LessThan", true) => or!(
+            lt!(a_high(), b_high()),
+            and!(eq!(a_high(), b_high()), lt_un!(a_low(), b_low()))
+        ),
+        ("op_LessThan", false) => or!(
+            lt_un!(a_high(), b_high()),
+            and!(eq!(a_high(), b_high()), lt_un!(a_low(), b_low()))
+        ),
+        ("op_GreaterThan", true) => or!(
+            gt!(a_high(), b_high()),
+            and!(eq!(a_high(), b_high()), gt_un!(a_low(), b_low()))
+        ),
+        ("op_GreaterThan", false) => or!(
+            gt_un!(a_high(), b_high()),
+            and!(eq!(a_high(), b_high()), gt_un!(a_low(), b_low()))
+        ),
+        _ => panic!("{name} is not a 128 bit integer comparison!"),
+    };
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[tpe.clone(), tpe], &Type::Bool),
+        name,
+        vec![],
+        single_block(vec![CILRoot::Ret { tree: res }.into()]),
+    )
+}
+fn not(signed: bool) -> Method {
+    let tpe = int_type(signed);
+    op_method(
+        signed,
+        "op_OnesComplement",
+        FnSig::new(&[tpe.clone()], &tpe),
+        single_block(ret_halves(
+            signed,
+            0,
+            CILNode::Not(low(signed, CILNode::LDArgA(0)).into()),
+            CILNode::Not(high(signed, CILNode::LDArgA(0)).into()),
+        )),
+    )
+}
+/// `-a == !a + 1`: the carry into the upper half happens only if the lower half is 0.
+fn neg(signed: bool) -> Method {
+    let tpe = int_type(signed);
+    op_method(
+        signed,
+        "op_UnaryNegation",
+        FnSig::new(&[tpe.clone()], &tpe),
+        single_block(ret_halves(
+            signed,
+            0,
+            -low(signed, CILNode::LDArgA(0)),
+            CILNode::Not(high(signed, CILNode::LDArgA(0)).into())
+                + conv_u64!(eq!(low(signed, CILNode::LDArgA(0)), ldc_u64!(0))),
+        )),
+    )
+}
+/// `op_LeftShift` or `op_RightShift`. Like in .NET, the shift amount is masked to 7 bits.
+/// CIL does not define the result of shifting a 64 bit value by 64 or more bits, so shifts by 0 and by 64 or more bits are handled separately.
+fn shift(signed: bool, left: bool) -> Method {
+    let tpe = int_type(signed);
+    let amount = || and!(CILNode::LDArg(1), ldc_i32!(127));
+    let a_low = || low(signed, CILNode::LDArgA(0));
+    let a_high = || high(signed, CILNode::LDArgA(0));
+    let (name, short, long) = if left {
+        (
+            "op_LeftShift",
+            ret_halves(
+                signed,
+                0,
+                shl!(a_low(), amount()),
+                or!(
+                    shl!(a_high(), amount()),
+                    shr_un!(a_low(), ldc_i32!(64) - amount())
+                ),
+            ),
+            ret_halves(
+                signed,
+                0,
+                ldc_u64!(0),
+                shl!(a_low(), amount() - ldc_i32!(64)),
+            ),
+        )
+    } else if signed {
+        (
+            "op_RightShift",
+            ret_halves(
+                signed,
+                0,
+                or!(
+                    shr_un!(a_low(), amount()),
+                    shl!(a_high(), ldc_i32!(64) - amount())
+                ),
+                shr!(a_high(), amount()),
+            ),
+            ret_halves(
+                signed,
+                0,
+                shr!(a_high(), amount() - ldc_i32!(64)),
+                shr!(a_high(), ldc_i32!(63)),
+            ),
+        )
+    } else {
+        (
+            "op_RightShift",
+            ret_halves(
+                signed,
+                0,
+                or!(
+                    shr_un!(a_low(), amount()),
+                    shl!(a_high(), ldc_i32!(64) - amount())
+                ),
+                shr_un!(a_high(), amount()),
+            ),
+            ret_halves(
+                signed,
+                0,
+                shr_un!(a_high(), amount() - ldc_i32!(64)),
+                ldc_u64!(0),
+            ),
+        )
+    };
+    op_method(
+        signed,
+        name,
+        FnSig::new(&[tpe.clone(), Type::I32], &tpe),
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: eq!(amount(), ldc_i32!(0)),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 2,
+                        sub_target: 0,
+                        cond: lt!(amount(), ldc_i32!(64)),
+                    }
+                    .into(),
+                    CILRoot::GoTo {
+                        target: 3,
+                        sub_target: 0,
+                    }
+                    .into(),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: CILNode::LDArg(0),
+                }
+                .into()],
+                1,
+                None,
+            ),
+            BasicBlock::new(short, 2, None),
+            BasicBlock::new(long, 3, None),
+        ],
+    )
+}
+/// `MultiplyHigh(uint64,uint64)`: the upper 64 bits of the 128 bit product of two 64 bit integers, computed from 32 bit halves.
+fn mul_high() -> Method {
+    let half_mask = || ldc_u64!(u64::from(u32::MAX));
+    let a_low = || and!(CILNode::LDArg(0), half_mask());
+    let a_high = || shr_un!(CILNode::LDArg(0), ldc_i32!(32));
+    let b_low = || and!(CILNode::LDArg(1), half_mask());
+    let b_high = || shr_un!(CILNode::LDArg(1), ldc_i32!(32));
+    // Partial products of the halves.
+    let low_low = || a_low() * b_low();
+    let low_high = || a_low() * b_high();
+    let high_low = || a_high() * b_low();
+    let high_high = a_high() * b_high();
+    let middle = shr_un!(low_low(), ldc_i32!(32))
+        + and!(low_high(), half_mask())
+        + and!(high_low(), half_mask());
+    let res = high_high
+        + shr_un!(low_high(), ldc_i32!(32))
+        + shr_un!(high_low(), ldc_i32!(32))
+        + shr_un!(middle, ldc_i32!(32));
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[Type::U64, Type::U64], &Type::U64),
+        "MultiplyHigh",
+        vec![],
+        single_block(vec![CILRoot::Ret { tree: res }.into()]),
+    )
+}
+/// Unsigned `op_Division` or `op_Modulus`, using binary long division.
+fn unsigned_div_rem(div: bool) -> Method {
+    const QUOTIENT: u32 = 0;
+    const REMAINDER: u32 = 1;
+    const BIT: u32 = 2;
+    let bit = || CILNode::LDLoc(BIT);
+    let bit_shift = || and!(bit(), ldc_i32!(63));
+    let in_low = || conv_u64!(lt!(bit(), ldc_i32!(64)));
+    let in_high = || conv_u64!(eq!(lt!(bit(), ldc_i32!(64)), ldc_i32!(0)));
+    // The bit `BIT` of the dividend.
+    let dividend_bit = or!(
+        and!(
+            shr_un!(low(false, CILNode::LDArgA(0)), bit_shift()),
+            in_low()
+        ),
+        and!(
+            shr_un!(high(false, CILNode::LDArgA(0)), bit_shift()),
+            in_high()
+        )
+    );
+    let set_half = |local: u32, name: &str, value: CILNode| -> CILTree {
+        CILRoot::SetField {
+            addr: CILNode::LDLocA(local),
+            value,
+            desc: field(false, name),
+        }
+        .into()
+    };
+    let rem_low = || low(false, CILNode::LDLocA(REMAINDER));
+    let rem_high = || high(false, CILNode::LDLocA(REMAINDER));
+    let quot_low = || low(false, CILNode::LDLocA(QUOTIENT));
+    let quot_high = || high(false, CILNode::LDLocA(QUOTIENT));
+    let blocks = vec![
+        BasicBlock::new(
+            vec![
+                set_half(QUOTIENT, "low", ldc_u64!(0)),
+                set_half(QUOTIENT, "high", ldc_u64!(0)),
+                set_half(REMAINDER, "low", ldc_u64!(0)),
+                set_half(REMAINDER, "high", ldc_u64!(0)),
+                CILRoot::STLoc {
+                    local: BIT,
+                    tree: ldc_i32!(127),
+                }
+                .into(),
+                CILRoot::GoTo {
+                    target: 1,
+                    sub_target: 0,
+                }
+                .into(),
+            ],
+            0,
+            None,
+        ),
+        // remainder = (remainder << 1) | dividend_bit
+        BasicBlock::new(
+            vec![
+                set_half(
+                    REMAINDER,
+                    "high",
+                    or!(
+                        shl!(rem_high(), ldc_i32!(1)),
+                        shr_un!(rem_low(), ldc_i32!(63))
+                    ),
+                ),
+                set_half(
+                    REMAINDER,
+                    "low",
+                    or!(
+                        shl!(rem_low(), ldc_i32!(1)),
+                        and!(dividend_bit, ldc_u64!(1))
+                    ),
+                ),
+                CILRoot::BTrue {
+                    target: 3,
+                    sub_target: 0,
+                    cond: call_op(
+                        false,
+                        "op_LessThan",
+                        &[Type::U128, Type::U128],
+                        &Type::Bool,
+                        vec![CILNode::LDLoc(REMAINDER), CILNode::LDArg(1)],
+                    ),
+                }
+                .into(),
+                CILRoot::GoTo {
+                    target: 2,
+                    sub_target: 0,
+                }
+                .into(),
+            ],
+            1,
+            None,
+        ),
+        // remainder >= divisor, so subtract it and set the bit of the quotient.
+        BasicBlock::new(
+            vec![
+                CILRoot::STLoc {
+                    local: REMAINDER,
+                    tree: call_op(
+                        false,
+                        "op_Subtraction",
+                        &[Type::U128, Type::U128],
+                        &Type::U128,
+                        vec![CILNode::LDLoc(REMAINDER), CILNode::LDArg(1)],
+                    ),
+                }
+                .into(),
+                set_half(
+                    QUOTIENT,
+                    "low",
+                    or!(quot_low(), shl!(in_low(), bit_shift())),
+                ),
+                set_half(
+                    QUOTIENT,
+                    "high",
+                    or!(quot_high(), shl!(in_high(), bit_shift())),
+                ),
+                CILRoot::GoTo {
+                    target: 3,
+                    sub_target: 0,
+                }
+                .into(),
+            ],
+            2,
+            None,
+        ),
+        BasicBlock::new(
+            vec![
+                CILRoot::BTrue {
+                    target: 4,
+                    sub_target: 0,
+                    cond: eq!(bit(), ldc_i32!(0)),
+                }
+                .into(),
+                CILRoot::STLoc {
+                    local: BIT,
+                    tree: bit() - ldc_i32!(1),
+                }
+                .into(),
+                CILRoot::GoTo {
+                    target: 1,
+                    sub_target: 0,
+                }
+                .into(),
+            ],
+            3,
+            None,
+        ),
+        BasicBlock::new(
+            vec![CILRoot::Ret {
+                tree: CILNode::LDLoc(if div { QUOTIENT } else { REMAINDER }),
+            }
+            .into()],
+            4,
+            None,
+        ),
+    ];
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[Type::U128, Type::U128], &Type::U128),
+        if div { "op_Division" } else { "op_Modulus" },
+        vec![
+            (Some("quotient".into()), Type::U128),
+            (Some("remainder".into()), Type::U128),
+            (Some("bit".into()), Type::I32),
+        ],
+        blocks,
+    )
+}
+/// `UnsignedAbs(int128)`: the absolute value of a signed integer, as an unsigned integer(so that the absolute value of `MinValue` does not overflow).
+fn unsigned_abs() -> Method {
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[Type::I128], &Type::U128),
+        "UnsignedAbs",
+        vec![],
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: is_negative(CILNode::LDArgA(0)),
+                    }
+                    .into(),
+                    CILRoot::Ret {
+                        tree: cast_sign(true, CILNode::LDArg(0)),
+                    }
+                    .into(),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: cast_sign(true, negate(true, CILNode::LDArg(0))),
+                }
+                .into()],
+                1,
+                None,
+            ),
+        ],
+    )
+}
+/// Checks if the signed integer at `addr` is negative.
+fn is_negative(addr: CILNode) -> CILNode {
+    lt!(high(true, addr), ldc_i64!(0))
+}
+/// Signed `op_Division` or `op_Modulus`, implemented using their unsigned counterparts.
+/// The quotient is negative if the signs of the operands differ, and the remainder has the sign of the dividend.
+fn signed_div_rem(div: bool) -> Method {
+    let name = if div { "op_Division" } else { "op_Modulus" };
+    let abs = |arg: u32| {
+        call_op(
+            true,
+            "UnsignedAbs",
+            &[Type::I128],
+            &Type::U128,
+            vec![CILNode::LDArg(arg)],
+        )
+    };
+    let unsigned = call_op(
+        false,
+        name,
+        &[Type::U128, Type::U128],
+        &Type::U128,
+        vec![abs(0), abs(1)],
+    );
+    let res = || cast_sign(false, CILNode::LDLoc(0));
+    let is_negative_res = if div {
+        CILNode::XOr(
+            is_negative(CILNode::LDArgA(0)).into(),
+            is_negative(CILNode::LDArgA(1)).into(),
+        )
+    } else {
+        is_negative(CILNode::LDArgA(0))
+    };
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[Type::I128, Type::I128], &Type::I128),
+        name,
+        vec![(Some("unsigned".into()), Type::U128)],
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::STLoc {
+                        local: 0,
+                        tree: unsigned,
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: is_negative_res,
+                    }
+                    .into(),
+                    CILRoot::Ret { tree: res() }.into(),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: negate(true, res()),
+                }
+                .into()],
+                1,
+                None,
+            ),
+        ],
+    )
+}
+fn is_signed_int(int: &Type) -> bool {
+    matches!(
+        int,
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::ISize
+    )
+}
+/// Converts `int` to a 128 bit integer. Signed integers are sign-extended, and unsigned ones are zero-extended.
+fn from_int(signed: bool, name: &str, int: Type) -> Method {
+    let (res_low, res_high) = if is_signed_int(&int) {
+        (
+            conv_u64!(conv_i64!(CILNode::LDArg(0))),
+            shr!(conv_i64!(CILNode::LDArg(0)), ldc_i32!(63)),
+        )
+    } else {
+        (conv_u64!(CILNode::LDArg(0)), ldc_u64!(0))
+    };
+    op_method(
+        signed,
+        name,
+        FnSig::new(&[int], &int_type(signed)),
+        single_block(ret_halves(signed, 0, res_low, res_high)),
+    )
+}
+/// Truncates a 128 bit integer to `int`.
+fn to_int(signed: bool, name: &str, int: Type) -> Method {
+    let res = crate::casts::int_to_int(Type::U64, &int, low(signed, CILNode::LDArgA(0)));
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[int_type(signed)], &int),
+        name,
+        vec![],
+        single_block(vec![CILRoot::Ret { tree: res }.into()]),
+    )
+}
+/// Reinterprets an integer of the other signedness.
+fn from_other(signed: bool, name: &str) -> Method {
+    op_method(
+        signed,
+        name,
+        FnSig::new(&[int_type(!signed)], &int_type(signed)),
+        single_block(ret_halves(
+            signed,
+            0,
+            low(!signed, CILNode::LDArgA(0)),
+            high(!signed, CILNode::LDArgA(0)),
+        )),
+    )
+}
+/// Reinterprets as an integer of the other signedness.
+fn to_other(signed: bool, name: &str) -> Method {
+    op_method(
+        !signed,
+        name,
+        FnSig::new(&[int_type(signed)], &int_type(!signed)),
+        single_block(ret_halves(
+            !signed,
+            0,
+            low(signed, CILNode::LDArgA(0)),
+            high(signed, CILNode::LDArgA(0)),
+        )),
+    )
+}
+/// Converts a `f64` to a 128 bit integer. Like Rust's `as`, the conversion saturates, and maps NaN to 0.
+fn from_f64(signed: bool, name: &str) -> Method {
+    let tpe = int_type(signed);
+    let float = || CILNode::LDArg(0);
+    let from_unsigned = |val: CILNode| {
+        cast_sign(
+            false,
+            call_op(false, name, &[Type::F64], &Type::U128, vec![val]),
+        )
+    };
+    let min = || call_op(signed, "get_MinValue", &[], &tpe, vec![]);
+    let max = || call_op(signed, "get_MaxValue", &[], &tpe, vec![]);
+    let blocks = if signed {
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: eq!(eq!(float(), float()), ldc_i32!(0)),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 2,
+                        sub_target: 0,
+                        cond: eq!(lt!(float(), CILNode::LdcF64(TWO_POW_127)), ldc_i32!(0)),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 3,
+                        sub_target: 0,
+                        cond: eq!(gt!(float(), CILNode::LdcF64(-TWO_POW_127)), ldc_i32!(0)),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 4,
+                        sub_target: 0,
+                        cond: lt!(float(), CILNode::LdcF64(0.0)),
+                    }
+                    .into(),
+                    CILRoot::Ret {
+                        tree: from_unsigned(float()),
+                    }
+                    .into(),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(ret_halves(signed, 0, ldc_u64!(0), ldc_u64!(0)), 1, None),
+            BasicBlock::new(vec![CILRoot::Ret { tree: max() }.into()], 2, None),
+            BasicBlock::new(vec![CILRoot::Ret { tree: min() }.into()], 3, None),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: negate(true, from_unsigned(-float())),
+                }
+                .into()],
+                4,
+                None,
+            ),
+        ]
+    } else {
+        // Both halves are exact: dividing by 2^64 only changes the exponent, and the remainder of a division is always exact.
+        let two_pow_64 = || CILNode::LdcF64(TWO_POW_64);
+        vec![
+            BasicBlock::new(
+                vec![
+                    // Also true for NaNs.
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: lt_un!(float(), CILNode::LdcF64(1.0)),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 2,
+                        sub_target: 0,
+                        cond: eq!(lt!(float(), CILNode::LdcF64(TWO_POW_128)), ldc_i32!(0)),
+                    }
+                    .into(),
+                    CILRoot::GoTo {
+                        target: 3,
+                        sub_target: 0,
+                    }
+                    .into(),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(ret_halves(signed, 0, ldc_u64!(0), ldc_u64!(0)), 1, None),
+            BasicBlock::new(vec![CILRoot::Ret { tree: max() }.into()], 2, None),
+            BasicBlock::new(
+                ret_halves(
+                    signed,
+                    0,
+                    conv_u64!(CILNode::Rem(float().into(), two_pow_64().into())),
+                    conv_u64!(CILNode::Div(float().into(), two_pow_64().into())),
+                ),
+                3,
+                None,
+            ),
+        ]
+    };
+    op_method(signed, name, FnSig::new(&[Type::F64], &tpe), blocks)
+}
+fn from_f32(signed: bool, name: &str) -> Method {
+    let tpe = int_type(signed);
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[Type::F32], &tpe),
+        name,
+        vec![],
+        single_block(vec![CILRoot::Ret {
+            tree: call_op(
+                signed,
+                name,
+                &[Type::F64],
+                &tpe,
+                vec![conv_f64!(CILNode::LDArg(0))],
+            ),
+        }
+        .into()]),
+    )
+}
+/// Converts a 128 bit integer to the nearest `f64`.
+fn to_f64(signed: bool, name: &str) -> Method {
+    to_float(signed, name, Type::F64)
+}
+/// Converts a 128 bit integer to the nearest `f32`.
+fn to_f32(signed: bool, name: &str) -> Method {
+    to_float(signed, name, Type::F32)
+}
+/// Converts a 128 bit integer to the nearest `float`. Signed integers are converted using the unsigned conversion of their absolute value.
+fn to_float(signed: bool, name: &str, float: Type) -> Method {
+    let tpe = int_type(signed);
+    let (locals, blocks) = if signed {
+        let convert_unsigned =
+            |val: CILNode| call_op(false, name, &[Type::U128], &float, vec![val]);
+        let abs = call_op(
+            true,
+            "UnsignedAbs",
+            &[Type::I128],
+            &Type::U128,
+            vec![CILNode::LDArg(0)],
+        );
+        (
+            vec![],
+            vec![
+                BasicBlock::new(
+                    vec![
+                        CILRoot::BTrue {
+                            target: 1,
+                            sub_target: 0,
+                            cond: is_negative(CILNode::LDArgA(0)),
+                        }
+                        .into(),
+                        CILRoot::Ret {
+                            tree: convert_unsigned(cast_sign(true, CILNode::LDArg(0))),
+                        }
+                        .into(),
+                    ],
+                    0,
+                    None,
+                ),
+                BasicBlock::new(
+                    vec![CILRoot::Ret {
+                        tree: -convert_unsigned(abs),
+                    }
+                    .into()],
+                    1,
+                    None,
+                ),
+            ],
+        )
+    } else {
+        (
+            vec![
+                (Some("high".into()), Type::U64),
+                (Some("low".into()), Type::U64),
+                (Some("lz".into()), Type::I32),
+                (Some("shift".into()), Type::I32),
+            ],
+            single_block(unsigned_to_float(float.clone())),
+        )
+    };
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[tpe], &float),
+        name,
+        locals,
+        blocks,
+    )
+}
+/// Converts an unsigned 128 bit integer to `float`, rounding only once.
+/// The integer is shifted left until its top bit is set. The upper 64 bits then hold all the bits which can end up in the result,
+/// and the bits shifted out of them only matter for rounding, so they are replaced by a single "sticky" bit.
+fn unsigned_to_float(float: Type) -> Vec<CILTree> {
+    const HIGH: u32 = 0;
+    const LOW: u32 = 1;
+    const LZ: u32 = 2;
+    const SHIFT: u32 = 3;
+    let high_loc = || CILNode::LDLoc(HIGH);
+    let low_loc = || CILNode::LDLoc(LOW);
+    let lz = || CILNode::LDLoc(LZ);
+    let shift = || CILNode::LDLoc(SHIFT);
+    let set = |local: u32, tree: CILNode| -> CILTree { CILRoot::STLoc { local, tree }.into() };
+    let is_nonzero = |val: CILNode| conv_u64!(eq!(eq!(val, ldc_u64!(0)), ldc_i32!(0)));
+    let mut trees = vec![
+        set(HIGH, high(false, CILNode::LDArgA(0))),
+        set(LOW, low(false, CILNode::LDArgA(0))),
+        // If the upper half is 0, move the lower half into it.
+        set(SHIFT, eq!(high_loc(), ldc_u64!(0))),
+        set(
+            HIGH,
+            or!(
+                high_loc(),
+                and!(low_loc(), ldc_u64!(0) - conv_u64!(shift()))
+            ),
+        ),
+        set(LOW, and!(low_loc(), conv_u64!(shift()) - ldc_u64!(1))),
+        set(LZ, shift() * ldc_i32!(64)),
+    ];
+    // Binary search for the highest set bit. `(low >> 1) >> (63 - shift)` is used, because shifting a 64 bit integer by 64 is not defined.
+    for bits in [32, 16, 8, 4, 2, 1] {
+        trees.extend([
+            set(
+                SHIFT,
+                eq!(shr_un!(high_loc(), ldc_i32!(64 - bits)), ldc_u64!(0)) * ldc_i32!(bits),
+            ),
+            set(
+                HIGH,
+                or!(
+                    shl!(high_loc(), shift()),
+                    shr_un!(shr_un!(low_loc(), ldc_i32!(1)), ldc_i32!(63) - shift())
+                ),
+            ),
+            set(LOW, shl!(low_loc(), shift())),
+            set(LZ, lz() + shift()),
+        ]);
+    }
+    let top = or!(high_loc(), is_nonzero(low_loc()));
+    // 2^exp, where exp is the exponent of the lowest bit of `top`.
+    let scale = |exp: CILNode| {
+        call!(
+            CallSite::new_extern(
+                bit_converter(),
+                "Int64BitsToDouble".into(),
+                FnSig::new(&[Type::I64], &Type::F64),
+                true,
+            ),
+            [shl!(conv_i64!(exp + ldc_i32!(1023)), ldc_i32!(52))]
+        )
+    };
+    let res = match float {
+        Type::F64 => conv_f64!(conv_f64!(conv_f_un!(top)) * scale(ldc_i32!(64) - lz())),
+        // A `f64` could round to a `f32` differently, so the top 53 bits(with a new sticky bit) are converted exactly,
+        // and then rounded to a `f32` once. Multiplying that `f32` by a power of 2 is exact(or overflows to infinity).
+        Type::F32 => {
+            let top_53 = or!(
+                shr_un!(top.clone(), ldc_i32!(11)),
+                is_nonzero(and!(top, ldc_u64!(0x7FF)))
+            );
+            conv_f32!(conv_f64!(conv_f32!(conv_f_un!(top_53))) * scale(ldc_i32!(75) - lz()))
+        }
+        _ => panic!("Can't convert a 128 bit integer to {float:?}"),
+    };
+    trees.push(CILRoot::Ret { tree: res }.into());
+    trees
+}
+fn bit_converter() -> DotnetTypeRef {
+    DotnetTypeRef::new(Some("System.Runtime"), "System.BitConverter").with_valuetype(false)
+}
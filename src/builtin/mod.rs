@@ -1,4 +1,5 @@
 use crate::basic_block::BasicBlock;
+use crate::cil::CILOp;
 use crate::cil_tree::cil_node::CILNode;
 use crate::cil_tree::cil_root::CILRoot;
 use crate::method::MethodType;
@@ -9,8 +10,13 @@ use crate::{
 };
 use crate::{call, conv_usize, ldc_u64, lt_un};
 use rustc_middle::ty::TyCtxt;
+pub(crate) mod atomics;
 mod casts;
+mod delegates;
+pub(crate) mod int128;
 mod select;
+pub(crate) mod soft_float;
+mod thread_local;
 const MAX_ALLOC_SIZE: u64 = u32::MAX as u64;
 add_method_from_trees!(
     bounds_check,
@@ -126,6 +132,10 @@ macro_rules! add_method_from_trees {
 /// Inserts a small subset of libc and some standard types into an assembly.
 pub fn insert_ffi_functions(asm: &mut Assembly, tyctx: TyCtxt) {
     bounds_check(asm);
+    catch_unwind(asm);
+    rust_exception(asm);
+    delegates::closure_state(asm);
+    thread_local::thread_local_owner(asm);
     let c_void = Type::c_void(tyctx);
     asm.add_typedef(crate::r#type::TypeDef::new(
         AccessModifer::Public,
@@ -327,7 +337,11 @@ pub fn insert_ffi_functions(asm: &mut Assembly, tyctx: TyCtxt) {
     //TODO: add volatile prefix to volatile loads
 
     // asm.add_method(volatile_load);
-    //atomics::add_atomics(asm);
+    atomics::add_atomics(asm);
+    soft_float::add_soft_float(asm);
+    if *crate::config::EMULATE_INT128 {
+        int128::add_int128(asm);
+    }
     //ctpop::add_ctpop(asm);
     // exact_div::add_exact_div(asm);
     //memcmp::add_memcmp(asm);
@@ -381,3 +395,123 @@ add_method_from_trees!(
         BasicBlock::new(vec![CILRoot::VoidRet.into(),], 1, None,)
     ]
 );
+/// Adds `RustException`, the exception type Rust panics are unwound as. It carries the pointer to the exception object passed to `_Unwind_RaiseException`.
+fn rust_exception(asm: &mut Assembly) {
+    let this: Type = DotnetTypeRef::rust_exception().into();
+    let exception =
+        DotnetTypeRef::new(Some("System.Runtime"), "System.Exception").with_valuetype(false);
+    let ctor = Method::new(
+        AccessModifer::Public,
+        MethodType::Instance,
+        FnSig::new(&[this, Type::USize], &Type::Void),
+        ".ctor",
+        vec![],
+        vec![BasicBlock::new(
+            vec![
+                CILRoot::Call {
+                    site: CallSite::new(
+                        Some(exception.clone()),
+                        ".ctor".into(),
+                        FnSig::new(
+                            &[
+                                exception.clone().into(),
+                                DotnetTypeRef::string_type().into(),
+                            ],
+                            &Type::Void,
+                        ),
+                        false,
+                    ),
+                    args: [
+                        CILNode::LDArg(0),
+                        CILNode::LdStr("A Rust panic unwound past this point.".into()),
+                    ]
+                    .into(),
+                }
+                .into(),
+                CILRoot::SetField {
+                    addr: CILNode::LDArg(0),
+                    value: CILNode::LDArg(1),
+                    desc: crate::cil::FieldDescriptor::new(
+                        DotnetTypeRef::rust_exception(),
+                        Type::USize,
+                        "data_pointer".into(),
+                    ),
+                }
+                .into(),
+                CILRoot::VoidRet.into(),
+            ],
+            0,
+            None,
+        )],
+    );
+    asm.add_typedef(crate::r#type::TypeDef::new(
+        AccessModifer::Public,
+        DotnetTypeRef::rust_exception().name_path().into(),
+        vec![],
+        vec![("data_pointer".into(), Type::USize)],
+        vec![ctor],
+        None,
+        0,
+        Some(exception),
+        None,
+    ));
+}
+/// Signature of the builtin `catch_unwind`: `fn(try_fn, data, catch_fn) -> i32`.
+pub(crate) fn catch_unwind_sig() -> FnSig {
+    let data = Type::Ptr(Type::U8.into());
+    FnSig::new(
+        &[
+            Type::DelegatePtr(FnSig::new(&[data.clone()], &Type::Void).into()),
+            data.clone(),
+            Type::DelegatePtr(FnSig::new(&[data.clone(), data], &Type::Void).into()),
+        ],
+        &Type::I32,
+    )
+}
+/// Calls `try_fn(data)`. If it panics, the `RustException` is caught, `catch_fn(data, payload)` is called, and 1 is returned. Otherwise, returns 0.
+fn catch_unwind(asm: &mut Assembly) {
+    let data = Type::Ptr(Type::U8.into());
+    let payload = crate::cil::FieldDescriptor::new(
+        DotnetTypeRef::rust_exception(),
+        Type::USize,
+        "data_pointer".into(),
+    );
+    let method = Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        catch_unwind_sig(),
+        "catch_unwind",
+        vec![(Some("payload".into()), Type::USize)],
+        vec![BasicBlock::new(
+            vec![CILRoot::JumpingPad {
+                ops: [
+                    CILOp::BeginTry,
+                    CILOp::LDArg(1),
+                    CILOp::LDArg(0),
+                    CILOp::CallI(FnSig::new(&[data.clone()], &Type::Void).into()),
+                    CILOp::Leave(1),
+                    CILOp::BeginTypedCatch(DotnetTypeRef::rust_exception().into()),
+                    CILOp::LDField(payload.into()),
+                    CILOp::STLoc(0),
+                    CILOp::LDArg(1),
+                    CILOp::LDLoc(0),
+                    CILOp::LDArg(2),
+                    CILOp::CallI(FnSig::new(&[data.clone(), data], &Type::Void).into()),
+                    CILOp::Leave(2),
+                    CILOp::EndTry,
+                    CILOp::Label(1, 0),
+                    CILOp::LdcI32(0),
+                    CILOp::Ret,
+                    CILOp::Label(2, 0),
+                    CILOp::LdcI32(1),
+                    CILOp::Ret,
+                ]
+                .into(),
+            }
+            .into()],
+            0,
+            None,
+        )],
+    );
+    asm.add_method(method);
+}
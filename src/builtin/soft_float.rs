@@ -0,0 +1,1745 @@
+//! A software implementation of `f128`, which has no .NET equivalent.
+//! `f128` values are stored as their IEEE bits, in a `System.UInt128`. The builtins share the names and signatures of their libgcc counterparts,
+//! so that the C exporter can use libgcc instead.
+//! All operations are exact, or correctly rounded to nearest, ties to even: emulating them with the precision of `f64` would silently give wrong results.
+use crate::{
+    access_modifier::AccessModifer,
+    and,
+    assembly::Assembly,
+    basic_block::BasicBlock,
+    call,
+    cil::CallSite,
+    cil_tree::{cil_node::CILNode, cil_root::CILRoot, CILTree},
+    conv_f32, conv_f64, conv_f_un, conv_i32, conv_i64, conv_u64, eq,
+    function_sig::FnSig,
+    gt, gt_un, ldc_i32, ldc_i64, ldc_u64, lt, lt_un,
+    method::{Method, MethodType},
+    or,
+    r#type::{DotnetTypeRef, Type},
+    shl, shr, shr_un, xor,
+};
+/// Names of all the soft-float builtins. Those are provided by libgcc, so the C exporter does not emit them.
+pub const SOFT_FLOAT_BUILTINS: [&str; 22] = [
+    "__addtf3",
+    "__subtf3",
+    "__multf3",
+    "__divtf3",
+    "__extenddftf2",
+    "__trunctfdf2",
+    "__trunctfsf2",
+    "__trunctfhf2",
+    "__floatditf",
+    "__floatunditf",
+    "__fixtfdi",
+    "__fixunstfdi",
+    "__floattitf",
+    "__floatuntitf",
+    "__fixtfti",
+    "__fixunstfti",
+    "__negtf2",
+    "__eqtf2",
+    "__lttf2",
+    "__letf2",
+    "__gttf2",
+    "__getf2",
+];
+/// Name of the helper which rounds the results of `f128` arithmetic. It is only used by the soft-float builtins, so the C exporter does not emit it either.
+pub const F128_ROUND_PACK: &str = "f128_round_pack";
+/// Exponent bias of `f128`.
+const F128_BIAS: i32 = 16383;
+const F64_SIGN: u64 = 1 << 63;
+const F64_MANTISSA: u64 = (1 << 52) - 1;
+/// Mask of the part of the `f128` mantissa, which is stored in the upper 64 bits.
+const F128_MANTISSA_HIGH: u64 = (1 << 48) - 1;
+/// Upper 64 bits of a positive `f128` infinity.
+const F128_INF_HIGH: u64 = 0x7FFF << 48;
+/// Signature of the soft-float builtin `name`.
+fn soft_float_sig(name: &str) -> FnSig {
+    match name {
+        "__addtf3" | "__subtf3" | "__multf3" | "__divtf3" => {
+            FnSig::new(&[Type::F128, Type::F128], &Type::F128)
+        }
+        "__extenddftf2" => FnSig::new(&[Type::F64], &Type::F128),
+        "__trunctfdf2" => FnSig::new(&[Type::F128], &Type::F64),
+        "__trunctfsf2" => FnSig::new(&[Type::F128], &Type::F32),
+        "__trunctfhf2" => FnSig::new(&[Type::F128], &Type::F16),
+        "__floatditf" => FnSig::new(&[Type::I64], &Type::F128),
+        "__floatunditf" => FnSig::new(&[Type::U64], &Type::F128),
+        "__fixtfdi" => FnSig::new(&[Type::F128], &Type::I64),
+        "__fixunstfdi" => FnSig::new(&[Type::F128], &Type::U64),
+        "__floattitf" => FnSig::new(&[Type::I128], &Type::F128),
+        "__floatuntitf" => FnSig::new(&[Type::U128], &Type::F128),
+        "__fixtfti" => FnSig::new(&[Type::F128], &Type::I128),
+        "__fixunstfti" => FnSig::new(&[Type::F128], &Type::U128),
+        "__negtf2" => FnSig::new(&[Type::F128], &Type::F128),
+        "__eqtf2" | "__lttf2" | "__letf2" | "__gttf2" | "__getf2" => {
+            FnSig::new(&[Type::F128, Type::F128], &Type::I32)
+        }
+        _ => panic!("{name} is not a soft-float builtin!"),
+    }
+}
+/// Call site of the soft-float builtin `name`.
+pub(crate) fn soft_float_builtin(name: &str) -> CallSite {
+    CallSite::builtin(name.into(), soft_float_sig(name), true)
+}
+/// Extends `val` to a `f128`.
+pub(crate) fn f64_to_f128(val: CILNode) -> CILNode {
+    call!(soft_float_builtin("__extenddftf2"), [val])
+}
+/// Rounds `val` to the nearest `f64`.
+pub(crate) fn f128_to_f64(val: CILNode) -> CILNode {
+    call!(soft_float_builtin("__trunctfdf2"), [val])
+}
+pub fn add_soft_float(asm: &mut Assembly) {
+    asm.add_method(extend_df_tf());
+    asm.add_method(trunc_tf("__trunctfdf2", false, |val| val));
+    // Rounding to odd first makes the second rounding give the same result as rounding the `f128` directly.
+    asm.add_method(trunc_tf("__trunctfsf2", true, |val| conv_f32!(val)));
+    asm.add_method(trunc_tf("__trunctfhf2", true, |val| {
+        call!(
+            CallSite::new_extern(
+                DotnetTypeRef::half(),
+                "op_Explicit".into(),
+                FnSig::new(&[Type::F64], &Type::F16),
+                true,
+            ),
+            [val]
+        )
+    }));
+    asm.add_method(int_to_tf("__floatditf", true));
+    asm.add_method(int_to_tf("__floatunditf", false));
+    asm.add_method(tf_to_int("__fixtfdi", true));
+    asm.add_method(tf_to_int("__fixunstfdi", false));
+    asm.add_method(int128_to_tf("__floattitf", true));
+    asm.add_method(int128_to_tf("__floatuntitf", false));
+    asm.add_method(tf_to_int128("__fixtfti", true));
+    asm.add_method(tf_to_int128("__fixunstfti", false));
+    asm.add_method(round_pack_method());
+    asm.add_method(add_tf());
+    asm.add_method(sub_tf());
+    asm.add_method(mul_div_tf(false));
+    asm.add_method(mul_div_tf(true));
+    // Negation only flips the sign bit, so it is exact.
+    asm.add_method(builtin_method(
+        "__negtf2",
+        vec![],
+        vec![BasicBlock::new(
+            vec![CILRoot::Ret {
+                tree: call!(
+                    CallSite::new_extern(
+                        DotnetTypeRef::uint_128(),
+                        "op_ExclusiveOr".into(),
+                        FnSig::new(&[Type::U128, Type::U128], &Type::U128),
+                        true,
+                    ),
+                    [
+                        CILNode::LDArg(0),
+                        make_u128(ldc_u64!(F64_SIGN), ldc_u64!(0))
+                    ]
+                ),
+            }
+            .into()],
+            0,
+            None,
+        )],
+    ));
+    // libgcc comparisons return an integer, which has to be compared with 0.
+    // If the operands are unordered, each of them returns a value which makes that comparison false.
+    asm.add_method(cmp_tf("__eqtf2", 1));
+    asm.add_method(cmp_tf("__lttf2", 1));
+    asm.add_method(cmp_tf("__letf2", 1));
+    asm.add_method(cmp_tf("__gttf2", -1));
+    asm.add_method(cmp_tf("__getf2", -1));
+}
+fn builtin_method(
+    name: &str,
+    locals: Vec<(Option<crate::IString>, Type)>,
+    blocks: Vec<BasicBlock>,
+) -> Method {
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        soft_float_sig(name),
+        name,
+        locals,
+        blocks,
+    )
+}
+/// The upper 64 bits of the `f128` `val`.
+fn high_bits(val: CILNode) -> CILNode {
+    crate::casts::int_to_int(
+        Type::U128,
+        &Type::U64,
+        call!(
+            CallSite::new_extern(
+                DotnetTypeRef::uint_128(),
+                "op_RightShift".into(),
+                FnSig::new(&[Type::U128, Type::I32], &Type::U128),
+                true,
+            ),
+            [val, ldc_i32!(64)]
+        ),
+    )
+}
+/// The lower 64 bits of the `f128` `val`.
+fn low_bits(val: CILNode) -> CILNode {
+    crate::casts::int_to_int(Type::U128, &Type::U64, val)
+}
+/// Checks if the `f128` with the upper bits `high`(without the sign) and lower bits `low` is a NaN.
+fn is_nan(abs_high: CILNode, low: CILNode) -> CILNode {
+    or!(
+        gt_un!(abs_high.clone(), ldc_u64!(F128_INF_HIGH)),
+        and!(
+            eq!(abs_high, ldc_u64!(F128_INF_HIGH)),
+            gt_un!(low, ldc_u64!(0))
+        )
+    )
+}
+/// A libgcc comparison builtin, returning `unordered` if any of the operands is a NaN, and otherwise -1, 0 or 1 if the first operand is less than,
+/// equal to or greater than the second one.
+fn cmp_tf(name: &str, unordered: i32) -> Method {
+    let a_high = CILNode::LDLoc(0);
+    let a_low = CILNode::LDLoc(1);
+    let b_high = CILNode::LDLoc(2);
+    let b_low = CILNode::LDLoc(3);
+    let a_abs = and!(a_high.clone(), ldc_u64!(!F64_SIGN));
+    let b_abs = and!(b_high.clone(), ldc_u64!(!F64_SIGN));
+    // 1 if `a` is positive, -1 if it is negative.
+    let a_sign = ldc_i32!(1)
+        - shl!(
+            conv_i32!(shr_un!(a_high.clone(), ldc_i32!(63))),
+            ldc_i32!(1)
+        );
+    // Comparison of the magnitudes, as 128 bit unsigned integers.
+    let abs_gt = or!(
+        gt_un!(a_abs.clone(), b_abs.clone()),
+        and!(
+            eq!(a_abs.clone(), b_abs.clone()),
+            gt_un!(a_low.clone(), b_low.clone())
+        )
+    );
+    let abs_lt = or!(
+        lt_un!(a_abs.clone(), b_abs.clone()),
+        and!(
+            eq!(a_abs.clone(), b_abs.clone()),
+            lt_un!(a_low.clone(), b_low.clone())
+        )
+    );
+    builtin_method(
+        name,
+        vec![
+            (Some("a_high".into()), Type::U64),
+            (Some("a_low".into()), Type::U64),
+            (Some("b_high".into()), Type::U64),
+            (Some("b_low".into()), Type::U64),
+        ],
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::STLoc {
+                        local: 0,
+                        tree: high_bits(CILNode::LDArg(0)),
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 1,
+                        tree: low_bits(CILNode::LDArg(0)),
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 2,
+                        tree: high_bits(CILNode::LDArg(1)),
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 3,
+                        tree: low_bits(CILNode::LDArg(1)),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: or!(
+                            is_nan(a_abs.clone(), a_low.clone()),
+                            is_nan(b_abs.clone(), b_low.clone())
+                        ),
+                    }
+                    .into(),
+                    // +0 and -0 are equal.
+                    CILRoot::BTrue {
+                        target: 2,
+                        sub_target: 0,
+                        cond: eq!(
+                            or!(or!(a_abs.clone(), b_abs.clone()), or!(a_low, b_low)),
+                            ldc_u64!(0)
+                        ),
+                    }
+                    .into(),
+                    // Operands of different signs
+                    CILRoot::BTrue {
+                        target: 3,
+                        sub_target: 0,
+                        cond: gt_un!(shr_un!(xor!(a_high, b_high), ldc_i32!(63)), ldc_u64!(0)),
+                    }
+                    .into(),
+                    // Operands of the same sign: the one with the bigger magnitude is further away from 0.
+                    CILRoot::Ret {
+                        tree: (abs_gt - abs_lt) * a_sign.clone(),
+                    }
+                    .into(),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: ldc_i32!(unordered),
+                }
+                .into()],
+                1,
+                None,
+            ),
+            BasicBlock::new(vec![CILRoot::Ret { tree: ldc_i32!(0) }.into()], 2, None),
+            BasicBlock::new(vec![CILRoot::Ret { tree: a_sign }.into()], 3, None),
+        ],
+    )
+}
+/// `__floatditf(i64) -> f128` and `__floatunditf(u64) -> f128`: exact, since the `f128` mantissa has more than 64 bits.
+fn int_to_tf(name: &str, signed: bool) -> Method {
+    let mag = CILNode::LDLoc(0);
+    let sign = CILNode::LDLoc(1);
+    let lz = CILNode::LDLoc(2);
+    let (init_mag, init_sign) = if signed {
+        // All ones if the argument is negative, zero otherwise.
+        let mask = shr!(CILNode::LDArg(0), ldc_i32!(63));
+        (
+            xor!(CILNode::LDArg(0), mask.clone()) - mask,
+            and!(CILNode::LDArg(0), ldc_u64!(F64_SIGN)),
+        )
+    } else {
+        (CILNode::LDArg(0), ldc_u64!(0))
+    };
+    // The magnitude, shifted so that the implicit bit of the mantissa is shifted out.
+    let mantissa = shl!(shl!(mag.clone(), lz.clone()), ldc_i32!(1));
+    let exp = conv_u64!(ldc_i32!(63 + F128_BIAS) - lz.clone());
+    builtin_method(
+        name,
+        vec![
+            (Some("mag".into()), Type::U64),
+            (Some("sign".into()), Type::U64),
+            (Some("lz".into()), Type::I32),
+        ],
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::STLoc {
+                        local: 0,
+                        tree: init_mag,
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 1,
+                        tree: init_sign,
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: eq!(mag.clone(), ldc_u64!(0)),
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 2,
+                        tree: leading_zeros_u64(mag),
+                    }
+                    .into(),
+                    CILRoot::Ret {
+                        tree: make_u128(
+                            or!(
+                                or!(sign, shl!(exp, ldc_i32!(48))),
+                                shr_un!(mantissa.clone(), ldc_i32!(16))
+                            ),
+                            shl!(mantissa, ldc_i32!(48)),
+                        ),
+                    }
+                    .into(),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: make_u128(ldc_u64!(0), ldc_u64!(0)),
+                }
+                .into()],
+                1,
+                None,
+            ),
+        ],
+    )
+}
+/// `__fixtfdi(f128) -> i64` and `__fixunstfdi(f128) -> u64`: round towards zero, saturating out-of-range values, and turning NaNs into 0.
+fn tf_to_int(name: &str, signed: bool) -> Method {
+    let high = CILNode::LDLoc(0);
+    let low = CILNode::LDLoc(1);
+    let exp = CILNode::LDLoc(2);
+    let mag = CILNode::LDLoc(3);
+    let is_negative = gt_un!(shr_un!(high.clone(), ldc_i32!(63)), ldc_u64!(0));
+    // The 64 most significant bits of the mantissa, including the implicit one.
+    let mantissa = or!(
+        or!(
+            ldc_u64!(1 << 63),
+            shl!(
+                and!(high.clone(), ldc_u64!(F128_MANTISSA_HIGH)),
+                ldc_i32!(15)
+            )
+        ),
+        shr_un!(low.clone(), ldc_i32!(49))
+    );
+    let saturated: Vec<crate::cil_tree::CILTree> = if signed {
+        // `i64::MAX` for positive values, `i64::MIN` for negative ones.
+        vec![CILRoot::Ret {
+            tree: ldc_i64!(i64::MAX) + conv_i64!(shr_un!(high.clone(), ldc_i32!(63))),
+        }
+        .into()]
+    } else {
+        vec![
+            CILRoot::BTrue {
+                target: 1,
+                sub_target: 0,
+                cond: is_negative.clone(),
+            }
+            .into(),
+            CILRoot::Ret {
+                tree: ldc_u64!(u64::MAX),
+            }
+            .into(),
+        ]
+    };
+    let result = if signed {
+        // All ones if the value is negative, zero otherwise.
+        let mask = shr!(high.clone(), ldc_i32!(63));
+        xor!(mag, mask.clone()) - mask
+    } else {
+        mag
+    };
+    let mut bb0 = vec![
+        CILRoot::STLoc {
+            local: 0,
+            tree: high_bits(CILNode::LDArg(0)),
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: 1,
+            tree: low_bits(CILNode::LDArg(0)),
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: 2,
+            tree: conv_i32!(and!(shr_un!(high.clone(), ldc_i32!(48)), ldc_u64!(0x7FFF)))
+                - ldc_i32!(F128_BIAS),
+        }
+        .into(),
+        // Zeroes, subnormals and all values with a magnitude smaller than 1.
+        CILRoot::BTrue {
+            target: 1,
+            sub_target: 0,
+            cond: lt!(exp.clone(), ldc_i32!(0)),
+        }
+        .into(),
+        // Infinities and NaNs
+        CILRoot::BTrue {
+            target: 2,
+            sub_target: 0,
+            cond: eq!(exp.clone(), ldc_i32!(0x7FFF - F128_BIAS)),
+        }
+        .into(),
+        // Values too big for the target. The only negative value with a magnitude of 2^63 which fits in an `i64`, `i64::MIN`, is also the saturated value.
+        CILRoot::BTrue {
+            target: 3,
+            sub_target: 0,
+            cond: gt!(exp.clone(), ldc_i32!(if signed { 62 } else { 63 })),
+        }
+        .into(),
+    ];
+    if !signed {
+        bb0.push(
+            CILRoot::BTrue {
+                target: 1,
+                sub_target: 0,
+                cond: is_negative,
+            }
+            .into(),
+        );
+    }
+    bb0.extend([
+        CILRoot::STLoc {
+            local: 3,
+            tree: shr_un!(mantissa, ldc_i32!(63) - exp),
+        }
+        .into(),
+        CILRoot::Ret { tree: result }.into(),
+    ]);
+    let zero = if signed { ldc_i64!(0) } else { ldc_u64!(0) };
+    let mut bb2 = vec![CILRoot::BTrue {
+        target: 1,
+        sub_target: 0,
+        cond: is_nan(and!(high, ldc_u64!(!F64_SIGN)), low),
+    }
+    .into()];
+    bb2.extend(saturated.clone());
+    builtin_method(
+        name,
+        vec![
+            (Some("high".into()), Type::U64),
+            (Some("low".into()), Type::U64),
+            (Some("exp".into()), Type::I32),
+            (Some("mag".into()), Type::U64),
+        ],
+        vec![
+            BasicBlock::new(bb0, 0, None),
+            BasicBlock::new(vec![CILRoot::Ret { tree: zero }.into()], 1, None),
+            // Infinity or NaN
+            BasicBlock::new(bb2, 2, None),
+            BasicBlock::new(saturated, 3, None),
+        ],
+    )
+}
+/// Mask of the stored part of the `f128` mantissa.
+fn mantissa_mask() -> CILNode {
+    make_u128(ldc_u64!(F128_MANTISSA_HIGH), ldc_u64!(u64::MAX))
+}
+/// The absolute value of the `f128` `val`, as an integer.
+fn abs_bits(val: CILNode) -> CILNode {
+    u128_binop(
+        "op_BitwiseAnd",
+        val,
+        make_u128(ldc_u64!(!F64_SIGN), ldc_u64!(u64::MAX)),
+    )
+}
+/// A quiet NaN.
+fn nan() -> CILNode {
+    make_u128(ldc_u64!(0x7FFF_8000_0000_0000), ldc_u64!(0))
+}
+fn u128_from_u64(val: CILNode) -> CILNode {
+    make_u128(ldc_u64!(0), val)
+}
+/// Calls the binary operator `op` of `System.UInt128`.
+fn u128_binop(op: &str, a: CILNode, b: CILNode) -> CILNode {
+    call!(
+        CallSite::new_extern(
+            DotnetTypeRef::uint_128(),
+            op.into(),
+            FnSig::new(&[Type::U128, Type::U128], &Type::U128),
+            true,
+        ),
+        [a, b]
+    )
+}
+/// Shifts `val` by `amount`, using the shift operator `op` of `System.UInt128`.
+fn u128_shift(op: &str, val: CILNode, amount: CILNode) -> CILNode {
+    call!(
+        CallSite::new_extern(
+            DotnetTypeRef::uint_128(),
+            op.into(),
+            FnSig::new(&[Type::U128, Type::I32], &Type::U128),
+            true,
+        ),
+        [val, amount]
+    )
+}
+/// Compares `a` and `b`, using the comparison operator `op` of `System.UInt128`.
+fn u128_cmp(op: &str, a: CILNode, b: CILNode) -> CILNode {
+    call!(
+        CallSite::new_extern(
+            DotnetTypeRef::uint_128(),
+            op.into(),
+            FnSig::new(&[Type::U128, Type::U128], &Type::Bool),
+            true,
+        ),
+        [a, b]
+    )
+}
+fn u128_is_zero(val: CILNode) -> CILNode {
+    u128_cmp("op_Equality", val, u128_from_u64(ldc_u64!(0)))
+}
+fn leading_zeros_u64(val: CILNode) -> CILNode {
+    call!(
+        CallSite::new_extern(
+            bit_operations(),
+            "LeadingZeroCount".into(),
+            FnSig::new(&[Type::U64], &Type::I32),
+            true,
+        ),
+        [val]
+    )
+}
+/// The number of leading zeros of the 128 bit integer `val`.
+fn leading_zeros(val: CILNode) -> CILNode {
+    let high = high_bits(val.clone());
+    leading_zeros_u64(high.clone()) + eq!(high, ldc_u64!(0)) * leading_zeros_u64(low_bits(val))
+}
+/// Shifts `val` right by `amount`(at most 127), setting the lowest bit of the result if any of the bits shifted out was set.
+fn shr_sticky(val: CILNode, amount: CILNode) -> CILNode {
+    let one = || u128_from_u64(ldc_u64!(1));
+    let shifted_out = u128_binop(
+        "op_BitwiseAnd",
+        val.clone(),
+        u128_binop(
+            "op_Subtraction",
+            u128_shift("op_LeftShift", one(), amount.clone()),
+            one(),
+        ),
+    );
+    u128_binop(
+        "op_BitwiseOr",
+        u128_shift("op_RightShift", val, amount),
+        u128_from_u64(conv_u64!(eq!(u128_is_zero(shifted_out), ldc_i32!(0)))),
+    )
+}
+/// Unpacks the `f128` `val` into the locals `high`(its upper bits), `exp` and `mantissa`.
+/// The implicit bit is added to the mantissa of normal values, and the exponent of subnormals is set to 1, so that both have the same scale.
+fn unpack(val: CILNode, high: u32, exp: u32, mantissa: u32) -> [CILTree; 4] {
+    let implicit_bit = shl!(
+        conv_u64!(eq!(eq!(CILNode::LDLoc(exp), ldc_i32!(0)), ldc_i32!(0))),
+        ldc_i32!(48)
+    );
+    [
+        CILRoot::STLoc {
+            local: high,
+            tree: high_bits(val.clone()),
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: exp,
+            tree: conv_i32!(and!(
+                shr_un!(CILNode::LDLoc(high), ldc_i32!(48)),
+                ldc_u64!(0x7FFF)
+            )),
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: mantissa,
+            tree: u128_binop(
+                "op_BitwiseOr",
+                u128_binop("op_BitwiseAnd", val, mantissa_mask()),
+                make_u128(implicit_bit, ldc_u64!(0)),
+            ),
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: exp,
+            tree: CILNode::LDLoc(exp) + eq!(CILNode::LDLoc(exp), ldc_i32!(0)),
+        }
+        .into(),
+    ]
+}
+/// Shifts the nonzero `mantissa` left until its highest bit is the implicit one, adjusting `exp` so that the value stays the same.
+fn normalize(exp: u32, mantissa: u32, lz: u32) -> [CILTree; 3] {
+    [
+        CILRoot::STLoc {
+            local: lz,
+            tree: leading_zeros(CILNode::LDLoc(mantissa)) - ldc_i32!(15),
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: mantissa,
+            tree: u128_shift("op_LeftShift", CILNode::LDLoc(mantissa), CILNode::LDLoc(lz)),
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: exp,
+            tree: CILNode::LDLoc(exp) - CILNode::LDLoc(lz),
+        }
+        .into(),
+    ]
+}
+/// Rounds the value `mantissa * 2^(exp - F128_BIAS - 115)` to the nearest `f128`, with the sign `sign`.
+/// The lowest bit of `mantissa` is "sticky": it must be set if the exact value has any nonzero bits past it.
+fn round_pack(sign: CILNode, exp: CILNode, mantissa: CILNode) -> CILNode {
+    call!(
+        CallSite::builtin(
+            F128_ROUND_PACK.into(),
+            FnSig::new(&[Type::U64, Type::I32, Type::U128], &Type::F128),
+            true,
+        ),
+        [sign, exp, mantissa]
+    )
+}
+/// Implementation of [`round_pack`]. The mantissa is shifted so that its highest bit is the implicit one, followed by 3 extra bits,
+/// which are used to round it to nearest, ties to even.
+fn round_pack_method() -> Method {
+    const MANTISSA: u32 = 0;
+    const EXP: u32 = 1;
+    const LZ: u32 = 2;
+    const ROUND: u32 = 3;
+    let mantissa = || CILNode::LDLoc(MANTISSA);
+    let exp = || CILNode::LDLoc(EXP);
+    let lz = || CILNode::LDLoc(LZ);
+    let round = || CILNode::LDLoc(ROUND);
+    let set = |local: u32, tree: CILNode| -> CILTree { CILRoot::STLoc { local, tree }.into() };
+    let goto = |target: u32| -> CILTree {
+        CILRoot::GoTo {
+            target,
+            sub_target: 0,
+        }
+        .into()
+    };
+    let round_up = or!(
+        gt!(round(), ldc_i32!(4)),
+        and!(
+            eq!(round(), ldc_i32!(4)),
+            conv_i32!(and!(low_bits(mantissa()), ldc_u64!(1)))
+        )
+    );
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[Type::U64, Type::I32, Type::U128], &Type::F128),
+        F128_ROUND_PACK,
+        vec![
+            (Some("mantissa".into()), Type::U128),
+            (Some("exp".into()), Type::I32),
+            (Some("lz".into()), Type::I32),
+            (Some("round".into()), Type::I32),
+        ],
+        vec![
+            BasicBlock::new(
+                vec![
+                    set(MANTISSA, CILNode::LDArg(2)),
+                    set(EXP, CILNode::LDArg(1)),
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: u128_is_zero(mantissa()),
+                    }
+                    .into(),
+                    set(LZ, leading_zeros(mantissa())),
+                    CILRoot::BTrue {
+                        target: 2,
+                        sub_target: 0,
+                        cond: lt!(lz(), ldc_i32!(12)),
+                    }
+                    .into(),
+                    set(
+                        MANTISSA,
+                        u128_shift("op_LeftShift", mantissa(), lz() - ldc_i32!(12)),
+                    ),
+                    goto(3),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: make_u128(CILNode::LDArg(0), ldc_u64!(0)),
+                }
+                .into()],
+                1,
+                None,
+            ),
+            BasicBlock::new(
+                vec![
+                    set(MANTISSA, shr_sticky(mantissa(), ldc_i32!(12) - lz())),
+                    goto(3),
+                ],
+                2,
+                None,
+            ),
+            BasicBlock::new(
+                vec![
+                    set(EXP, exp() - lz() + ldc_i32!(12)),
+                    CILRoot::BTrue {
+                        target: 5,
+                        sub_target: 0,
+                        cond: gt!(exp(), ldc_i32!(0x7FFE)),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 4,
+                        sub_target: 0,
+                        cond: lt!(exp(), ldc_i32!(1)),
+                    }
+                    .into(),
+                    goto(6),
+                ],
+                3,
+                None,
+            ),
+            // Subnormal: the exponent is the one of the smallest normal value, and the mantissa has no implicit bit.
+            BasicBlock::new(
+                vec![
+                    set(
+                        MANTISSA,
+                        shr_sticky(
+                            mantissa(),
+                            math(
+                                "Min",
+                                &[Type::I32, Type::I32],
+                                &Type::I32,
+                                vec![ldc_i32!(1) - exp(), ldc_i32!(127)],
+                            ),
+                        ),
+                    ),
+                    set(EXP, ldc_i32!(1)),
+                    goto(6),
+                ],
+                4,
+                None,
+            ),
+            // Overflow to infinity
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: make_u128(or!(CILNode::LDArg(0), ldc_u64!(F128_INF_HIGH)), ldc_u64!(0)),
+                }
+                .into()],
+                5,
+                None,
+            ),
+            BasicBlock::new(
+                vec![
+                    set(ROUND, conv_i32!(and!(low_bits(mantissa()), ldc_u64!(7)))),
+                    set(
+                        MANTISSA,
+                        u128_shift("op_RightShift", mantissa(), ldc_i32!(3)),
+                    ),
+                    set(
+                        MANTISSA,
+                        u128_binop(
+                            "op_Addition",
+                            mantissa(),
+                            u128_from_u64(conv_u64!(round_up)),
+                        ),
+                    ),
+                    // The implicit bit is added to the exponent, which is why it is decremented first.
+                    // If rounding carried into the next bit, this also increments the exponent.
+                    set(
+                        MANTISSA,
+                        u128_binop(
+                            "op_Addition",
+                            mantissa(),
+                            u128_shift(
+                                "op_LeftShift",
+                                u128_from_u64(conv_u64!(exp() - ldc_i32!(1))),
+                                ldc_i32!(112),
+                            ),
+                        ),
+                    ),
+                    CILRoot::BTrue {
+                        target: 5,
+                        sub_target: 0,
+                        cond: eq!(
+                            lt_un!(high_bits(mantissa()), ldc_u64!(F128_INF_HIGH)),
+                            ldc_i32!(0)
+                        ),
+                    }
+                    .into(),
+                    CILRoot::Ret {
+                        tree: u128_binop(
+                            "op_BitwiseOr",
+                            mantissa(),
+                            make_u128(CILNode::LDArg(0), ldc_u64!(0)),
+                        ),
+                    }
+                    .into(),
+                ],
+                6,
+                None,
+            ),
+        ],
+    )
+}
+/// `__addtf3(f128, f128) -> f128`. The operands are ordered by magnitude, so that the exponent of the result is close to the one of the first operand.
+/// The mantissa of the second operand is shifted right to match, keeping 3 extra bits(including a sticky one), which is enough to round the sum correctly.
+fn add_tf() -> Method {
+    const X: u32 = 0;
+    const Y: u32 = 1;
+    const X_HIGH: u32 = 2;
+    const Y_HIGH: u32 = 3;
+    const X_EXP: u32 = 4;
+    const Y_EXP: u32 = 5;
+    const X_MANTISSA: u32 = 6;
+    const Y_MANTISSA: u32 = 7;
+    let loc = CILNode::LDLoc;
+    let set = |local: u32, tree: CILNode| -> CILTree { CILRoot::STLoc { local, tree }.into() };
+    let goto = |target: u32| -> CILTree {
+        CILRoot::GoTo {
+            target,
+            sub_target: 0,
+        }
+        .into()
+    };
+    let branch = |target: u32, cond: CILNode| -> CILTree {
+        CILRoot::BTrue {
+            target,
+            sub_target: 0,
+            cond,
+        }
+        .into()
+    };
+    let ret = |tree: CILNode| -> CILTree { CILRoot::Ret { tree }.into() };
+    let sign = || and!(loc(X_HIGH), ldc_u64!(F64_SIGN));
+    let mut bb2 = vec![];
+    bb2.extend(unpack(loc(X), X_HIGH, X_EXP, X_MANTISSA));
+    bb2.extend(unpack(loc(Y), Y_HIGH, Y_EXP, Y_MANTISSA));
+    bb2.extend([
+        // Since `x` has the bigger magnitude, `y` can only be a NaN if `x` is one too.
+        branch(
+            3,
+            u128_cmp(
+                "op_LessThan",
+                make_u128(ldc_u64!(F128_INF_HIGH), ldc_u64!(0)),
+                abs_bits(loc(X)),
+            ),
+        ),
+        branch(4, eq!(loc(X_EXP), ldc_i32!(0x7FFF))),
+        branch(5, u128_is_zero(loc(Y_MANTISSA))),
+        set(
+            X_MANTISSA,
+            u128_shift("op_LeftShift", loc(X_MANTISSA), ldc_i32!(3)),
+        ),
+        set(
+            Y_MANTISSA,
+            u128_shift("op_LeftShift", loc(Y_MANTISSA), ldc_i32!(3)),
+        ),
+        set(
+            Y_MANTISSA,
+            shr_sticky(
+                loc(Y_MANTISSA),
+                math(
+                    "Min",
+                    &[Type::I32, Type::I32],
+                    &Type::I32,
+                    vec![loc(X_EXP) - loc(Y_EXP), ldc_i32!(127)],
+                ),
+            ),
+        ),
+        branch(
+            6,
+            gt_un!(
+                shr_un!(xor!(loc(X_HIGH), loc(Y_HIGH)), ldc_i32!(63)),
+                ldc_u64!(0)
+            ),
+        ),
+        ret(round_pack(
+            sign(),
+            loc(X_EXP),
+            u128_binop("op_Addition", loc(X_MANTISSA), loc(Y_MANTISSA)),
+        )),
+    ]);
+    builtin_method(
+        "__addtf3",
+        vec![
+            (Some("x".into()), Type::U128),
+            (Some("y".into()), Type::U128),
+            (Some("x_high".into()), Type::U64),
+            (Some("y_high".into()), Type::U64),
+            (Some("x_exp".into()), Type::I32),
+            (Some("y_exp".into()), Type::I32),
+            (Some("x_mantissa".into()), Type::U128),
+            (Some("y_mantissa".into()), Type::U128),
+        ],
+        vec![
+            BasicBlock::new(
+                vec![
+                    set(X, CILNode::LDArg(0)),
+                    set(Y, CILNode::LDArg(1)),
+                    branch(
+                        1,
+                        u128_cmp(
+                            "op_LessThan",
+                            abs_bits(CILNode::LDArg(0)),
+                            abs_bits(CILNode::LDArg(1)),
+                        ),
+                    ),
+                    goto(2),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(
+                vec![
+                    set(X, CILNode::LDArg(1)),
+                    set(Y, CILNode::LDArg(0)),
+                    goto(2),
+                ],
+                1,
+                None,
+            ),
+            BasicBlock::new(bb2, 2, None),
+            BasicBlock::new(vec![ret(nan())], 3, None),
+            // `x` is infinite. Adding infinities of different signs gives a NaN.
+            BasicBlock::new(
+                vec![
+                    branch(
+                        3,
+                        u128_cmp(
+                            "op_Equality",
+                            u128_binop("op_ExclusiveOr", loc(X), loc(Y)),
+                            make_u128(ldc_u64!(F64_SIGN), ldc_u64!(0)),
+                        ),
+                    ),
+                    ret(loc(X)),
+                ],
+                4,
+                None,
+            ),
+            // `y` is zero. If `x` is zero too, the result is only negative if both of them are.
+            BasicBlock::new(
+                vec![branch(7, u128_is_zero(loc(X_MANTISSA))), ret(loc(X))],
+                5,
+                None,
+            ),
+            // Operands of different signs
+            BasicBlock::new(
+                vec![
+                    set(
+                        X_MANTISSA,
+                        u128_binop("op_Subtraction", loc(X_MANTISSA), loc(Y_MANTISSA)),
+                    ),
+                    // An exact zero is positive.
+                    branch(8, u128_is_zero(loc(X_MANTISSA))),
+                    ret(round_pack(sign(), loc(X_EXP), loc(X_MANTISSA))),
+                ],
+                6,
+                None,
+            ),
+            BasicBlock::new(
+                vec![ret(u128_binop("op_BitwiseAnd", loc(X), loc(Y)))],
+                7,
+                None,
+            ),
+            BasicBlock::new(vec![ret(u128_from_u64(ldc_u64!(0)))], 8, None),
+        ],
+    )
+}
+/// `__subtf3(f128, f128) -> f128`: adds the negated second operand.
+fn sub_tf() -> Method {
+    builtin_method(
+        "__subtf3",
+        vec![],
+        vec![BasicBlock::new(
+            vec![CILRoot::Ret {
+                tree: call!(
+                    soft_float_builtin("__addtf3"),
+                    [
+                        CILNode::LDArg(0),
+                        call!(soft_float_builtin("__negtf2"), [CILNode::LDArg(1)])
+                    ]
+                ),
+            }
+            .into()],
+            0,
+            None,
+        )],
+    )
+}
+/// `__multf3(f128, f128) -> f128` and `__divtf3(f128, f128) -> f128`. The mantissas are normalized first, so that subnormals don't need special handling.
+/// The product is computed exactly from 64 bit halves. The quotient is computed 15 bits at a time(so that the shifted remainder fits in 128 bits),
+/// until it has more bits than needed, with the remainder becoming the sticky bit.
+fn mul_div_tf(div: bool) -> Method {
+    const A_HIGH: u32 = 0;
+    const B_HIGH: u32 = 1;
+    const A_EXP: u32 = 2;
+    const B_EXP: u32 = 3;
+    const A_MANTISSA: u32 = 4;
+    const B_MANTISSA: u32 = 5;
+    const LZ: u32 = 6;
+    const SIGN: u32 = 7;
+    // The lower and upper parts of the product, or the quotient and the remainder.
+    const LOW: u32 = 8;
+    const HIGH: u32 = 9;
+    let loc = CILNode::LDLoc;
+    let set = |local: u32, tree: CILNode| -> CILTree { CILRoot::STLoc { local, tree }.into() };
+    let branch = |target: u32, cond: CILNode| -> CILTree {
+        CILRoot::BTrue {
+            target,
+            sub_target: 0,
+            cond,
+        }
+        .into()
+    };
+    let ret = |tree: CILNode| -> CILTree { CILRoot::Ret { tree }.into() };
+    let is_nan_arg = |arg: u32, high: u32| {
+        is_nan(
+            and!(loc(high), ldc_u64!(!F64_SIGN)),
+            low_bits(CILNode::LDArg(arg)),
+        )
+    };
+    let infinity = || make_u128(or!(loc(SIGN), ldc_u64!(F128_INF_HIGH)), ldc_u64!(0));
+    let zero = || make_u128(loc(SIGN), ldc_u64!(0));
+    let mul =
+        |a: CILNode, b: CILNode| u128_binop("op_Multiply", u128_from_u64(a), u128_from_u64(b));
+    let mut bb0 = vec![];
+    bb0.extend(unpack(CILNode::LDArg(0), A_HIGH, A_EXP, A_MANTISSA));
+    bb0.extend(unpack(CILNode::LDArg(1), B_HIGH, B_EXP, B_MANTISSA));
+    bb0.extend([
+        set(
+            SIGN,
+            and!(xor!(loc(A_HIGH), loc(B_HIGH)), ldc_u64!(F64_SIGN)),
+        ),
+        branch(1, or!(is_nan_arg(0, A_HIGH), is_nan_arg(1, B_HIGH))),
+        branch(2, eq!(loc(A_EXP), ldc_i32!(0x7FFF))),
+        branch(3, eq!(loc(B_EXP), ldc_i32!(0x7FFF))),
+    ]);
+    if div {
+        bb0.extend([
+            branch(5, u128_is_zero(loc(B_MANTISSA))),
+            branch(4, u128_is_zero(loc(A_MANTISSA))),
+        ]);
+    } else {
+        bb0.push(branch(
+            4,
+            or!(u128_is_zero(loc(A_MANTISSA)), u128_is_zero(loc(B_MANTISSA))),
+        ));
+    }
+    bb0.extend(normalize(A_EXP, A_MANTISSA, LZ));
+    bb0.extend(normalize(B_EXP, B_MANTISSA, LZ));
+    if div {
+        // The quotient of the normalized mantissas is between 1/2 and 2, so 8 steps give it 120 or 121 bits.
+        bb0.extend([
+            set(LOW, u128_from_u64(ldc_u64!(0))),
+            set(HIGH, loc(A_MANTISSA)),
+        ]);
+        for _ in 0..8 {
+            bb0.extend([
+                set(HIGH, u128_shift("op_LeftShift", loc(HIGH), ldc_i32!(15))),
+                set(
+                    LOW,
+                    u128_binop(
+                        "op_BitwiseOr",
+                        u128_shift("op_LeftShift", loc(LOW), ldc_i32!(15)),
+                        u128_binop("op_Division", loc(HIGH), loc(B_MANTISSA)),
+                    ),
+                ),
+                set(HIGH, u128_binop("op_Modulus", loc(HIGH), loc(B_MANTISSA))),
+            ]);
+        }
+        bb0.push(ret(round_pack(
+            loc(SIGN),
+            loc(A_EXP) - loc(B_EXP) + ldc_i32!(F128_BIAS - 5),
+            u128_binop(
+                "op_BitwiseOr",
+                loc(LOW),
+                u128_from_u64(conv_u64!(eq!(u128_is_zero(loc(HIGH)), ldc_i32!(0)))),
+            ),
+        )));
+    } else {
+        let a_low = || low_bits(loc(A_MANTISSA));
+        let a_high = || high_bits(loc(A_MANTISSA));
+        let b_low = || low_bits(loc(B_MANTISSA));
+        let b_high = || high_bits(loc(B_MANTISSA));
+        // The product of 2 normalized mantissas has 225 or 226 bits. Only its top 117 bits are kept, with the rest becoming the sticky bit.
+        let sticky = u128_binop(
+            "op_BitwiseAnd",
+            loc(LOW),
+            make_u128(ldc_u64!((1 << 45) - 1), ldc_u64!(u64::MAX)),
+        );
+        bb0.extend([
+            set(LOW, mul(a_low(), b_low())),
+            // The middle partial products, which are temporarily stored in `high`.
+            set(
+                HIGH,
+                u128_binop(
+                    "op_Addition",
+                    mul(a_low(), b_high()),
+                    mul(a_high(), b_low()),
+                ),
+            ),
+            set(
+                LOW,
+                u128_binop(
+                    "op_Addition",
+                    loc(LOW),
+                    u128_shift("op_LeftShift", loc(HIGH), ldc_i32!(64)),
+                ),
+            ),
+            // The carry out of the lower part: the sum wrapped around if it is smaller than one of its terms.
+            set(
+                HIGH,
+                u128_binop(
+                    "op_Addition",
+                    u128_binop(
+                        "op_Addition",
+                        mul(a_high(), b_high()),
+                        u128_shift("op_RightShift", loc(HIGH), ldc_i32!(64)),
+                    ),
+                    u128_from_u64(conv_u64!(u128_cmp(
+                        "op_LessThan",
+                        loc(LOW),
+                        u128_shift("op_LeftShift", loc(HIGH), ldc_i32!(64)),
+                    ))),
+                ),
+            ),
+            ret(round_pack(
+                loc(SIGN),
+                loc(A_EXP) + loc(B_EXP) - ldc_i32!(F128_BIAS),
+                u128_binop(
+                    "op_BitwiseOr",
+                    u128_binop(
+                        "op_BitwiseOr",
+                        u128_shift("op_LeftShift", loc(HIGH), ldc_i32!(19)),
+                        u128_shift("op_RightShift", loc(LOW), ldc_i32!(109)),
+                    ),
+                    u128_from_u64(conv_u64!(eq!(u128_is_zero(sticky), ldc_i32!(0)))),
+                ),
+            )),
+        ]);
+    }
+    // `inf * 0`, `0 * inf`, `inf / inf` and `0 / 0` are NaNs.
+    let (a_inf, b_inf, b_zero) = if div {
+        (
+            vec![
+                branch(1, eq!(loc(B_EXP), ldc_i32!(0x7FFF))),
+                ret(infinity()),
+            ],
+            vec![ret(zero())],
+            vec![branch(1, u128_is_zero(loc(A_MANTISSA))), ret(infinity())],
+        )
+    } else {
+        (
+            vec![branch(1, u128_is_zero(loc(B_MANTISSA))), ret(infinity())],
+            vec![branch(1, u128_is_zero(loc(A_MANTISSA))), ret(infinity())],
+            vec![],
+        )
+    };
+    let mut blocks = vec![
+        BasicBlock::new(bb0, 0, None),
+        BasicBlock::new(vec![ret(nan())], 1, None),
+        BasicBlock::new(a_inf, 2, None),
+        BasicBlock::new(b_inf, 3, None),
+        BasicBlock::new(vec![ret(zero())], 4, None),
+    ];
+    if div {
+        blocks.push(BasicBlock::new(b_zero, 5, None));
+    }
+    builtin_method(
+        if div { "__divtf3" } else { "__multf3" },
+        vec![
+            (Some("a_high".into()), Type::U64),
+            (Some("b_high".into()), Type::U64),
+            (Some("a_exp".into()), Type::I32),
+            (Some("b_exp".into()), Type::I32),
+            (Some("a_mantissa".into()), Type::U128),
+            (Some("b_mantissa".into()), Type::U128),
+            (Some("lz".into()), Type::I32),
+            (Some("sign".into()), Type::U64),
+            (
+                Some(if div { "quotient" } else { "low" }.into()),
+                Type::U128,
+            ),
+            (
+                Some(if div { "remainder" } else { "high" }.into()),
+                Type::U128,
+            ),
+        ],
+        blocks,
+    )
+}
+/// `__floattitf(i128) -> f128` and `__floatuntitf(u128) -> f128`: integers with more than 113 significant bits are rounded.
+fn int128_to_tf(name: &str, signed: bool) -> Method {
+    // An integer is its own mantissa, if the highest bit of the mantissa is the bit 115.
+    let exp = ldc_i32!(F128_BIAS + 115);
+    let tree = if signed {
+        let bits = || CILNode::LDLoc(0);
+        // All ones if the argument is negative, zero otherwise.
+        let mask_half = shr!(high_bits(bits()), ldc_i32!(63));
+        let mask = make_u128(mask_half.clone(), mask_half);
+        round_pack(
+            and!(high_bits(bits()), ldc_u64!(F64_SIGN)),
+            exp,
+            u128_binop(
+                "op_Subtraction",
+                u128_binop("op_ExclusiveOr", bits(), mask.clone()),
+                mask,
+            ),
+        )
+    } else {
+        round_pack(ldc_u64!(0), exp, CILNode::LDArg(0))
+    };
+    let mut trees = vec![];
+    if signed {
+        trees.push(
+            CILRoot::STLoc {
+                local: 0,
+                tree: crate::casts::int_to_int(Type::I128, &Type::U128, CILNode::LDArg(0)),
+            }
+            .into(),
+        );
+    }
+    trees.push(CILRoot::Ret { tree }.into());
+    builtin_method(
+        name,
+        if signed {
+            vec![(Some("bits".into()), Type::U128)]
+        } else {
+            vec![]
+        },
+        vec![BasicBlock::new(trees, 0, None)],
+    )
+}
+/// `__fixtfti(f128) -> i128` and `__fixunstfti(f128) -> u128`: round towards zero, saturating out-of-range values, and turning NaNs into 0.
+fn tf_to_int128(name: &str, signed: bool) -> Method {
+    let high = || CILNode::LDLoc(0);
+    let exp = || CILNode::LDLoc(1);
+    let mag = || CILNode::LDLoc(2);
+    let tpe = if signed { Type::I128 } else { Type::U128 };
+    let to_tpe = |val: CILNode| crate::casts::int_to_int(Type::U128, &tpe, val);
+    let is_negative = || gt_un!(shr_un!(high(), ldc_i32!(63)), ldc_u64!(0));
+    let result = |mag: CILNode| {
+        if signed {
+            // All ones if the value is negative, zero otherwise.
+            let mask_half = shr!(high(), ldc_i32!(63));
+            let mask = make_u128(mask_half.clone(), mask_half);
+            to_tpe(u128_binop(
+                "op_Subtraction",
+                u128_binop("op_ExclusiveOr", mag, mask.clone()),
+                mask,
+            ))
+        } else {
+            mag
+        }
+    };
+    let saturated: Vec<CILTree> = if signed {
+        // `i128::MAX` for positive values, `i128::MIN` for negative ones.
+        vec![CILRoot::Ret {
+            tree: to_tpe(make_u128(
+                ldc_u64!(i64::MAX as u64) + shr_un!(high(), ldc_i32!(63)),
+                ldc_u64!(u64::MAX) + shr_un!(high(), ldc_i32!(63)),
+            )),
+        }
+        .into()]
+    } else {
+        vec![
+            CILRoot::BTrue {
+                target: 1,
+                sub_target: 0,
+                cond: is_negative(),
+            }
+            .into(),
+            CILRoot::Ret {
+                tree: make_u128(ldc_u64!(u64::MAX), ldc_u64!(u64::MAX)),
+            }
+            .into(),
+        ]
+    };
+    let mut bb0: Vec<CILTree> = vec![
+        CILRoot::STLoc {
+            local: 0,
+            tree: high_bits(CILNode::LDArg(0)),
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: 1,
+            tree: conv_i32!(and!(shr_un!(high(), ldc_i32!(48)), ldc_u64!(0x7FFF)))
+                - ldc_i32!(F128_BIAS),
+        }
+        .into(),
+        // Zeroes, subnormals and all values with a magnitude smaller than 1.
+        CILRoot::BTrue {
+            target: 1,
+            sub_target: 0,
+            cond: lt!(exp(), ldc_i32!(0)),
+        }
+        .into(),
+        // Infinities and NaNs
+        CILRoot::BTrue {
+            target: 2,
+            sub_target: 0,
+            cond: eq!(exp(), ldc_i32!(0x7FFF - F128_BIAS)),
+        }
+        .into(),
+        // Values too big for the target. `i128::MIN` is also the saturated value.
+        CILRoot::BTrue {
+            target: 3,
+            sub_target: 0,
+            cond: gt!(exp(), ldc_i32!(if signed { 126 } else { 127 })),
+        }
+        .into(),
+    ];
+    if !signed {
+        bb0.push(
+            CILRoot::BTrue {
+                target: 1,
+                sub_target: 0,
+                cond: is_negative(),
+            }
+            .into(),
+        );
+    }
+    bb0.extend([
+        CILRoot::STLoc {
+            local: 2,
+            tree: u128_binop(
+                "op_BitwiseOr",
+                u128_binop("op_BitwiseAnd", CILNode::LDArg(0), mantissa_mask()),
+                make_u128(ldc_u64!(1 << 48), ldc_u64!(0)),
+            ),
+        }
+        .into(),
+        CILRoot::BTrue {
+            target: 4,
+            sub_target: 0,
+            cond: lt!(exp(), ldc_i32!(112)),
+        }
+        .into(),
+        CILRoot::Ret {
+            tree: result(u128_shift("op_LeftShift", mag(), exp() - ldc_i32!(112))),
+        }
+        .into(),
+    ]);
+    let mut bb2: Vec<CILTree> = vec![CILRoot::BTrue {
+        target: 1,
+        sub_target: 0,
+        cond: is_nan(
+            and!(high(), ldc_u64!(!F64_SIGN)),
+            low_bits(CILNode::LDArg(0)),
+        ),
+    }
+    .into()];
+    bb2.extend(saturated.clone());
+    builtin_method(
+        name,
+        vec![
+            (Some("high".into()), Type::U64),
+            (Some("exp".into()), Type::I32),
+            (Some("mag".into()), Type::U128),
+        ],
+        vec![
+            BasicBlock::new(bb0, 0, None),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: to_tpe(u128_from_u64(ldc_u64!(0))),
+                }
+                .into()],
+                1,
+                None,
+            ),
+            // Infinity or NaN
+            BasicBlock::new(bb2, 2, None),
+            BasicBlock::new(saturated, 3, None),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: result(u128_shift("op_RightShift", mag(), ldc_i32!(112) - exp())),
+                }
+                .into()],
+                4,
+                None,
+            ),
+        ],
+    )
+}
+/// Creates a `System.UInt128` from its upper and lower halves.
+fn make_u128(upper: CILNode, lower: CILNode) -> CILNode {
+    CILNode::NewObj {
+        site: CallSite::boxed(
+            Some(DotnetTypeRef::uint_128()),
+            ".ctor".into(),
+            FnSig::new(
+                &[
+                    Type::ManagedReference(Type::U128.into()),
+                    Type::U64,
+                    Type::U64,
+                ],
+                &Type::Void,
+            ),
+            false,
+        ),
+        args: [upper, lower].into(),
+    }
+}
+/// Creates a `f128` from its sign, biased exponent and the upper 52 bits of its mantissa. The lower 60 bits of the mantissa are zero.
+fn make_f128(sign: CILNode, exp: CILNode, mantissa: CILNode) -> CILNode {
+    make_u128(
+        CILNode::Or(
+            CILNode::Or(
+                sign.into(),
+                CILNode::Shl(exp.into(), ldc_i32!(48).into()).into(),
+            )
+            .into(),
+            CILNode::ShrUn(mantissa.clone().into(), ldc_i32!(4).into()).into(),
+        ),
+        CILNode::Shl(mantissa.into(), ldc_i32!(60).into()),
+    )
+}
+fn double_to_bits(val: CILNode) -> CILNode {
+    call!(
+        CallSite::new_extern(
+            bit_converter(),
+            "DoubleToUInt64Bits".into(),
+            FnSig::new(&[Type::F64], &Type::U64),
+            true,
+        ),
+        [val]
+    )
+}
+fn bits_to_double(val: CILNode) -> CILNode {
+    call!(
+        CallSite::new_extern(
+            bit_converter(),
+            "UInt64BitsToDouble".into(),
+            FnSig::new(&[Type::U64], &Type::F64),
+            true,
+        ),
+        [val]
+    )
+}
+fn bit_operations() -> DotnetTypeRef {
+    DotnetTypeRef::new(Some("System.Runtime"), "System.Numerics.BitOperations")
+        .with_valuetype(false)
+}
+fn bit_converter() -> DotnetTypeRef {
+    DotnetTypeRef::new(Some("System.Runtime"), "System.BitConverter").with_valuetype(false)
+}
+fn math(name: &str, inputs: &[Type], output: &Type, args: Vec<CILNode>) -> CILNode {
+    call!(
+        CallSite::new_extern(
+            DotnetTypeRef::math(),
+            name.into(),
+            FnSig::new(inputs, output),
+            true,
+        ),
+        args
+    )
+}
+/// `__extenddftf2(f64) -> f128`: exact.
+fn extend_df_tf() -> Method {
+    let bits = CILNode::LDLoc(0);
+    let sign = CILNode::And(bits.clone().into(), ldc_u64!(F64_SIGN).into());
+    let mantissa = CILNode::And(bits.clone().into(), ldc_u64!(F64_MANTISSA).into());
+    let exp = CILNode::And(
+        CILNode::ShrUn(bits.clone().into(), ldc_i32!(52).into()).into(),
+        ldc_u64!(0x7FF).into(),
+    );
+    // Subnormal `f64`s are normal `f128`s, so the value is normalized first.
+    let ilogb = math("ILogB", &[Type::F64], &Type::I32, vec![CILNode::LDArg(0)]);
+    let normalized = math(
+        "ScaleB",
+        &[Type::F64, Type::I32],
+        &Type::F64,
+        vec![CILNode::LDArg(0), -ilogb.clone()],
+    );
+    builtin_method(
+        "__extenddftf2",
+        vec![(Some("bits".into()), Type::U64)],
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::STLoc {
+                        local: 0,
+                        tree: double_to_bits(CILNode::LDArg(0)),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: eq!(exp, ldc_u64!(0x7FF)),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 2,
+                        sub_target: 0,
+                        cond: eq!(
+                            CILNode::And(bits.clone().into(), ldc_u64!(!F64_SIGN).into()),
+                            ldc_u64!(0)
+                        ),
+                    }
+                    .into(),
+                    CILRoot::Ret {
+                        tree: make_f128(
+                            sign.clone(),
+                            conv_u64!(ilogb + ldc_i32!(F128_BIAS)),
+                            CILNode::And(
+                                double_to_bits(normalized).into(),
+                                ldc_u64!(F64_MANTISSA).into(),
+                            ),
+                        ),
+                    }
+                    .into(),
+                ],
+                0,
+                None,
+            ),
+            // Infinity or NaN
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: make_f128(sign.clone(), ldc_u64!(0x7FFF), mantissa),
+                }
+                .into()],
+                1,
+                None,
+            ),
+            // Zero
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: make_f128(sign, ldc_u64!(0), ldc_u64!(0)),
+                }
+                .into()],
+                2,
+                None,
+            ),
+        ],
+    )
+}
+/// Rounds a `f128` to a `f64`, and converts it to the return type of the builtin `name` using `convert`.
+/// `__trunctfdf2` rounds to nearest, ties to even. The other builtins round to a `f64` with an odd mantissa if the result is inexact,
+/// so that converting it to a smaller float gives a correctly rounded result.
+fn trunc_tf(name: &str, round_to_odd: bool, convert: fn(CILNode) -> CILNode) -> Method {
+    let high = CILNode::LDLoc(0);
+    let low = CILNode::LDLoc(1);
+    let exp = CILNode::LDLoc(2);
+    let mantissa = CILNode::LDLoc(3);
+    let rest = CILNode::LDLoc(4);
+    let sign = CILNode::And(high.clone().into(), ldc_u64!(F64_SIGN).into());
+    // The top 52 bits of the `f128` mantissa.
+    let mantissa_top = CILNode::Or(
+        CILNode::Shl(
+            CILNode::And(high.clone().into(), ldc_u64!(F128_MANTISSA_HIGH).into()).into(),
+            ldc_i32!(4).into(),
+        )
+        .into(),
+        CILNode::ShrUn(low.clone().into(), ldc_i32!(60).into()).into(),
+    );
+    let half = ldc_u64!(1 << 63);
+    let rounded = if round_to_odd {
+        or!(mantissa.clone(), conv_u64!(gt_un!(rest, ldc_u64!(0))))
+    } else {
+        let round_up = CILNode::Or(
+            gt_un!(rest.clone(), half.clone()).into(),
+            CILNode::And(
+                eq!(rest, half).into(),
+                conv_i32!(CILNode::And(mantissa.clone().into(), ldc_u64!(1).into())).into(),
+            )
+            .into(),
+        );
+        mantissa.clone() + conv_u64!(round_up)
+    };
+    let scaled = math(
+        "ScaleB",
+        &[Type::F64, Type::I32],
+        &Type::F64,
+        vec![
+            conv_f64!(conv_f_un!(mantissa.clone())),
+            exp.clone() - ldc_i32!(F128_BIAS + 52),
+        ],
+    );
+    builtin_method(
+        name,
+        vec![
+            (Some("high".into()), Type::U64),
+            (Some("low".into()), Type::U64),
+            (Some("exp".into()), Type::I32),
+            (Some("mantissa".into()), Type::U64),
+            (Some("rest".into()), Type::U64),
+        ],
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::STLoc {
+                        local: 0,
+                        tree: high_bits(CILNode::LDArg(0)),
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 1,
+                        tree: low_bits(CILNode::LDArg(0)),
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 2,
+                        tree: conv_i32!(CILNode::And(
+                            CILNode::ShrUn(high.clone().into(), ldc_i32!(48).into()).into(),
+                            ldc_u64!(0x7FFF).into(),
+                        )),
+                    }
+                    .into(),
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: eq!(exp.clone(), ldc_i32!(0x7FFF)),
+                    }
+                    .into(),
+                    // Zeroes, and subnormal `f128`s, which are too small to be represented by a `f64`.
+                    CILRoot::BTrue {
+                        target: 2,
+                        sub_target: 0,
+                        cond: eq!(exp, ldc_i32!(0)),
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 3,
+                        tree: CILNode::Or(ldc_u64!(1 << 52).into(), mantissa_top.clone().into()),
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 4,
+                        tree: CILNode::Shl(low.clone().into(), ldc_i32!(4).into()),
+                    }
+                    .into(),
+                    CILRoot::STLoc {
+                        local: 3,
+                        tree: rounded,
+                    }
+                    .into(),
+                    // `ScaleB` handles both overflow to infinity and subnormal results.
+                    CILRoot::Ret {
+                        tree: convert(bits_to_double(CILNode::Or(
+                            double_to_bits(scaled).into(),
+                            sign.clone().into(),
+                        ))),
+                    }
+                    .into(),
+                ],
+                0,
+                None,
+            ),
+            // Infinity or NaN
+            BasicBlock::new(
+                vec![
+                    CILRoot::BTrue {
+                        target: 3,
+                        sub_target: 0,
+                        cond: eq!(
+                            CILNode::Or(
+                                CILNode::And(high.into(), ldc_u64!(F128_MANTISSA_HIGH).into())
+                                    .into(),
+                                low.into(),
+                            ),
+                            ldc_u64!(0)
+                        ),
+                    }
+                    .into(),
+                    // A quiet NaN, with as much of the payload preserved as possible.
+                    CILRoot::Ret {
+                        tree: convert(bits_to_double(CILNode::Or(
+                            CILNode::Or(sign.clone().into(), ldc_u64!(0x7FF8 << 48).into()).into(),
+                            mantissa_top.into(),
+                        ))),
+                    }
+                    .into(),
+                ],
+                1,
+                None,
+            ),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: convert(bits_to_double(CILNode::Or(
+                        sign.clone().into(),
+                        ldc_u64!(0x7FF << 52).into(),
+                    ))),
+                }
+                .into()],
+                3,
+                None,
+            ),
+            BasicBlock::new(
+                vec![CILRoot::Ret {
+                    tree: convert(bits_to_double(sign)),
+                }
+                .into()],
+                2,
+                None,
+            ),
+        ],
+    )
+}
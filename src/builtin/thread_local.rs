@@ -0,0 +1,92 @@
+//! Managed objects owning the per-thread buffers of `#[thread_local]` statics.
+use crate::{
+    access_modifier::AccessModifer,
+    assembly::Assembly,
+    basic_block::BasicBlock,
+    cil::{CallSite, FieldDescriptor},
+    cil_tree::{cil_node::CILNode, cil_root::CILRoot},
+    function_sig::FnSig,
+    method::{Method, MethodType},
+    r#type::{DotnetTypeRef, Type, TypeDef},
+};
+/// Adds the `RustThreadLocal` class. Each thread stores one instance of it alongside its copy of a `#[thread_local]` static.
+/// Once the thread exits, the instance becomes unreachable, and its finalizer frees the buffer holding that copy.
+pub fn thread_local_owner(asm: &mut Assembly) {
+    let this: Type = DotnetTypeRef::thread_local_owner().into();
+    let void_ptr = Type::Ptr(Type::Void.into());
+    let buffer = FieldDescriptor::new(
+        DotnetTypeRef::thread_local_owner(),
+        void_ptr.clone(),
+        "buffer".into(),
+    );
+    let ctor = Method::new(
+        AccessModifer::Public,
+        MethodType::Instance,
+        FnSig::new(&[this.clone(), void_ptr.clone()], &Type::Void),
+        ".ctor",
+        vec![],
+        vec![BasicBlock::new(
+            vec![
+                CILRoot::Call {
+                    site: CallSite::new(
+                        Some(DotnetTypeRef::object_type()),
+                        ".ctor".into(),
+                        FnSig::new(&[DotnetTypeRef::object_type().into()], &Type::Void),
+                        false,
+                    ),
+                    args: [CILNode::LDArg(0)].into(),
+                }
+                .into(),
+                CILRoot::SetField {
+                    addr: CILNode::LDArg(0),
+                    value: CILNode::LDArg(1),
+                    desc: buffer.clone(),
+                }
+                .into(),
+                CILRoot::VoidRet.into(),
+            ],
+            0,
+            None,
+        )],
+    );
+    // Overrides `System.Object.Finalize`. The buffer was allocated with `NativeMemory.AlignedAlloc`.
+    let finalize = Method::new(
+        AccessModifer::Public,
+        MethodType::Virtual,
+        FnSig::new(&[this], &Type::Void),
+        "Finalize",
+        vec![],
+        vec![BasicBlock::new(
+            vec![
+                CILRoot::Call {
+                    site: CallSite::new_extern(
+                        DotnetTypeRef::native_mem(),
+                        "AlignedFree".into(),
+                        FnSig::new(&[void_ptr.clone()], &Type::Void),
+                        true,
+                    ),
+                    args: [CILNode::LDField {
+                        addr: Box::new(CILNode::LDArg(0)),
+                        field: Box::new(buffer),
+                    }]
+                    .into(),
+                }
+                .into(),
+                CILRoot::VoidRet.into(),
+            ],
+            0,
+            None,
+        )],
+    );
+    asm.add_typedef(TypeDef::new(
+        AccessModifer::Public,
+        DotnetTypeRef::thread_local_owner().name_path().into(),
+        vec![],
+        vec![("buffer".into(), void_ptr)],
+        vec![ctor, finalize],
+        None,
+        0,
+        Some(DotnetTypeRef::object_type()),
+        None,
+    ));
+}
@@ -1,7 +1,5 @@
 use crate::{function_sig::FnSig, r#type::TyCache};
-use rustc_middle::ty::{Instance, List, ParamEnv, ParamEnvAnd, TyCtxt, TyKind};
-use rustc_target::abi::call::Conv;
-use rustc_target::spec::abi::Abi as TargetAbi;
+use rustc_middle::ty::{Instance, List, ParamEnv, ParamEnvAnd, TyCtxt};
 pub struct CallInfo {
     sig: FnSig,
     split_last_tuple: bool,
@@ -21,38 +19,16 @@ impl CallInfo {
             Ok(abi) => abi,
             Err(_error) => todo!(),
         };
-        let conv = fn_abi.conv;
-        match conv {
-            Conv::C | Conv::Rust => (),
-            _ => panic!("ERROR:calling using convention {conv:?} is not supported!"),
-        }
+        crate::function_sig::assert_conv_supported(fn_abi.conv);
         //assert!(!fn_abi.c_variadic);
         let ret = tycache.type_from_cache(fn_abi.ret.layout.ty, tyctx, Some(function));
         let mut args = Vec::with_capacity(fn_abi.args.len());
         for arg in fn_abi.args.iter() {
             args.push(tycache.type_from_cache(arg.layout.ty, tyctx, Some(function)));
         }
-        // There are 2 ABI enums for some reasons(they differ in what memebers they have)
-        let fn_ty = function.ty(tyctx, ParamEnv::reveal_all());
-        let internal_abi = match fn_ty.kind() {
-            TyKind::FnDef(_, _) => fn_ty.fn_sig(tyctx),
-            TyKind::Closure(_, args) => args.as_closure().sig(),
-            _ => todo!("Can't get signature of {fn_ty}"),
-        }
-        .abi();
-        // Only those ABIs are supported
-        let split_last_tuple = match internal_abi {
-            TargetAbi::C { unwind: _ }
-            | TargetAbi::Cdecl { unwind: _ }
-            | TargetAbi::RustIntrinsic
-            | TargetAbi::Rust
-            | TargetAbi::RustCold => false,
-
-            TargetAbi::RustCall => true, /*Err(CodegenError::FunctionABIUnsuported(
-            "\"rust_call\" ABI, used for things like clsoures, is not supported yet!",
-            ))?,*/
-            _ => todo!("Unsuported ABI:{internal_abi:?}"),
-        };
+        let split_last_tuple = crate::function_sig::abi_splits_last_tuple(
+            crate::function_sig::instance_abi(function, tyctx),
+        );
         let mut sig = FnSig::new(&args, &ret);
         if fn_abi.c_variadic {
             let remaining = fn_abi.args[(fn_abi.fixed_count as usize)..]
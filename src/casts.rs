@@ -1,3 +1,4 @@
+use crate::builtin::soft_float::soft_float_builtin;
 use crate::{
     call,
     cil::CallSite,
@@ -132,6 +133,26 @@ pub fn int_to_int(src: Type, target: &Type, operand: CILNode) -> CILNode {
 }
 /// Returns CIL ops required to convert type src to target
 pub fn float_to_int(src: Type, target: &Type, operand: CILNode) -> CILNode {
+    if src == Type::F128 {
+        // Going trough `f64` would round the value before truncating it, so `f128`s are truncated directly.
+        return match target {
+            Type::I128 => call!(soft_float_builtin("__fixtfti"), [operand]),
+            Type::U128 => call!(soft_float_builtin("__fixunstfti"), [operand]),
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::ISize => int_to_int(
+                Type::I64,
+                target,
+                call!(soft_float_builtin("__fixtfdi"), [operand]),
+            ),
+            _ => int_to_int(
+                Type::U64,
+                target,
+                call!(soft_float_builtin("__fixunstfdi"), [operand]),
+            ),
+        };
+    }
+    if src == Type::F16 {
+        return float_to_int(Type::F64, target, float_to_float(&src, &Type::F64, operand));
+    }
     match target {
         Type::I128 => call!(
             CallSite::new_extern(
@@ -368,7 +389,23 @@ fn to_int(target: &Type, operand: CILNode) -> CILNode {
 }
 /// Returns CIL ops required to casts from intiger type `src` to `target`
 pub fn int_to_float(src: Type, target: &Type, parrent: CILNode) -> CILNode {
-    if matches!(src, Type::I128) {
+    if *target == Type::F128 {
+        // Integers with more than 53 significant bits can't be converted trough `f64` exactly.
+        match src {
+            Type::I128 => call!(soft_float_builtin("__floattitf"), [parrent]),
+            Type::U128 => call!(soft_float_builtin("__floatuntitf"), [parrent]),
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::ISize => call!(
+                soft_float_builtin("__floatditf"),
+                [int_to_int(src, &Type::I64, parrent)]
+            ),
+            _ => call!(
+                soft_float_builtin("__floatunditf"),
+                [int_to_int(src, &Type::U64, parrent)]
+            ),
+        }
+    } else if *target == Type::F16 {
+        float_to_float(&Type::F64, target, int_to_float(src, &Type::F64, parrent))
+    } else if matches!(src, Type::I128) {
         call!(
             CallSite::boxed(
                 DotnetTypeRef::int_128().into(),
@@ -403,3 +440,44 @@ pub fn int_to_float(src: Type, target: &Type, parrent: CILNode) -> CILNode {
         }
     }
 }
+/// Returns CIL ops required to cast from float type `src` to `target`.
+/// Floats with no native .NET support(`f16` and `f128`) are converted trough `f64`, unless that could change the result.
+pub fn float_to_float(src: &Type, target: &Type, operand: CILNode) -> CILNode {
+    if src == target {
+        return operand;
+    }
+    match (src, target) {
+        (Type::F16, _) => float_to_float(
+            &Type::F64,
+            target,
+            call!(
+                CallSite::new_extern(
+                    DotnetTypeRef::half(),
+                    "op_Explicit".into(),
+                    FnSig::new(&[Type::F16], &Type::F64),
+                    true,
+                ),
+                [operand]
+            ),
+        ),
+        (Type::F128, Type::F64) => crate::builtin::soft_float::f128_to_f64(operand),
+        // Rounding trough `f64` twice could give a different result than rounding the `f128` directly.
+        (Type::F128, Type::F32) => call!(soft_float_builtin("__trunctfsf2"), [operand]),
+        (Type::F128, Type::F16) => call!(soft_float_builtin("__trunctfhf2"), [operand]),
+        (_, Type::F16) => call!(
+            CallSite::new_extern(
+                DotnetTypeRef::half(),
+                "op_Explicit".into(),
+                FnSig::new(&[Type::F64], &Type::F16),
+                true,
+            ),
+            [float_to_float(src, &Type::F64, operand)]
+        ),
+        (_, Type::F128) => {
+            crate::builtin::soft_float::f64_to_f128(float_to_float(src, &Type::F64, operand))
+        }
+        (_, Type::F32) => conv_f32!(operand),
+        (_, Type::F64) => conv_f64!(operand),
+        _ => panic!("Can't preform a float to float cast from {src:?} to {target:?}"),
+    }
+}
@@ -14,6 +14,8 @@ pub struct CallSite {
     signature: FnSig,
     is_static: bool,
     generics: Vec<Type>,
+    /// Number of fixed arguments of a call to a variadic function, lowered to a `vararg` call site.
+    fixed_args: Option<usize>,
 }
 impl CallSite {
     #[must_use]
@@ -62,6 +64,16 @@ impl CallSite {
             true,
         )
     }
+    /// Returns the call site of the function a non-capturing closure is reified into. It forwards its arguments to `shim`(the `FnOnce` shim of the closure),
+    /// passing it a dummy, zero-sized closure environment. The environment can't be a part of the signature, since function pointers don't have it.
+    #[must_use]
+    pub fn closure_fn_ptr(shim: &CallSite) -> Self {
+        CallSite::builtin(
+            format!("{name}_fn_ptr", name = shim.name()).into(),
+            FnSig::new(&shim.signature().inputs()[1..], shim.signature().output()),
+            true,
+        )
+    }
     /// Constructs a new call site targeting method `name`, with signature `signature` and bleonging to class `class`. If `class` is [`None`], then the `<Module>` class
     /// is assumed.
     #[must_use]
@@ -77,6 +89,7 @@ impl CallSite {
             signature,
             is_static,
             generics: vec![],
+            fixed_args: None,
         }
     }
     #[must_use]
@@ -93,6 +106,7 @@ impl CallSite {
             signature,
             is_static,
             generics: vec![],
+            fixed_args: None,
         }
     }
     #[must_use]
@@ -103,6 +117,7 @@ impl CallSite {
             signature,
             is_static,
             generics: vec![],
+            fixed_args: None,
         }
     }
     #[must_use]
@@ -112,6 +127,31 @@ impl CallSite {
     pub fn set_generics(&mut self, generics: Vec<Type>) {
         self.generics = generics;
     }
+    /// Marks this call site as a `vararg` call to a variadic function, taking `fixed_args` fixed arguments.
+    /// The remaining inputs of the signature are the variadic arguments passed at this call site.
+    pub fn set_vararg(&mut self, fixed_args: usize) {
+        assert!(
+            fixed_args <= self.signature.inputs().len(),
+            "A variadic call site has fewer arguments than its fixed argument count!"
+        );
+        self.fixed_args = Some(fixed_args);
+    }
+    /// Returns the number of fixed arguments, if this is a `vararg` call site.
+    #[must_use]
+    pub fn fixed_args(&self) -> Option<usize> {
+        self.fixed_args
+    }
+    /// Returns the signature of the called function, without the variadic arguments passed at this call site.
+    #[must_use]
+    pub fn fixed_signature(&self) -> FnSig {
+        match self.fixed_args {
+            Some(fixed_args) => FnSig::new(
+                &self.signature.inputs()[..fixed_args],
+                self.signature.output(),
+            ),
+            None => self.signature.clone(),
+        }
+    }
     /// The same as [`Self::new`], but boxes the result.
     #[must_use]
     pub fn boxed(
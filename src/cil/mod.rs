@@ -247,6 +247,14 @@ pub enum CILOp {
     SourceFileInfo(Box<(std::ops::Range<u64>, std::ops::Range<u64>, IString)>),
     LDLen,
     LDElelemRef,
+    /// Begins a catch block, which only handles exceptions of type `type`. The caught exception is pushed on top of the stack.
+    BeginTypedCatch(Box<DotnetTypeRef>),
+    /// Casts the object reference on top of the stack to `type`, throwing an `InvalidCastException` if that is not possible.
+    CastClass(Box<DotnetTypeRef>),
+    /// Allocates a new, zero-based, one-dimensional managed array of `type`. The element count is on top of the stack.
+    NewArr(Box<crate::r#type::Type>),
+    /// Loads the address of an element of type `type` of a managed array.
+    LDElemAdress(Box<crate::r#type::Type>),
 }
 impl CILOp {
     /// If the cil op is a call, virtual call, new object cosntructor OR it loads a pointer to a function, returns the [`CallSite`] representing this function.
@@ -362,7 +370,7 @@ impl CILOp {
             CILOp::SourceFileInfo(_) => 0,
             CILOp::Leave(_) => 0,
             CILOp::BeginTry | CILOp::EndTry => 0,
-            CILOp::BeginCatch => 1,
+            CILOp::BeginCatch | CILOp::BeginTypedCatch(_) => 1,
             CILOp::EHClause { .. } => 0,
             CILOp::CustomLabel(_) => 0,
             CILOp::Nop => 0,
@@ -479,6 +487,9 @@ impl CILOp {
             CILOp::Volatile => 0,
             CILOp::LDLen => 0,
             CILOp::LDElelemRef => -1,
+            CILOp::CastClass(_) => 0,
+            CILOp::NewArr(_) => 0,
+            CILOp::LDElemAdress(_) => -1,
         }
     }
 
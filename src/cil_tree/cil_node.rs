@@ -134,6 +134,10 @@ pub enum CILNode {
     LoadGlobalAllocPtr {
         alloc_id: u64,
     },
+    /// Pointer to the current thread's copy of the `#[thread_local]` static with allocation `alloc_id`.
+    LoadThreadLocalPtr {
+        alloc_id: u64,
+    },
     ConvU8(Box<Self>),
     ConvU16(Box<Self>),
     ConvU32(Box<Self>),
@@ -206,8 +210,23 @@ pub enum CILNode {
     LdFalse,
     /// Equivalent to `CILNode::LdcI32(1`), but with addtional typechecking info.
     LdTrue,
+    /// Allocates a buffer of `size` bytes on the stack. The buffer is freed when the method returns. Must not be used inside exception handlers.
+    LocAlloc{size:Box<Self>},
     /// Allocates a buffer of size at least `sizeof(tpe)` with aligement of `align`
     LocAllocAligned{tpe:Box<Type>,align:u64},
+    /// Casts a managed object reference to a different managed class, throwing if the object is not an instance of that class.
+    CheckedCast(Box<(CILNode, DotnetTypeRef)>),
+    /// Allocates a new, zero-based, one-dimensional managed array of `tpe`, with `size` elements.
+    NewArr {
+        tpe: Box<Type>,
+        size: Box<CILNode>,
+    },
+    /// Loads a managed reference to an element of a managed array.
+    LDElemAdress {
+        tpe: Box<Type>,
+        arr: Box<CILNode>,
+        idx: Box<CILNode>,
+    },
 }
 
 impl CILNode {
@@ -370,6 +389,7 @@ impl CILNode {
     }
     fn opt_children(&mut self) {
         match self {
+            Self::LocAlloc { size }=>size.opt(),
             Self::LocAllocAligned { .. }=>(),
             Self::LdFalse=>(),
             Self::LdTrue=>(),
@@ -434,6 +454,7 @@ impl CILNode {
             | Self::LdcF64(_)
             | Self::LdcF32(_)
             | Self::LoadGlobalAllocPtr { .. }
+            | Self::LoadThreadLocalPtr { .. }
             | Self::PointerToConstValue(_)=> (),
             Self::ConvU8(inner)
             | Self::ConvU16(inner)
@@ -470,6 +491,12 @@ impl CILNode {
                 idx.opt();
                 arr.opt();
             },
+            Self::CheckedCast(val_tpe) => val_tpe.0.opt(),
+            Self::NewArr { size, .. } => size.opt(),
+            Self::LDElemAdress { arr, idx, .. } => {
+                idx.opt();
+                arr.opt();
+            }
         }
     }
     // This fucntion will get expanded, so a single match is a non-issue.
@@ -508,15 +535,29 @@ impl CILNode {
     #[must_use]
     pub fn flatten(&self) -> Vec<CILOp> {
         let mut ops = match self {
-            Self::LocAllocAligned { tpe, align }=>vec![
-                CILOp::SizeOf(tpe.clone()),
-                CILOp::LdcU64(*align),
-                CILOp::ConvISize(false),
-                CILOp::Add,
-                CILOp::LocAlloc,
-                CILOp::LdcU64(*align - 1),
-                CILOp::Add,
-                CILOp::LdcU64(*align),CILOp::Rem,CILOp::Sub,CILOp::LdcU64(*align),CILOp::Add],
+            Self::LocAlloc { size }=>{
+                let mut res = size.flatten();
+                res.push(CILOp::LocAlloc);
+                res
+            }
+            // Allocates `align - 1` additional bytes, and then rounds the pointer up to `align`.
+            Self::LocAllocAligned { tpe, align }=>{
+                assert!(align.is_power_of_two(),"Invalid localloc aligement:{align}");
+                vec![
+                    CILOp::SizeOf(tpe.clone()),
+                    CILOp::ConvUSize(false),
+                    CILOp::LdcU64(*align - 1),
+                    CILOp::ConvUSize(false),
+                    CILOp::Add,
+                    CILOp::LocAlloc,
+                    CILOp::LdcU64(*align - 1),
+                    CILOp::ConvUSize(false),
+                    CILOp::Add,
+                    CILOp::LdcU64(!(*align - 1)),
+                    CILOp::ConvUSize(false),
+                    CILOp::And,
+                ]
+            }
             Self::LdFalse => vec![CILOp::LdcI32(0)],
             Self::LdTrue => vec![CILOp::LdcI32(1)],
             Self::TransmutePtr { val, new_ptr: _ } => val.flatten(),
@@ -747,6 +788,11 @@ impl CILNode {
                     "Unresolved global alloc with id:{alloc_id}  during the CIL flattening phase!"
                 )
             }
+            Self::LoadThreadLocalPtr { alloc_id } => {
+                panic!(
+                    "Unresolved thread local with id:{alloc_id}  during the CIL flattening phase!"
+                )
+            }
             Self::LDStaticField(sfield) => vec![CILOp::LDStaticField(sfield.clone())],
             Self::LDLen { arr } => {
                 let mut res = arr.flatten();
@@ -759,6 +805,22 @@ impl CILNode {
                 res.push(CILOp::LDElelemRef);
                 res
             }
+            Self::CheckedCast(val_tpe) => {
+                let mut res = val_tpe.0.flatten();
+                res.push(CILOp::CastClass(Box::new(val_tpe.1.clone())));
+                res
+            }
+            Self::NewArr { tpe, size } => {
+                let mut res = size.flatten();
+                res.push(CILOp::NewArr(tpe.clone()));
+                res
+            }
+            Self::LDElemAdress { tpe, arr, idx } => {
+                let mut res = arr.flatten();
+                res.extend(idx.flatten());
+                res.push(CILOp::LDElemAdress(tpe.clone()));
+                res
+            }
         };
         {
             ops.push(CILOp::Pop);
@@ -774,6 +836,7 @@ impl CILNode {
         locals: &mut Vec<(Option<Box<str>>, Type)>,
     ) {
         match self {
+            Self::LocAlloc { size }=>size.allocate_tmps(curr_loc, locals),
             Self::LocAllocAligned {..}=>(),
             Self::LdFalse=>(),
             Self::LdTrue=>(),
@@ -785,6 +848,7 @@ impl CILNode {
             },
             Self:: PointerToConstValue(_arr)=>(),
             Self::LoadGlobalAllocPtr { alloc_id: _ } => (),
+            Self::LoadThreadLocalPtr { alloc_id: _ } => (),
             Self::LDLoc(_) |
             Self::LDArg(_) |
             Self::LDLocA(_)|
@@ -891,6 +955,12 @@ impl CILNode {
                 arr.allocate_tmps(curr_loc, locals);
                 idx.allocate_tmps(curr_loc, locals);
             }
+            Self::CheckedCast(val_tpe) => val_tpe.0.allocate_tmps(curr_loc, locals),
+            Self::NewArr { size, .. } => size.allocate_tmps(curr_loc, locals),
+            Self::LDElemAdress { arr, idx, .. } => {
+                arr.allocate_tmps(curr_loc, locals);
+                idx.allocate_tmps(curr_loc, locals);
+            }
         };
     }
     pub(crate) fn resolve_global_allocations(
@@ -900,6 +970,7 @@ impl CILNode {
         tycache: &mut TyCache,
     ) {
         match self {
+            Self::LocAlloc { size }=>size.resolve_global_allocations(asm, tyctx, tycache),
             Self::LocAllocAligned {..}=>(),
             Self::LdFalse=>(),
             Self::LdTrue=>(),
@@ -968,6 +1039,9 @@ impl CILNode {
             Self::LoadGlobalAllocPtr { alloc_id } => {
                 *self = Self::LDStaticField(asm.add_allocation(*alloc_id,tyctx,tycache).into());
             }
+            Self::LoadThreadLocalPtr { alloc_id } => {
+                *self = asm.add_thread_local(*alloc_id,tyctx,tycache);
+            }
             Self::ConvF64Un(val) |
             Self::ConvF32(val)|
             Self::ConvF64(val) |
@@ -1013,11 +1087,18 @@ impl CILNode {
                 arr.resolve_global_allocations(asm, tyctx,tycache);
                 idx.resolve_global_allocations(asm, tyctx,tycache);
             }
+            Self::CheckedCast(val_tpe) => val_tpe.0.resolve_global_allocations(asm, tyctx,tycache),
+            Self::NewArr { size, .. } => size.resolve_global_allocations(asm, tyctx,tycache),
+            Self::LDElemAdress { arr, idx, .. } => {
+                arr.resolve_global_allocations(asm, tyctx,tycache);
+                idx.resolve_global_allocations(asm, tyctx,tycache);
+            }
         }
     }
 
     pub(crate) fn sheed_trees(&mut self) -> Vec<CILRoot> {
         match self {
+            Self::LocAlloc { size }=>size.sheed_trees(),
             Self::LocAllocAligned {..}=>vec![],
             Self::LdFalse => vec![],
             Self::LdTrue => vec![],
@@ -1079,6 +1160,7 @@ impl CILNode {
             | Self::LdcF64(_)
             | Self::LdcF32(_) => vec![],
             Self::LoadGlobalAllocPtr { alloc_id: _ } => vec![],
+            Self::LoadThreadLocalPtr { alloc_id: _ } => vec![],
             Self::PointerToConstValue(_value) => vec![],
             Self::ConvU8(val)
             | Self::ConvU16(val)
@@ -1131,6 +1213,13 @@ impl CILNode {
                 res.extend(idx.sheed_trees());
                 res
             }
+            Self::CheckedCast(val_tpe) => val_tpe.0.sheed_trees(),
+            Self::NewArr { size, .. } => size.sheed_trees(),
+            Self::LDElemAdress { arr, idx, .. } => {
+                let mut res = arr.sheed_trees();
+                res.extend(idx.sheed_trees());
+                res
+            }
         }
     }
 
@@ -1473,13 +1562,11 @@ impl CILNode {
                 }
                 match site.class() {
                     Some(class) => {
-                        if class.asm() == Some("System.Runtime") {
-                            if *class == DotnetTypeRef::int_128() {
-                                return Ok(Type::I128);
-                            }
-                            if *class == DotnetTypeRef::uint_128() {
-                                return Ok(Type::U128);
-                            }
+                        if *class == DotnetTypeRef::int_128() {
+                            return Ok(Type::I128);
+                        }
+                        if *class == DotnetTypeRef::uint_128() {
+                            return Ok(Type::U128);
                         }
                         Ok(Type::DotnetType(class.clone().into()))
                     }
@@ -1543,6 +1630,57 @@ impl CILNode {
             Self::LdFalse => Ok(Type::Bool),
             Self::SizeOf(_) => Ok(Type::I32),
             Self::LDFtn(ftn) => Ok(Type::DelegatePtr(Box::new(ftn.signature().clone()))),
+            Self::CheckedCast(val_tpe) => {
+                let val = val_tpe.0.validate(method)?;
+                if !val.is_gcref() {
+                    return Err(format!(
+                        "Invalid CheckedCast input: {val:?} is not a managed object reference"
+                    ));
+                }
+                Ok(Type::DotnetType(Box::new(val_tpe.1.clone())))
+            }
+            Self::LocAlloc { size } => {
+                let size = size.validate(method)?;
+                match size {
+                    Type::USize | Type::ISize | Type::I32 | Type::U32 => {
+                        Ok(Type::Ptr(Box::new(Type::U8)))
+                    }
+                    _ => Err(format!("Invalid LocAlloc size: {size:?} is not an integer")),
+                }
+            }
+            Self::LocAllocAligned { tpe, align } => {
+                if !align.is_power_of_two() {
+                    return Err(format!("Invalid LocAllocAligned aligement: {align}"));
+                }
+                Ok(Type::Ptr(tpe.clone()))
+            }
+            Self::NewArr { tpe, size } => {
+                let size = size.validate(method)?;
+                match size {
+                    Type::USize | Type::ISize | Type::I32 => Ok(Type::ManagedArray {
+                        element: tpe.clone(),
+                        dims: std::num::NonZeroU8::new(1).unwrap(),
+                    }),
+                    _ => Err(format!("Invalid NewArr size: {size:?} is not an integer")),
+                }
+            }
+            Self::LDElemAdress { tpe, arr, idx } => {
+                let arr = arr.validate(method)?;
+                let idx = idx.validate(method)?;
+                match idx {
+                    Type::USize | Type::ISize | Type::I32 => (),
+                    _ => return Err(format!("Invalid array index: {idx:?} is not an integer")),
+                }
+                match arr {
+                    Type::ManagedArray { element, dims } if Into::<u8>::into(dims) == 1 => {
+                        if element != *tpe {
+                            return Err(format!("Tried to get the address of a {tpe:?} element of an array of {element:?}"));
+                        }
+                        Ok(Type::ManagedReference(tpe.clone()))
+                    }
+                    _ => Err(format!("{arr:?} is not a one-dimensional managed array")),
+                }
+            }
             _ => todo!("Can't check the type safety of {self:?}"),
         }
     }
@@ -85,8 +85,10 @@ fn test_lib(args: &[&str], test_name: &str) {
         .args(args);
 
     let command = if *IS_MONO_PRESENT {
-        // Tell the linker to test AOT
-        command.args(["-C", "link-arg=--aot-mode,mono-full"])
+        // Tell the linker to test AOT. Mono lacks `System.Int128`, so 128 bit integers have to be emulated.
+        command
+            .args(["-C", "link-arg=--aot-mode,mono-full"])
+            .env("EMULATE_INT128", "1")
     } else {
         command
     };
@@ -99,7 +101,11 @@ fn test_lib(args: &[&str], test_name: &str) {
         panic!("stdout:\n{stdout}\nstderr:\n{stderr}");
     }
     let test_dll = format!("./{test_name}.dll");
-    let out = std::process::Command::new(RUSTC_CODEGEN_CLR_LINKER.display().to_string())
+    let mut linker = std::process::Command::new(RUSTC_CODEGEN_CLR_LINKER.display().to_string());
+    if *IS_MONO_PRESENT {
+        linker.env("EMULATE_INT128", "1");
+    }
+    let out = linker
         .current_dir("./test/out")
         .arg("-o")
         .arg(test_dll)
@@ -344,7 +350,14 @@ macro_rules! test_lib {
 }
 macro_rules! run_test {
     ($prefix:ident,$test_name:ident,$is_stable:ident) => {
-        mod $test_name {
+        run_test! {@impl $prefix,$test_name,$test_name,$is_stable,}
+    };
+    // Runs the test again, under the name `$variant`, with each of the config flags `$flag` set.
+    ($prefix:ident,$test_name:ident,$is_stable:ident,$variant:ident,$($flag:literal),+) => {
+        run_test! {@impl $prefix,$test_name,$variant,$is_stable,$($flag),+}
+    };
+    (@impl $prefix:ident,$test_name:ident,$variant:ident,$is_stable:ident,$($flag:literal),*) => {
+        mod $variant {
             mod $is_stable {
                 #[cfg(test)]
                 static COMPILE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
@@ -367,8 +380,9 @@ macro_rules! run_test {
                         .args([
                             concat!("./", stringify!($test_name), ".rs"),
                             "-o",
-                            concat!("./", stringify!($test_name), ".exe"),
+                            concat!("./", stringify!($variant), ".exe"),
                         ]);
+                    $(cmd.env($flag, "1");)*
                     eprintln!("Command: {cmd:?}");
                     let out = cmd.output().expect("failed to execute process");
                     // If stderr is not empty, then something went wrong, so print the stdout and stderr for debuging.
@@ -379,7 +393,7 @@ macro_rules! run_test {
                             .expect("rustc error contained non-UTF8 characters.");
                         panic!("stdout:\n{stdout}\nstderr:\n{stderr}");
                     }
-                    let exec_path = concat!("./", stringify!($test_name));
+                    let exec_path = concat!("./", stringify!($variant));
                     drop(lock);
                     let _ = super::super::test_dotnet_executable(exec_path, test_dir);
                 }
@@ -393,7 +407,7 @@ macro_rules! run_test {
                     super::super::RUSTC_BUILD_STATUS
                         .as_ref()
                         .expect("Could not build rustc!");
-                    let test_name = concat!("debug_", stringify!($test_name));
+                    let test_name = concat!("debug_", stringify!($variant));
                     // Compiles the test project
                     let mut out = std::process::Command::new("rustc");
                     //.env("RUST_TARGET_PATH","../../")
@@ -403,8 +417,9 @@ macro_rules! run_test {
                         .args([
                             concat!("./", stringify!($test_name), ".rs"),
                             "-o",
-                            concat!("./debug_", stringify!($test_name), ".exe"),
+                            concat!("./debug_", stringify!($variant), ".exe"),
                         ]);
+                    $(out.env($flag, "1");)*
                     // /eprintln!("out:{out:?}");
                     eprintln!("test_name:{test_name:?}");
                     let out = out.output().expect("failed to execute process");
@@ -502,6 +517,50 @@ macro_rules! cargo_test {
         }}
     };
 }
+/// Builds a cargo project using the backend, runs it, and checks it exited successfully, without writing anything to stderr.
+macro_rules! cargo_run_test {
+    ($test_name:ident,$is_stable:ident) => {
+        mod $test_name {
+            mod $is_stable {
+                #[cfg(test)]
+                static COMPILE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+                #[test]
+                fn cargo_run() {
+                    let lock = COMPILE_LOCK.lock();
+                    let test_dir = concat!("./cargo_tests/", stringify!($test_name), "/");
+                    let rustflags = super::super::cargo_build_env();
+                    let out = std::process::Command::new("cargo")
+                        .env("RUSTFLAGS", &rustflags)
+                        .current_dir(test_dir)
+                        .args(["build"])
+                        .output()
+                        .expect("failed to execute process");
+                    let stderr = String::from_utf8(out.stderr)
+                        .expect("rustc error contained non-UTF8 characters.");
+                    if !out.status.success() {
+                        let stdout = String::from_utf8(out.stdout)
+                            .expect("rustc error contained non-UTF8 characters.");
+                        panic!("stdout:\n{stdout}\nstderr:\n{stderr}");
+                    }
+                    // The bootstrap executable only unpacks the assembly if it is missing, so a stale one has to be removed.
+                    let exec_path = concat!("./target/debug/", stringify!($test_name));
+                    let _ = std::fs::remove_file(format!("{test_dir}{exec_path}.dll"));
+                    drop(lock);
+                    let out = std::process::Command::new(exec_path)
+                        .current_dir(test_dir)
+                        .output()
+                        .expect("failed to run the test program");
+                    let stderr = String::from_utf8(out.stderr).expect("stderr is not UTF8 String!");
+                    assert!(
+                        out.status.success() && stderr.is_empty(),
+                        "Test program failed with status {status} and message {stderr}",
+                        status = out.status
+                    );
+                }
+            }
+        }
+    };
+}
 macro_rules! cargo_test_ignored {
     ($test_name:ident) => {
         mod $test_name {
@@ -670,6 +729,7 @@ test_lib! {tuple,stable}
 run_test! {types,dst,stable}
 run_test! {types,dyns,stable}
 run_test! {types,maybeuninit,stable}
+run_test! {types,unions,stable}
 test_lib! {types,stable}
 
 run_test! {arthm,add,stable}
@@ -683,6 +743,7 @@ run_test! {arthm,xor,stable}
 run_test! {arthm,mul,stable}
 run_test! {arthm,greater_than,stable}
 run_test! {arthm,sub,stable}
+run_test! {arthm,float,stable}
 
 run_test! {types,tuple_structs,stable}
 run_test! {types,tuple_enum,stable}
@@ -691,7 +752,17 @@ run_test! {types,arr,stable}
 run_test! {types,adt_enum,stable}
 run_test! {types,nbody,stable}
 run_test! {types,structs,stable}
+// Declares the fields of all types in a shuffled order, and checks each field access resolves to the right offset.
+run_test! {types,structs,stable,structs_randomized,"RANDOMIZE_LAYOUT"}
+run_test! {types,enums,stable,enums_randomized,"RANDOMIZE_LAYOUT"}
+run_test! {types,projections,stable,projections_randomized,"RANDOMIZE_LAYOUT"}
+run_test! {types,delegates,stable,delegates_randomized,"RANDOMIZE_LAYOUT"}
+run_test! {types,async_types,stable,async_types_randomized,"RANDOMIZE_LAYOUT"}
+// Checks that the runtime lays out all types(including tuples) the same way rustc does.
+run_test! {types,projections,stable,projections_verified,"VERIFY_LAYOUT"}
 run_test! {types,interop,stable}
+run_test! {types,delegates,stable}
+run_test! {types,export,stable}
 run_test! {types,vec,stable}
 run_test! {types,subslice,stable}
 
@@ -702,17 +773,32 @@ run_test! {types,slice_index_ref,stable}
 run_test! {types,slice,stable}
 run_test! {types,slice_from_end,stable}
 run_test! {types,statics,stable}
-run_test! {types,async_types,unstable}
+run_test! {types,async_types,stable}
 run_test! {types,self_referential_statics,stable}
 run_test! {types,int128,stable}
+// Covers the builtin 128 bit integer types on CoreCLR too, not only when testing with Mono.
+run_test! {types,int128,stable,int128_emulated,"EMULATE_INT128"}
+run_test! {types,f16_f128,stable}
+run_test! {types,dyn_star,stable}
+run_test! {types,fn_ptr_cast,stable}
+run_test! {types,projections,stable}
+run_test! {types,aligned_locals,stable}
+run_test! {types,large_locals,stable}
+
+run_test! {abi,abi,stable}
 
 run_test! {std,main,stable}
+// `std` panics format their message using `core::fmt`, which does not fully work yet(see `std::format`). The unwinding itself is tested by `intrinsics::catch_unwind`.
+run_test! {std,catch_unwind_test,unstable}
 run_test! {std,futex_test,unstable}
 run_test! {std,futexrw_test,unstable}
 run_test! {std,tlocal_key_test,stable}
+run_test! {std,thread_local_test,stable}
 run_test! {std,cstr,unstable}
 run_test! {std,format,unstable}
 run_test! {std,cell_test,stable}
+run_test! {std,mstring_test,stable}
+run_test! {std,box_upcast,stable}
 run_test! {std,once_lock_test,unstable}
 run_test! {control_flow,cf_for,stable}
 run_test! {control_flow,drop,stable}
@@ -722,6 +808,8 @@ run_test! {intrinsics,bswap,stable}
 run_test! {intrinsics,assert,stable}
 run_test! {intrinsics,addr_of,stable}
 run_test! {intrinsics,atomics,stable}
+run_test! {intrinsics,catch_unwind,stable}
+run_test! {intrinsics,simd,stable}
 run_test! {intrinsics,alloc,stable}
 run_test! {intrinsics,malloc,stable}
 run_test! {intrinsics,offset_of,stable}
@@ -889,6 +977,8 @@ cargo_test_ignored! {build_std}
 cargo_test! {benchmarks,stable}
 cargo_test! {glam_test,unstable}
 cargo_test! {fastrand_test,stable}
+cargo_run_test! {gc_handle_test,stable}
+cargo_run_test! {managed_array_test,stable}
 
 use lazy_static::lazy_static;
 /// Cached runtime configuration string, obtained from calling the .NET runtime.
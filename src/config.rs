@@ -43,11 +43,13 @@ config_flag! {OPTIMIZE_CIL,true,"Tells the codegen to optmize the emiited CIL."}
 config_flag! {ESCAPE_NAMES,false,"Tells the codegen to escape class and method names."}
 
 config_flag! {TEST_WITH_MONO,false,"Tells the codegen to use the mono runtime for tests."}
+config_flag! {EMULATE_INT128,*TEST_WITH_MONO && !*C_MODE,"Tells the codegen to implement 128 bit integers(and checked 64 bit multiplication, which relies on them) using builtin types, instead of `System.Int128` and `System.UInt128`. Required by runtimes lacking those types, like Mono. Enabled by default when testing with Mono. The linker refuses to run Mono AOT without it."}
 
 config_flag! {C_MODE,false,"Tells the codegen to emmit C source files."}
+config_flag! {VARARG_CALLS,cfg!(windows) || *C_MODE,"Tells the codegen to lower calls to variadic foreign functions(like `printf`) to `vararg` call sites. The .NET runtime supports those only on Windows, so by default, calls to variadic functions elsewhere use a non-variadic signature matching the passed arguments."}
 config_flag! {C_SANITIZE,false,"Tells the codegen sanitize C."}
 
-config_flag! {RANDOMIZE_LAYOUT,false,"Tells the codegen to randomize TEST type layout."}
+config_flag! {RANDOMIZE_LAYOUT,false,"Tells the codegen to randomize type layout. Test crates are built with `-Z randomize-layout`, and the fields of all emitted types with explicit offsets(except `#[repr(C)]` ones) are declared in a shuffled order, to catch code assuming a certain field order."}
 config_flag! {NATIVE_PASSTROUGH,false,"Tells the codegen compile linked static libraries into a shared library, which will be bundled with the .NET executable."}
 
 config_flag! {ENFORCE_CIL_VALID,false,"Tells the codegen to preform additonal checks before saving the ."}
@@ -56,8 +58,10 @@ config_flag! {PRINT_PTRS,false,"Tells codegen the print each pointer it derefere
 config_flag! {CHECK_REFS,false,"Tells codegen to check if references it assigns are valid."}
 config_flag! {MEM_CHECKS,false,"Tells codegen to insert memory consistency checks after each call. If INSERT_MIR_DEBUG_COMMENTS is enabled, the consistency checks will be run also after each MIR statement."}
 config_flag! {TYPECHECK_CIL,false,"Checks the geneareted CIL for type safety."}
+config_flag! {VERIFY_LAYOUT,false,"Tells the linker to emit a check, ran on startup, comparing the size and field offsets of all types with explicit layout with the ones computed by rustc, printing all mismatches, and throwing if there were any."}
 
 config_flag! {TRACE_CIL_OPS,false,"Tells the print each CIL op before it is executed."}
+config_flag! {LIST_UNSUPPORTED_INTRINSICS,false,"Tells the codegen to print all intrinsics exposed by `core`, which it does not support yet."}
 
 lazy_static! {
     #[doc = "Specifies the path to the IL assembler."]
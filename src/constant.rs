@@ -225,7 +225,16 @@ fn load_scalar_ptr(
                 CallSite::new(None, function_name, call_info.sig().clone(), true).into(),
             );
         }
-        _ => todo!("Unhandled global alloc {global_alloc:?}"),
+        GlobalAlloc::VTable(ty, trait_ref) => {
+            let vtable_alloc = tyctx.vtable_allocation((ty, trait_ref));
+            CILNode::Add(
+                CILNode::LoadGlobalAllocPtr {
+                    alloc_id: crate::utilis::alloc_id_to_u64(vtable_alloc),
+                }
+                .into(),
+                CILNode::ZeroExtendToUSize(CILNode::LdcU64(offset.bytes()).into()).into(),
+            )
+        }
     }
     //panic!("alloc_id:{alloc_id:?}")
 }
@@ -300,7 +309,17 @@ fn load_const_scalar<'ctx>(
 }
 fn load_const_float(value: u128, float_type: &FloatTy, _tyctx: TyCtxt) -> CILNode {
     match float_type {
-        FloatTy::F16 => todo!("Can't hanlde 16 bit floats yet!"),
+        // .NET has no constants of those types, so they must be loaded from memory.
+        FloatTy::F16 | FloatTy::F128 => {
+            let tpe: Type = float_type.into();
+            CILNode::LdObj {
+                ptr: Box::new(CILNode::TransmutePtr {
+                    val: Box::new(CILNode::PointerToConstValue(value)),
+                    new_ptr: Box::new(Type::Ptr(Box::new(tpe.clone()))),
+                }),
+                obj: tpe.into(),
+            }
+        }
         FloatTy::F32 => {
             let value = f32::from_ne_bytes((value as u32).to_ne_bytes());
             CILNode::LdcF32(value)
@@ -309,7 +328,6 @@ fn load_const_float(value: u128, float_type: &FloatTy, _tyctx: TyCtxt) -> CILNod
             let value = f64::from_ne_bytes((value as u64).to_ne_bytes());
             CILNode::LdcF64(value)
         }
-        FloatTy::F128 => todo!("Can't hanlde 128 bit floats yet!"),
     }
 }
 pub fn load_const_int(value: u128, int_type: &IntTy) -> CILNode {
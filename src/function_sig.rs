@@ -2,7 +2,9 @@ use crate::{
     codegen_error::CodegenError,
     r#type::{TyCache, Type},
 };
-use rustc_middle::ty::{Instance, List, ParamEnv, ParamEnvAnd, PolyFnSig, Ty, TyCtxt, TyKind};
+use rustc_middle::ty::{
+    Instance, InstanceDef, List, ParamEnv, ParamEnvAnd, PolyFnSig, Ty, TyCtxt, TyKind,
+};
 use rustc_target::abi::call::Conv;
 use rustc_target::spec::abi::Abi as TargetAbi;
 use serde::{Deserialize, Serialize};
@@ -49,12 +51,7 @@ impl FnSig {
             Ok(abi) => abi,
             Err(_error) => todo!(),
         };
-        let conv = fn_abi.conv;
-        match conv {
-            Conv::Rust => (),
-            Conv::C => (),
-            _ => panic!("ERROR:calling using convention {conv:?} is not supported!"),
-        }
+        assert_conv_supported(fn_abi.conv);
         //assert!(!fn_abi.c_variadic);
         let ret = crate::utilis::monomorphize(&function, fn_abi.ret.layout.ty, tyctx);
         let ret = tycache.type_from_cache(ret, tyctx, Some(function));
@@ -63,26 +60,7 @@ impl FnSig {
             let arg = crate::utilis::monomorphize(&function, arg.layout.ty, tyctx);
             args.push(tycache.type_from_cache(arg, tyctx, Some(function)));
         }
-        // There are 2 ABI enums for some reasons(they differ in what memebers they have)
-        let fn_ty = function.ty(tyctx, ParamEnv::reveal_all());
-        let internal_abi = match fn_ty.kind() {
-            TyKind::FnDef(_, _) => fn_ty.fn_sig(tyctx),
-            TyKind::Closure(_, args) => args.as_closure().sig(),
-            _ => todo!("Can't get signature of {fn_ty}"),
-        }
-        .abi();
-        // Only those ABIs are supported
-        match internal_abi {
-            TargetAbi::C { unwind: _ } => (),
-            TargetAbi::Cdecl { unwind: _ } => (),
-            TargetAbi::RustIntrinsic => (),
-            TargetAbi::Rust => (),
-            TargetAbi::RustCold => (),
-            TargetAbi::RustCall => (), /*Err(CodegenError::FunctionABIUnsuported(
-            "\"rust_call\" ABI, used for things like clsoures, is not supported yet!",
-            ))?,*/
-            _ => todo!("Unsuported ABI:{internal_abi:?}"),
-        }
+        assert_abi_supported(instance_abi(function, tyctx));
         Ok(Self {
             inputs: args,
             output: ret,
@@ -115,6 +93,70 @@ impl FnSig {
         &mut self.inputs
     }
 }
+/// Returns the ABI `function` is declared with.
+/// There are 2 ABI enums for some reasons(they differ in what memebers they have), and this returns the one used in declarations.
+pub(crate) fn instance_abi<'tyctx>(function: Instance<'tyctx>, tyctx: TyCtxt<'tyctx>) -> TargetAbi {
+    let fn_ty = function.ty(tyctx, ParamEnv::reveal_all());
+    match fn_ty.kind() {
+        // Thread local shims have the type of the static, but always use the Rust ABI.
+        _ if matches!(function.def, InstanceDef::ThreadLocalShim(_)) => TargetAbi::Rust,
+        TyKind::FnDef(_, _) => fn_ty.fn_sig(tyctx).abi(),
+        TyKind::Closure(_, args) => args.as_closure().sig().abi(),
+        // Coroutine resume functions always use the Rust ABI.
+        TyKind::Coroutine(_, _) => TargetAbi::Rust,
+        _ => todo!("Can't get signature of {fn_ty}"),
+    }
+}
+/// Checks if the last argument of a function using `abi` is a tuple, which has to be split into separate arguments("rust-call").
+/// # Panics
+/// Panics if `abi` is not supported.
+pub(crate) fn abi_splits_last_tuple(abi: TargetAbi) -> bool {
+    assert_abi_supported(abi);
+    matches!(abi, TargetAbi::RustCall)
+}
+/// Checks that a function declared with `abi` can be called from .NET.
+/// # Panics
+/// Panics if `abi` is not supported.
+pub(crate) fn assert_abi_supported(abi: TargetAbi) {
+    match abi {
+        TargetAbi::Rust
+        | TargetAbi::RustCall
+        | TargetAbi::RustCold
+        | TargetAbi::RustIntrinsic
+        | TargetAbi::PlatformIntrinsic
+        | TargetAbi::Unadjusted => (),
+        // .NET has only one native calling convention per platform, so all of those are aliases of it.
+        TargetAbi::C { unwind: _ }
+        | TargetAbi::Cdecl { unwind: _ }
+        | TargetAbi::System { unwind: _ }
+        | TargetAbi::SysV64 { unwind: _ }
+        | TargetAbi::Win64 { unwind: _ }
+        | TargetAbi::Stdcall { unwind: _ }
+        | TargetAbi::Fastcall { unwind: _ }
+        | TargetAbi::Vectorcall { unwind: _ }
+        | TargetAbi::Thiscall { unwind: _ }
+        | TargetAbi::Aapcs { unwind: _ }
+        | TargetAbi::EfiApi => (),
+        _ => todo!("Unsuported ABI:{abi:?}"),
+    }
+}
+/// Checks that a function using the calling convention `conv` can be called from .NET.
+/// # Panics
+/// Panics if `conv` is not supported.
+pub(crate) fn assert_conv_supported(conv: Conv) {
+    match conv {
+        Conv::Rust | Conv::Cold | Conv::C => (),
+        // The runtime uses the native calling convention of the platform for all of those.
+        Conv::X86_64SysV
+        | Conv::X86_64Win64
+        | Conv::X86Stdcall
+        | Conv::X86Fastcall
+        | Conv::X86VectorCall
+        | Conv::X86ThisCall
+        | Conv::ArmAapcs => (),
+        _ => panic!("ERROR:calling using convention {conv:?} is not supported!"),
+    }
+}
 /// Checks if this function is variadic.
 #[must_use]
 pub fn is_fn_variadic<'tyctx>(ty: Ty<'tyctx>, tyctx: TyCtxt<'tyctx>) -> bool {
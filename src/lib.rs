@@ -132,7 +132,8 @@ mod entrypoint;
 pub mod function_sig;
 /// Interop type handling.
 mod interop;
-mod verify;
+/// Self-checks of the final assembly, ran when it starts.
+pub mod verify;
 //
 
 /// A representation of a .NET method
@@ -207,6 +208,11 @@ impl CodegenBackend for MyBackend {
     ) -> Box<dyn Any> {
         {
             let (_defid_set, cgus) = tcx.collect_and_partition_mono_items(());
+            if *crate::config::LIST_UNSUPPORTED_INTRINSICS {
+                for name in terminator::intrinsics::unsupported_intrinsics(tcx) {
+                    tcx.dcx().note(format!("unsupported intrinsic `{name}`"));
+                }
+            }
 
             let mut codegen = Assembly::empty();
             let mut cache = crate::r#type::TyCache::empty();
@@ -13,7 +13,11 @@ use rustc_middle::{
     mir::PlaceElem,
     ty::{Instance, Ty, TyCtxt, TyKind},
 };
-pub fn local_adress(local: usize, method: &rustc_middle::mir::Body) -> CILNode {
+pub fn local_adress(
+    local: usize,
+    method: &rustc_middle::mir::Body,
+    type_cache: &TyCache,
+) -> CILNode {
     if let Some(spread_arg) = method.spread_arg
         && local == spread_arg.as_usize()
     {
@@ -23,6 +27,9 @@ pub fn local_adress(local: usize, method: &rustc_middle::mir::Body) -> CILNode {
                 .unwrap(),
         )));
     }
+    if let Some(ptr) = type_cache.aligned_local_ptr(local) {
+        return CILNode::LDLoc(ptr);
+    }
     if local == 0 {
         CILNode::MRefToRawPtr(CILNode::LDLocA(0).into())
     } else if local > method.arg_count {
@@ -165,35 +172,6 @@ pub fn place_elem_adress<'ctx>(
                 }
             }
         },
-        /*
-        PlaceElem::Downcast(symbol, _) => {
-            let curr_type = curr_type
-                .as_ty()
-                .expect("Can't get enum variant of an enum varaint!");
-            let curr_type = crate::utilis::monomorphize(&method_instance, curr_type, tyctx);
-            let curr_dotnet_type =
-                type_cache.type_from_cache(curr_type, tyctx, Some(method_instance));
-            let curr_dotnet_type =
-                if let crate::r#type::Type::DotnetType(dotnet_type) = curr_dotnet_type {
-                    dotnet_type.as_ref().clone()
-                } else {
-                    panic!();
-                };
-            let variant_name = symbol.unwrap();
-            let field_name = format!("v_{variant_name}").into();
-            let _curr_type_name = (curr_dotnet_type).name_path();
-            let mut field_type = curr_dotnet_type.clone();
-            field_type.append_path(&format!("/{variant_name}"));
-            let field_desc = FieldDescriptor::boxed(
-                curr_dotnet_type.clone(),
-                crate::r#type::Type::DotnetType(Box::new(field_type)),
-                field_name,
-            );
-            CILNode::LDFieldAdress {
-                addr: addr_calc.into(),
-                field: field_desc,
-            }
-        }*/
         PlaceElem::Index(index) => {
             let curr_ty = curr_type
                 .as_ty()
@@ -248,70 +226,39 @@ pub fn place_elem_adress<'ctx>(
             }
         }
         PlaceElem::Subslice { from, to, from_end } => {
-            let curr_type = crate::r#type::tycache::slice_ref_to(
-                tyctx,
-                type_cache,
-                curr_type.as_ty().expect("Can't index into an enum!"),
-                Some(method_instance),
-            );
-            let curr_dotnet = curr_type.as_dotnet().unwrap();
-            if *from_end {
-                let metadata_field =
-                    FieldDescriptor::new(curr_dotnet.clone(), Type::USize, "metadata".into());
-                let ptr_field = FieldDescriptor::new(
-                    curr_dotnet.clone(),
-                    Type::Ptr(Type::Void.into()),
-                    "data_pointer".into(),
-                );
-                CILNode::TemporaryLocal(Box::new((
-                    curr_type,
-                    [
-                        CILRoot::SetField {
-                            addr: CILNode::LoadAddresOfTMPLocal,
-                            value: CILNode::Sub(
-                                Box::new(ld_field!(addr_calc.clone(), metadata_field.clone())),
-                                Box::new(conv_usize!(ldc_u64!(*to + 1))),
-                            ),
-                            desc: metadata_field,
-                        },
-                        CILRoot::SetField {
-                            addr: CILNode::LoadAddresOfTMPLocal,
-                            value: ld_field!(addr_calc, ptr_field.clone())
-                                + conv_usize!(ldc_u64!(*from)),
-                            desc: ptr_field.clone(),
-                        },
-                    ]
-                    .into(),
-                    CILNode::LoadTMPLocal,
-                )))
-            } else {
-                let metadata_field =
-                    FieldDescriptor::new(curr_dotnet.clone(), Type::USize, "metadata".into());
-                let ptr_field = FieldDescriptor::new(
-                    curr_dotnet.clone(),
-                    Type::Ptr(Type::Void.into()),
-                    "data_pointer".into(),
-                );
-                CILNode::TemporaryLocal(Box::new((
-                    curr_type,
-                    [
-                        CILRoot::SetField {
-                            addr: CILNode::LoadAddresOfTMPLocal,
-                            value: conv_usize!(ldc_u64!(to - from)),
-                            desc: metadata_field,
-                        },
-                        CILRoot::SetField {
-                            addr: CILNode::LoadAddresOfTMPLocal,
-                            value: ld_field!(addr_calc, ptr_field.clone())
-                                + conv_usize!(ldc_u64!(*from)),
-                            desc: ptr_field.clone(),
-                        },
-                    ]
-                    .into(),
-                    CILNode::LoadTMPLocal,
-                )))
+            let curr_ty = curr_type
+                .as_ty()
+                .expect("INVALID PLACE: Subslicing an enum variant???");
+            match curr_ty.kind() {
+                TyKind::Slice(inner) => {
+                    let inner = crate::utilis::monomorphize(&method_instance, *inner, tyctx);
+                    super::subslice(
+                        inner,
+                        *from,
+                        *to,
+                        *from_end,
+                        tyctx,
+                        &method_instance,
+                        type_cache,
+                        addr_calc,
+                    )
+                }
+                TyKind::Array(_, _) => super::subarray_adress(
+                    curr_ty,
+                    place_ty,
+                    *from,
+                    tyctx,
+                    &method_instance,
+                    type_cache,
+                    addr_calc,
+                ),
+                _ => {
+                    rustc_middle::ty::print::with_no_trimmed_paths! {todo!("Can't subslice {curr_ty}!")}
+                }
             }
         }
+        // An enum variant has the same address as the enum it belongs to.
+        PlaceElem::Downcast(_, _) => addr_calc,
         PlaceElem::ConstantIndex {
             offset,
             min_length,
@@ -368,26 +315,29 @@ pub fn place_elem_adress<'ctx>(
                     let array_type =
                         type_cache.type_from_cache(curr_ty, tyctx, Some(method_instance));
                     let array_dotnet = array_type.as_dotnet().expect("Non array type");
-                    if *from_end {
-                        todo!("Can't index array from end!");
-                    } else {
-                        CILNode::Call {
-                            site: crate::cil::CallSite::new(
-                                Some(array_dotnet),
-                                "get_Address".into(),
-                                FnSig::new(
-                                    &[Type::Ptr(array_type.into()), Type::USize],
-                                    &Type::Ptr(element.into()),
-                                ),
-                                false,
-                            )
-                            .into(),
-                            args: [
-                                addr_calc,
-                                CILNode::ZeroExtendToUSize(ldc_u64!(*offset).into()),
-                            ]
-                            .into(),
-                        }
+                    let index = super::array_const_index(
+                        curr_ty,
+                        *offset,
+                        *from_end,
+                        tyctx,
+                        &method_instance,
+                    );
+                    CILNode::Call {
+                        site: crate::cil::CallSite::new(
+                            Some(array_dotnet),
+                            "get_Address".into(),
+                            FnSig::new(
+                                &[Type::Ptr(array_type.into()), Type::USize],
+                                &Type::Ptr(element.into()),
+                            ),
+                            false,
+                        )
+                        .into(),
+                        args: [
+                            addr_calc,
+                            CILNode::ZeroExtendToUSize(ldc_u64!(index).into()),
+                        ]
+                        .into(),
                     }
                 }
                 _ => {
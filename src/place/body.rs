@@ -15,13 +15,20 @@ pub fn local_body<'tcx>(
     method: &rustc_middle::mir::Body<'tcx>,
     tyctx: TyCtxt<'tcx>,
     method_instance: &Instance<'tcx>,
+    type_cache: &mut crate::r#type::TyCache,
 ) -> (CILNode, Ty<'tcx>) {
     let ty = method.local_decls[local.into()].ty;
     let ty = crate::utilis::monomorphize(method_instance, ty, tyctx);
     if body_ty_is_by_adress(ty) {
-        (super::adress::local_adress(local, method), ty)
+        (
+            super::adress::local_adress(local, method, type_cache),
+            ty,
+        )
     } else {
-        (super::get::local_get(local, method), ty)
+        (
+            super::get::local_get_aligned(local, method, tyctx, method_instance, type_cache),
+            ty,
+        )
     }
 }
 pub fn place_elem_body<'ctx>(
@@ -253,8 +260,6 @@ pub fn place_elem_body<'ctx>(
             let curr_ty = curr_ty
                 .as_ty()
                 .expect("INVALID PLACE: Indexing into enum variant???");
-            let index = CILNode::LdcU64(*offset);
-            assert!(!from_end);
             match curr_ty.kind() {
                 TyKind::Slice(inner) => {
                     let inner = crate::utilis::monomorphize(&method_instance, *inner, tyctx);
@@ -269,7 +274,10 @@ pub fn place_elem_body<'ctx>(
                         Type::Ptr(Type::Void.into()),
                         "data_pointer".into(),
                     );
-                    let metadata = FieldDescriptor::new(slice, Type::USize, "metadata".into());
+                    let metadata =
+                        FieldDescriptor::new(slice.clone(), Type::USize, "metadata".into());
+                    let index =
+                        super::slice_const_index(*offset, *from_end, parrent_node.clone(), slice);
                     let addr = ld_field!(parrent_node.clone(), desc)
                         + call!(
                             CallSite::builtin(
@@ -301,6 +309,13 @@ pub fn place_elem_body<'ctx>(
                     let array_type =
                         type_cache.type_from_cache(curr_ty, tyctx, Some(method_instance));
                     let array_dotnet = array_type.as_dotnet().expect("Non array type");
+                    let index = CILNode::LdcU64(super::array_const_index(
+                        curr_ty,
+                        *offset,
+                        *from_end,
+                        tyctx,
+                        &method_instance,
+                    ));
                     if body_ty_is_by_adress(element_ty) {
                         let ops = CILNode::Call {
                             site: crate::cil::CallSite::new(
@@ -335,6 +350,50 @@ pub fn place_elem_body<'ctx>(
                 }
             }
         }
-        _ => todo!("Can't handle porojection {place_elem:?} in body"),
+        PlaceElem::Subslice { from, to, from_end } => {
+            let curr_ty = curr_ty
+                .as_ty()
+                .expect("INVALID PLACE: Subslicing an enum variant???");
+            match curr_ty.kind() {
+                TyKind::Slice(inner) => {
+                    let inner = crate::utilis::monomorphize(&method_instance, *inner, tyctx);
+                    let subslice = super::subslice(
+                        inner,
+                        *from,
+                        *to,
+                        *from_end,
+                        tyctx,
+                        &method_instance,
+                        type_cache,
+                        parrent_node,
+                    );
+                    (curr_ty.into(), subslice)
+                }
+                TyKind::Array(element, _) => {
+                    // Arrays are always subsliced using `array[from..to]`.
+                    assert!(!from_end, "Subslicing an array from end!");
+                    let element = crate::utilis::monomorphize(&method_instance, *element, tyctx);
+                    let subarray = Ty::new_array(tyctx, element, to - from);
+                    let addr = super::subarray_adress(
+                        curr_ty,
+                        subarray,
+                        *from,
+                        tyctx,
+                        &method_instance,
+                        type_cache,
+                        parrent_node,
+                    );
+                    (subarray.into(), addr)
+                }
+                _ => {
+                    rustc_middle::ty::print::with_no_trimmed_paths! { todo!("Can't subslice {curr_ty}!")}
+                }
+            }
+        }
+        // Those projections only change the type of a place, and not its representation.
+        PlaceElem::OpaqueCast(ty) | PlaceElem::Subtype(ty) => (
+            crate::utilis::monomorphize(&method_instance, *ty, tyctx).into(),
+            parrent_node,
+        ),
     }
 }
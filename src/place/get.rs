@@ -5,7 +5,7 @@ use crate::r#type::Type;
 use crate::{call, conv_usize, ld_field};
 
 use rustc_middle::mir::{Place, PlaceElem};
-use rustc_middle::ty::{Instance, TyCtxt, TyKind};
+use rustc_middle::ty::{Instance, Ty, TyCtxt, TyKind};
 
 pub(super) fn local_get(local: usize, method: &rustc_middle::mir::Body) -> CILNode {
     if let Some(spread_arg) = method.spread_arg
@@ -28,6 +28,24 @@ pub(super) fn local_get(local: usize, method: &rustc_middle::mir::Body) -> CILNo
         CILNode::LDArg(u32::try_from(local - 1).expect("Method has more than 2^32 local variables"))
     }
 }
+/// Returns the value of `local`. Over-aligned locals are loaded from their aligned storage.
+pub(crate) fn local_get_aligned<'tcx>(
+    local: usize,
+    method: &rustc_middle::mir::Body<'tcx>,
+    tyctx: TyCtxt<'tcx>,
+    method_instance: &Instance<'tcx>,
+    type_cache: &mut crate::r#type::TyCache,
+) -> CILNode {
+    let Some(ptr) = type_cache.aligned_local_ptr(local) else {
+        return local_get(local, method);
+    };
+    let ty =
+        crate::utilis::monomorphize(method_instance, method.local_decls[local.into()].ty, tyctx);
+    CILNode::LdObj {
+        ptr: Box::new(CILNode::LDLoc(ptr)),
+        obj: Box::new(type_cache.type_from_cache(ty, tyctx, Some(*method_instance))),
+    }
+}
 /// Returns the ops for getting the value of place.
 pub fn place_get<'tyctx>(
     place: &Place<'tyctx>,
@@ -36,15 +54,27 @@ pub fn place_get<'tyctx>(
     method_instance: Instance<'tyctx>,
     type_cache: &mut crate::r#type::TyCache,
 ) -> CILNode {
-    if place.projection.is_empty() {
-        local_get(place.local.as_usize(), method)
+    let projection = super::projection(place);
+    if projection.is_empty() {
+        local_get_aligned(
+            place.local.as_usize(),
+            method,
+            tyctx,
+            &method_instance,
+            type_cache,
+        )
     } else {
-        let (mut op, mut ty) =
-            super::local_body(place.local.as_usize(), method, tyctx, &method_instance);
+        let (mut op, mut ty) = super::local_body(
+            place.local.as_usize(),
+            method,
+            tyctx,
+            &method_instance,
+            type_cache,
+        );
         ty = crate::utilis::monomorphize(&method_instance, ty, tyctx);
         let mut ty = ty.into();
 
-        let (head, body) = super::slice_head(place.projection);
+        let (head, body) = super::slice_head(projection);
         for elem in body {
             let (curr_ty, curr_ops) =
                 super::place_elem_body(elem, ty, tyctx, method_instance, method, type_cache, op);
@@ -181,8 +211,6 @@ fn place_elem_get<'a>(
             let curr_ty = curr_type
                 .as_ty()
                 .expect("INVALID PLACE: Indexing into enum variant???");
-            let index = CILNode::LdcU64(*offset);
-            assert!(!from_end, "Indexing slice form end");
             match curr_ty.kind() {
                 TyKind::Slice(inner) => {
                     let inner = crate::utilis::monomorphize(&method_instance, *inner, tyctx);
@@ -197,8 +225,10 @@ fn place_elem_get<'a>(
                         Type::Ptr(Type::Void.into()),
                         "data_pointer".into(),
                     );
-                    let metadata = FieldDescriptor::new(slice, Type::USize, "metadata".into());
-
+                    let metadata =
+                        FieldDescriptor::new(slice.clone(), Type::USize, "metadata".into());
+                    let index =
+                        super::slice_const_index(*offset, *from_end, addr_calc.clone(), slice);
                     let addr = ld_field!(addr_calc.clone(), data_pointer)
                         + call!(
                             CallSite::new(
@@ -224,6 +254,13 @@ fn place_elem_get<'a>(
                         type_cache.type_from_cache(curr_ty, tyctx, Some(method_instance));
                     let array_dotnet = array_type.as_dotnet().expect("Non array type");
                     //eprintln!("WARNING: ConstantIndex has required min_length of {min_length}, but bounds checking on const access not supported yet!");
+                    let index = CILNode::LdcU64(super::array_const_index(
+                        curr_ty,
+                        *offset,
+                        *from_end,
+                        tyctx,
+                        &method_instance,
+                    ));
                     CILNode::Call {
                         site: crate::cil::CallSite::new(
                             Some(array_dotnet),
@@ -240,6 +277,32 @@ fn place_elem_get<'a>(
                 }
             }
         }
+        PlaceElem::Subslice { from, to, from_end } => {
+            let curr_ty = curr_type
+                .as_ty()
+                .expect("INVALID PLACE: Subslicing an enum variant???");
+            // Only a subslice of an array is sized, and can be loaded.
+            let TyKind::Array(element, _) = curr_ty.kind() else {
+                rustc_middle::ty::print::with_no_trimmed_paths! { todo!("Can't get a subslice of {curr_ty}!")}
+            };
+            assert!(!from_end, "Subslicing an array from end!");
+            let element = crate::utilis::monomorphize(&method_instance, *element, tyctx);
+            let subarray = Ty::new_array(tyctx, element, to - from);
+            let addr = super::subarray_adress(
+                curr_ty,
+                subarray,
+                *from,
+                tyctx,
+                &method_instance,
+                type_cache,
+                addr_calc,
+            );
+            super::deref_op(subarray.into(), tyctx, &method_instance, type_cache, addr)
+        }
+        // A whole enum variant is loaded as the enum it belongs to.
+        PlaceElem::Downcast(_, _) => {
+            super::deref_op(curr_type, tyctx, &method_instance, type_cache, addr_calc)
+        }
         _ => todo!("Can't handle porojection {place_elem:?} in get"),
     }
 }
@@ -1,10 +1,11 @@
 // FIXME: This file may contain unnecesary morphize calls.
+use crate::cil::FieldDescriptor;
 use crate::cil_tree::cil_node::CILNode;
 use crate::cil_tree::cil_root::CILRoot;
-use crate::r#type::{pointer_to_is_fat, DotnetTypeRef};
-use crate::{conv_usize, ldc_u64};
+use crate::r#type::{pointer_to_is_fat, DotnetTypeRef, Type};
+use crate::{conv_usize, ld_field, ldc_u64, size_of};
 
-use rustc_middle::mir::Place;
+use rustc_middle::mir::{Place, PlaceElem};
 
 mod adress;
 mod body;
@@ -13,8 +14,62 @@ mod set;
 pub use adress::*;
 pub use body::*;
 pub use get::*;
-use rustc_middle::ty::{FloatTy, Instance, IntTy, ParamEnv, Ty, TyCtxt, TyKind, UintTy};
+use rustc_middle::ty::{DynKind, FloatTy, Instance, IntTy, ParamEnv, Ty, TyCtxt, TyKind, UintTy};
 pub use set::*;
+/// Returns the projections of `place`, without the trailing `OpaqueCast`s and `Subtype`s.
+/// Those projections only change the type of a place, and not its representation, so they can be skipped.
+fn projection<'tcx>(place: &Place<'tcx>) -> &'tcx [PlaceElem<'tcx>] {
+    let mut projection: &'tcx [PlaceElem<'tcx>] = place.projection;
+    while let Some((PlaceElem::OpaqueCast(_) | PlaceElem::Subtype(_), rest)) =
+        projection.split_last()
+    {
+        projection = rest;
+    }
+    projection
+}
+/// Size(in bytes) of the biggest temporary stored in a .NET local. The JIT may zero-initialize value-type locals, so bigger temporaries are stored in stack buffers instead.
+const MAX_LOCAL_SIZE: u64 = 1024;
+/// Returns the locals of `method` stored in stack buffers, pointed to by additional locals, placed after all other locals of the method.
+/// Those are the locals requiring an aligement greater than the one .NET guarantees(together with that aligement), and temporaries bigger than [`MAX_LOCAL_SIZE`],
+/// which don't contain references to GC-managed objects.
+/// This computes the layout of every local, so it should be called once per method. Places look the pointers up in [`crate::r#type::TyCache::aligned_local_ptr`].
+pub(crate) fn stack_buffer_locals<'tcx>(
+    method: &rustc_middle::mir::Body<'tcx>,
+    tyctx: TyCtxt<'tcx>,
+    method_instance: &Instance<'tcx>,
+    type_cache: &mut crate::r#type::TyCache,
+) -> Vec<(usize, Option<u64>)> {
+    method
+        .local_decls
+        .iter_enumerated()
+        // The spread argument is always stored in a separate local, created by the codegen.
+        .filter(|(local, _)| Some(*local) != method.spread_arg)
+        .filter_map(|(local, decl)| {
+            let ty = crate::utilis::monomorphize(method_instance, decl.ty, tyctx);
+            if crate::utilis::is_zst(ty, tyctx) {
+                return None;
+            }
+            if let Some(align) = crate::utilis::requries_align_adjustement(ty, tyctx) {
+                return Some((local.as_usize(), Some(align)));
+            }
+            // Arguments and the return value are passed by value, so moving them would only add a copy.
+            if local.as_usize() <= method.arg_count {
+                return None;
+            }
+            let size = tyctx
+                .layout_of(ParamEnv::reveal_all().and(ty))
+                .expect("Can't get layout of a type.")
+                .size
+                .bytes();
+            if size <= MAX_LOCAL_SIZE {
+                return None;
+            }
+            // The GC does not track references stored in stack buffers.
+            let tpe = type_cache.type_from_cache(ty, tyctx, Some(*method_instance));
+            (!type_cache.contains_gcref(&tpe)).then_some((local.as_usize(), None))
+        })
+        .collect()
+}
 fn slice_head<T>(slice: &[T]) -> (&T, &[T]) {
     assert!(!slice.is_empty());
     let last = &slice[slice.len() - 1];
@@ -42,15 +97,18 @@ fn body_ty_is_by_adress(last_ty: Ty) -> bool {
         //TODO: check if slices are handled propely
         TyKind::Adt(_, _)
         | TyKind::Closure(_, _)
+        | TyKind::Coroutine(_, _)
         | TyKind::Array(_, _)
         | TyKind::Slice(_)
-        | TyKind::Str => true,
+        | TyKind::Str
+        | TyKind::Dynamic(_, _, _) => true,
 
         TyKind::Int(_)
         | TyKind::Float(_)
         | TyKind::Uint(_)
         | TyKind::Ref(_, _, _)
         | TyKind::RawPtr(_, _)
+        | TyKind::FnPtr(_)
         | TyKind::Bool
         | TyKind::Char => false,
 
@@ -60,6 +118,104 @@ fn body_ty_is_by_adress(last_ty: Ty) -> bool {
         ),
     }
 }
+/// Returns the index of the array element selected by [`PlaceElem::ConstantIndex`].
+fn array_const_index<'ctx>(
+    array: Ty<'ctx>,
+    offset: u64,
+    from_end: bool,
+    tyctx: TyCtxt<'ctx>,
+    method_instance: &Instance<'ctx>,
+) -> u64 {
+    if !from_end {
+        return offset;
+    }
+    let TyKind::Array(_, length) = array.kind() else {
+        panic!("{array:?} is not an array!");
+    };
+    let length = crate::utilis::monomorphize(method_instance, *length, tyctx);
+    let length = crate::utilis::try_resolve_const_size(length).unwrap() as u64;
+    length - offset
+}
+/// Returns the index of the slice element selected by [`PlaceElem::ConstantIndex`]. `slice` is the fat pointer to the slice.
+fn slice_const_index(
+    offset: u64,
+    from_end: bool,
+    slice: CILNode,
+    slice_dotnet: crate::r#type::DotnetTypeRef,
+) -> CILNode {
+    if from_end {
+        let metadata = FieldDescriptor::new(slice_dotnet, Type::USize, "metadata".into());
+        ld_field!(slice, metadata) - conv_usize!(ldc_u64!(offset))
+    } else {
+        conv_usize!(ldc_u64!(offset))
+    }
+}
+/// Returns a fat pointer to the subslice selected by [`PlaceElem::Subslice`] of a slice of `element`s, pointed to by `slice`.
+fn subslice<'ctx>(
+    element: Ty<'ctx>,
+    from: u64,
+    to: u64,
+    from_end: bool,
+    tyctx: TyCtxt<'ctx>,
+    method_instance: &Instance<'ctx>,
+    type_cache: &mut crate::r#type::TyCache,
+    slice: CILNode,
+) -> CILNode {
+    let element_type = type_cache.type_from_cache(element, tyctx, Some(*method_instance));
+    let slice_type = type_cache.slice_ty(element, tyctx, Some(*method_instance));
+    let slice_dotnet = slice_type.as_dotnet().unwrap();
+    let metadata_field = FieldDescriptor::new(slice_dotnet.clone(), Type::USize, "metadata".into());
+    let ptr_field = FieldDescriptor::new(
+        slice_dotnet,
+        Type::Ptr(Type::Void.into()),
+        "data_pointer".into(),
+    );
+    // `slice[from..(slice.len() - to)]` if `from_end` is set, `slice[from..to]` otherwise.
+    let length = if from_end {
+        ld_field!(slice.clone(), metadata_field.clone()) - conv_usize!(ldc_u64!(from + to))
+    } else {
+        conv_usize!(ldc_u64!(to - from))
+    };
+    CILNode::TemporaryLocal(Box::new((
+        slice_type,
+        [
+            CILRoot::SetField {
+                addr: CILNode::LoadAddresOfTMPLocal,
+                value: length,
+                desc: metadata_field,
+            },
+            CILRoot::SetField {
+                addr: CILNode::LoadAddresOfTMPLocal,
+                value: ld_field!(slice, ptr_field.clone())
+                    + conv_usize!(ldc_u64!(from)) * conv_usize!(size_of!(element_type)),
+                desc: ptr_field,
+            },
+        ]
+        .into(),
+        CILNode::LoadTMPLocal,
+    )))
+}
+/// Returns the address of the subarray selected by [`PlaceElem::Subslice`] of an array of type `array`, at address `addr`.
+fn subarray_adress<'ctx>(
+    array: Ty<'ctx>,
+    subarray: Ty<'ctx>,
+    from: u64,
+    tyctx: TyCtxt<'ctx>,
+    method_instance: &Instance<'ctx>,
+    type_cache: &mut crate::r#type::TyCache,
+    addr: CILNode,
+) -> CILNode {
+    let TyKind::Array(element, _) = array.kind() else {
+        panic!("{array:?} is not an array!");
+    };
+    let element = crate::utilis::monomorphize(method_instance, *element, tyctx);
+    let element_type = type_cache.type_from_cache(element, tyctx, Some(*method_instance));
+    let subarray_type = type_cache.type_from_cache(subarray, tyctx, Some(*method_instance));
+    CILNode::TransmutePtr {
+        val: Box::new(addr + conv_usize!(ldc_u64!(from)) * conv_usize!(size_of!(element_type))),
+        new_ptr: Box::new(Type::Ptr(Box::new(subarray_type))),
+    }
+}
 
 /// Given a type `derefed_type`, it retuns a set of instructions to get a value behind a pointer to `derefed_type`.
 pub fn deref_op<'ctx>(
@@ -70,99 +226,104 @@ pub fn deref_op<'ctx>(
     ptr: CILNode,
 ) -> CILNode {
     let ptr = Box::new(ptr);
-    let res = if let PlaceTy::Ty(derefed_type) = derefed_type {
-        match derefed_type.kind() {
-            TyKind::Int(int_ty) => match int_ty {
-                IntTy::I8 => CILNode::LDIndI8 { ptr },
-                IntTy::I16 => CILNode::LDIndI16 { ptr },
-                IntTy::I32 => CILNode::LDIndI32 { ptr },
-                IntTy::I64 => CILNode::LDIndI64 { ptr },
-                IntTy::Isize => CILNode::LDIndISize { ptr },
-                IntTy::I128 => CILNode::LdObj {
-                    ptr,
-                    obj: Box::new(DotnetTypeRef::int_128().into()),
-                },
-                //_ => todo!("TODO: can't deref int type {int_ty:?} yet"),
+    // An enum variant is stored in the same way as the enum it belongs to.
+    let derefed_type = match derefed_type {
+        PlaceTy::Ty(derefed_type) | PlaceTy::EnumVariant(derefed_type, _) => derefed_type,
+    };
+    match derefed_type.kind() {
+        TyKind::Int(int_ty) => match int_ty {
+            IntTy::I8 => CILNode::LDIndI8 { ptr },
+            IntTy::I16 => CILNode::LDIndI16 { ptr },
+            IntTy::I32 => CILNode::LDIndI32 { ptr },
+            IntTy::I64 => CILNode::LDIndI64 { ptr },
+            IntTy::Isize => CILNode::LDIndISize { ptr },
+            IntTy::I128 => CILNode::LdObj {
+                ptr,
+                obj: Box::new(DotnetTypeRef::int_128().into()),
             },
-            TyKind::Uint(int_ty) => match int_ty {
-                UintTy::U8 => CILNode::LDIndU8 { ptr },
-                UintTy::U16 => CILNode::LDIndU16 { ptr },
-                UintTy::U32 => CILNode::LDIndU32 { ptr },
-                UintTy::U64 => CILNode::LDIndU64 { ptr },
-                UintTy::Usize => CILNode::LDIndUSize { ptr },
-                UintTy::U128 => CILNode::LdObj {
-                    ptr,
-                    obj: Box::new(DotnetTypeRef::uint_128().into()),
-                }, //vec![CILOp::LdObj(Box::new())],
-                   //_ => todo!("TODO: can't deref int type {int_ty:?} yet"),
+            //_ => todo!("TODO: can't deref int type {int_ty:?} yet"),
+        },
+        TyKind::Uint(int_ty) => match int_ty {
+            UintTy::U8 => CILNode::LDIndU8 { ptr },
+            UintTy::U16 => CILNode::LDIndU16 { ptr },
+            UintTy::U32 => CILNode::LDIndU32 { ptr },
+            UintTy::U64 => CILNode::LDIndU64 { ptr },
+            UintTy::Usize => CILNode::LDIndUSize { ptr },
+            UintTy::U128 => CILNode::LdObj {
+                ptr,
+                obj: Box::new(DotnetTypeRef::uint_128().into()),
+            }, //vec![CILOp::LdObj(Box::new())],
+               //_ => todo!("TODO: can't deref int type {int_ty:?} yet"),
+        },
+        TyKind::Float(float_ty) => match float_ty {
+            FloatTy::F16 => CILNode::LdObj {
+                ptr,
+                obj: Box::new(Type::F16),
             },
-            TyKind::Float(float_ty) => match float_ty {
-                FloatTy::F16 => todo!("Can't handle halfs yet!"),
-                FloatTy::F32 => CILNode::LDIndF32 { ptr },
-                FloatTy::F64 => CILNode::LDIndF64 { ptr },
-                FloatTy::F128 => todo!("Can't 128 bit floats yet!"),
+            FloatTy::F32 => CILNode::LDIndF32 { ptr },
+            FloatTy::F64 => CILNode::LDIndF64 { ptr },
+            FloatTy::F128 => CILNode::LdObj {
+                ptr,
+                obj: Box::new(Type::F128),
             },
-            TyKind::Bool => CILNode::LDIndBool { ptr }, // Both Rust bool and a managed bool are 1 byte wide. .NET bools are 4 byte wide only in the context of Marshaling/PInvoke,
-            // due to historic reasons(BOOL was an alias for int in early Windows, and it stayed this way.) - FractalFir
-            TyKind::Char => CILNode::LDIndI32 { ptr }, // always 4 bytes wide: https://doc.rust-lang.org/std/primitive.char.html#representation
-            TyKind::Adt(_, _)
-            | TyKind::Tuple(_)
-            | TyKind::Array(_, _)
-            | TyKind::FnPtr(_)
-            | TyKind::Closure(_, _) => {
-                let derefed_type =
-                    type_cache.type_from_cache(derefed_type, tyctx, Some(*method_instance));
+        },
+        TyKind::Bool => CILNode::LDIndBool { ptr }, // Both Rust bool and a managed bool are 1 byte wide. .NET bools are 4 byte wide only in the context of Marshaling/PInvoke,
+        // due to historic reasons(BOOL was an alias for int in early Windows, and it stayed this way.) - FractalFir
+        TyKind::Char => CILNode::LDIndI32 { ptr }, // always 4 bytes wide: https://doc.rust-lang.org/std/primitive.char.html#representation
+        TyKind::Adt(_, _)
+        | TyKind::Tuple(_)
+        | TyKind::Array(_, _)
+        | TyKind::FnPtr(_)
+        | TyKind::Closure(_, _)
+        | TyKind::Coroutine(_, _)
+        | TyKind::Dynamic(_, _, DynKind::DynStar) => {
+            let derefed_type =
+                type_cache.type_from_cache(derefed_type, tyctx, Some(*method_instance));
 
+            CILNode::LdObj {
+                ptr,
+                obj: Box::new(derefed_type),
+            }
+        }
+        TyKind::Ref(_, inner, _) => {
+            if pointer_to_is_fat(*inner, tyctx, Some(*method_instance)) {
                 CILNode::LdObj {
                     ptr,
-                    obj: Box::new(derefed_type),
+                    obj: Box::new(type_cache.type_from_cache(
+                        derefed_type,
+                        tyctx,
+                        Some(*method_instance),
+                    )),
                 }
-            }
-            TyKind::Ref(_, inner, _) => {
-                if pointer_to_is_fat(*inner, tyctx, Some(*method_instance)) {
-                    CILNode::LdObj {
-                        ptr,
-                        obj: Box::new(type_cache.type_from_cache(
-                            derefed_type,
-                            tyctx,
-                            Some(*method_instance),
-                        )),
-                    }
-                } else {
-                    let inner =
-                        type_cache.type_from_cache(derefed_type, tyctx, Some(*method_instance));
-                    CILNode::LDIndPtr {
-                        ptr,
-                        loaded_ptr: Box::new(inner),
-                    }
+            } else {
+                let inner = type_cache.type_from_cache(derefed_type, tyctx, Some(*method_instance));
+                CILNode::LDIndPtr {
+                    ptr,
+                    loaded_ptr: Box::new(inner),
                 }
             }
-            TyKind::RawPtr(typ, _) => {
-                if pointer_to_is_fat(*typ, tyctx, Some(*method_instance)) {
-                    CILNode::LdObj {
-                        ptr,
-                        obj: Box::new(type_cache.type_from_cache(
-                            derefed_type,
-                            tyctx,
-                            Some(*method_instance),
-                        )),
-                    }
-                } else {
-                    let typ =
-                        type_cache.type_from_cache(derefed_type, tyctx, Some(*method_instance));
-                    CILNode::LDIndPtr {
-                        ptr,
-                        loaded_ptr: Box::new(typ),
-                    }
+        }
+        TyKind::RawPtr(typ, _) => {
+            if pointer_to_is_fat(*typ, tyctx, Some(*method_instance)) {
+                CILNode::LdObj {
+                    ptr,
+                    obj: Box::new(type_cache.type_from_cache(
+                        derefed_type,
+                        tyctx,
+                        Some(*method_instance),
+                    )),
+                }
+            } else {
+                let typ = type_cache.type_from_cache(derefed_type, tyctx, Some(*method_instance));
+                CILNode::LDIndPtr {
+                    ptr,
+                    loaded_ptr: Box::new(typ),
                 }
             }
-
-            _ => todo!("TODO: can't deref type {derefed_type:?} yet"),
         }
-    } else {
-        todo!("Can't dereference enum variants yet!")
-    };
-    res
+
+        _ => todo!("TODO: can't deref type {derefed_type:?} yet"),
+    }
 }
 
 /// Returns the ops for getting the address of a given place.
@@ -185,16 +346,22 @@ pub fn place_adress<'a>(
     if layout.is_zst() {
         return conv_usize!(ldc_u64!(layout.align.pref.bytes()));
     }
-    if place.projection.is_empty() {
-        local_adress(place.local.as_usize(), method)
+    let projection = projection(place);
+    if projection.is_empty() {
+        local_adress(place.local.as_usize(), method, type_cache)
     } else {
-        let (mut addr_calc, mut ty) =
-            local_body(place.local.as_usize(), method, tyctx, &method_instance);
+        let (mut addr_calc, mut ty) = local_body(
+            place.local.as_usize(),
+            method,
+            tyctx,
+            &method_instance,
+            type_cache,
+        );
 
         ty = crate::utilis::monomorphize(&method_instance, ty, tyctx);
         let mut ty = ty.into();
 
-        let (head, body) = slice_head(place.projection);
+        let (head, body) = slice_head(projection);
         for elem in body {
             let (curr_ty, curr_ops) = place_elem_body(
                 elem,
@@ -241,23 +408,24 @@ pub(crate) fn place_address_raw<'a>(
     if layout.is_zst() {
         return conv_usize!(ldc_u64!(layout.align.pref.bytes()));
     }
-    if place.projection.is_empty() {
-        local_adress(place.local.as_usize(), method)
-    } else if place.projection.len() == 1
-        && matches!(
-            slice_head(place.projection).0,
-            rustc_middle::mir::PlaceElem::Deref
-        )
-    {
-        return local_adress(place.local.as_usize(), method);
+    let projection = projection(place);
+    if projection.is_empty() {
+        local_adress(place.local.as_usize(), method, type_cache)
+    } else if projection.len() == 1 && matches!(slice_head(projection).0, PlaceElem::Deref) {
+        return local_adress(place.local.as_usize(), method, type_cache);
     } else {
-        let (mut addr_calc, mut ty) =
-            local_body(place.local.as_usize(), method, tyctx, &method_instance);
+        let (mut addr_calc, mut ty) = local_body(
+            place.local.as_usize(),
+            method,
+            tyctx,
+            &method_instance,
+            type_cache,
+        );
 
         ty = crate::utilis::monomorphize(&method_instance, ty, tyctx);
         let mut ty = ty.into();
 
-        let (head, body) = slice_head(place.projection);
+        let (head, body) = slice_head(projection);
         for elem in body {
             let (curr_ty, curr_ops) = place_elem_body(
                 elem,
@@ -284,6 +452,35 @@ pub(crate) fn place_address_raw<'a>(
         )
     }
 }
+/// Checks that `place` is not a value containing managed references, stored in memory which may be unmanaged(behind a raw pointer or a `Box`).
+/// The GC can't see references stored in unmanaged memory, so they could be collected or moved while still in use.
+/// Storing such a value in a local, or behind a reference, is fine, since it may still live on the stack.
+/// Reports an error at `span` if the store is not allowed.
+pub(crate) fn check_gcref_store<'tyctx>(
+    place: &Place<'tyctx>,
+    span: rustc_span::Span,
+    tyctx: TyCtxt<'tyctx>,
+    method: &rustc_middle::mir::Body<'tyctx>,
+    method_instance: Instance<'tyctx>,
+    type_cache: &mut crate::r#type::TyCache,
+) {
+    let behind_unmanaged_ptr = place.iter_projections().any(|(base, elem)| {
+        let base_ty =
+            crate::utilis::monomorphize(&method_instance, base.ty(method, tyctx).ty, tyctx);
+        matches!(elem, PlaceElem::Deref) && (base_ty.is_unsafe_ptr() || base_ty.is_box())
+    });
+    if !behind_unmanaged_ptr {
+        return;
+    }
+    let place_ty = crate::utilis::monomorphize(&method_instance, place.ty(method, tyctx).ty, tyctx);
+    let place_type = type_cache.type_from_cache(place_ty, tyctx, Some(method_instance));
+    if type_cache.contains_gcref(&place_type) {
+        tyctx.dcx().span_err(
+            span,
+            format!("storing a value of type `{place_ty}` behind a pointer. It contains a managed object reference, which can't be placed in unmanaged memory, since the GC can't see it. Store it in a `mycorrhiza::system::runtime::interop_services::GcHandle` instead."),
+        );
+    }
+}
 pub(crate) fn place_set<'tyctx>(
     place: &Place<'tyctx>,
     tyctx: TyCtxt<'tyctx>,
@@ -292,15 +489,28 @@ pub(crate) fn place_set<'tyctx>(
     method_instance: Instance<'tyctx>,
     type_cache: &mut crate::r#type::TyCache,
 ) -> CILRoot {
-    if place.projection.is_empty() {
-        set::local_set(place.local.as_usize(), method, value_calc)
+    let projection = projection(place);
+    if projection.is_empty() {
+        set::local_set_aligned(
+            place.local.as_usize(),
+            method,
+            value_calc,
+            tyctx,
+            &method_instance,
+            type_cache,
+        )
     } else {
-        let (mut addr_calc, ty) =
-            local_body(place.local.as_usize(), method, tyctx, &method_instance);
+        let (mut addr_calc, ty) = local_body(
+            place.local.as_usize(),
+            method,
+            tyctx,
+            &method_instance,
+            type_cache,
+        );
         let mut ty: PlaceTy = ty.into();
         ty = ty.monomorphize(&method_instance, tyctx);
 
-        let (head, body) = slice_head(place.projection);
+        let (head, body) = slice_head(projection);
         for elem in body {
             let (curr_ty, curr_ops) = place_elem_body(
                 elem,
@@ -7,7 +7,7 @@ use crate::r#type::{pointer_to_is_fat, DotnetTypeRef, Type};
 use crate::{call, conv_usize, ld_field, ldc_u64, size_of};
 
 use rustc_middle::mir::PlaceElem;
-use rustc_middle::ty::{FloatTy, Instance, IntTy, TyCtxt, TyKind, UintTy};
+use rustc_middle::ty::{DynKind, FloatTy, Instance, IntTy, Ty, TyCtxt, TyKind, UintTy};
 
 pub fn local_set(local: usize, method: &rustc_middle::mir::Body, tree: CILNode) -> CILRoot {
     if let Some(spread_arg) = method.spread_arg
@@ -34,6 +34,26 @@ pub fn local_set(local: usize, method: &rustc_middle::mir::Body, tree: CILNode)
         }
     }
 }
+/// Sets the value of `local`. Over-aligned locals are stored in their aligned storage.
+pub(crate) fn local_set_aligned<'tcx>(
+    local: usize,
+    method: &rustc_middle::mir::Body<'tcx>,
+    tree: CILNode,
+    tyctx: TyCtxt<'tcx>,
+    method_instance: &Instance<'tcx>,
+    type_cache: &mut crate::r#type::TyCache,
+) -> CILRoot {
+    let Some(ptr) = type_cache.aligned_local_ptr(local) else {
+        return local_set(local, method, tree);
+    };
+    let ty =
+        crate::utilis::monomorphize(method_instance, method.local_decls[local.into()].ty, tyctx);
+    CILRoot::STObj {
+        tpe: Box::new(type_cache.type_from_cache(ty, tyctx, Some(*method_instance))),
+        addr_calc: CILNode::LDLoc(ptr),
+        value_calc: tree,
+    }
+}
 pub fn place_elem_set<'a>(
     place_elem: &PlaceElem<'a>,
     curr_type: PlaceTy<'a>,
@@ -157,9 +177,6 @@ pub fn place_elem_set<'a>(
             let curr_ty = curr_type
                 .as_ty()
                 .expect("INVALID PLACE: Indexing into enum variant???");
-            let index = ldc_u64!(*offset);
-            assert!(!from_end, "Indexing slice form end");
-
             match curr_ty.kind() {
                 TyKind::Slice(inner) => {
                     let inner = crate::utilis::monomorphize(&method_instance, *inner, ctx);
@@ -174,7 +191,10 @@ pub fn place_elem_set<'a>(
                         Type::Ptr(Type::Void.into()),
                         "data_pointer".into(),
                     );
-                    let metadata = FieldDescriptor::new(slice, Type::USize, "metadata".into());
+                    let metadata =
+                        FieldDescriptor::new(slice.clone(), Type::USize, "metadata".into());
+                    let index =
+                        super::slice_const_index(*offset, *from_end, addr_calc.clone(), slice);
                     let addr = ld_field!(addr_calc.clone(), desc)
                         + call!(
                             CallSite::new(
@@ -196,6 +216,13 @@ pub fn place_elem_set<'a>(
                 }
                 TyKind::Array(element, _length) => {
                     //println!("WARNING: ConstantIndex has required min_length of {min_length}, but bounds checking on const access not supported yet!");
+                    let index = ldc_u64!(super::array_const_index(
+                        curr_ty,
+                        *offset,
+                        *from_end,
+                        ctx,
+                        &method_instance,
+                    ));
                     let element = crate::utilis::monomorphize(&method_instance, *element, ctx);
                     let element = type_cache.type_from_cache(element, ctx, Some(method_instance));
                     let array_type =
@@ -219,27 +246,44 @@ pub fn place_elem_set<'a>(
                 }
             }
         }
-        /*
-        PlaceElem::ConstantIndex {
-            offset,
-            min_length: _,
-            from_end,
-        } => {
-            let mut ops = if !from_end {
-                CILRoot::LdcI64(*offset as i64)]
-            } else {
-                let mut get_len = place_get_length(curr_type, ctx, method_instance, type_cache);
-                get_len.extend(CILRoot::LdcI64(*offset as i64), CILOp::Sub]);
-                get_len
+        PlaceElem::Subslice { from, to, from_end } => {
+            let curr_ty = curr_type
+                .as_ty()
+                .expect("INVALID PLACE: Subslicing an enum variant???");
+            // Only a subslice of an array is sized, and can be assigned to.
+            let TyKind::Array(element, _) = curr_ty.kind() else {
+                rustc_middle::ty::print::with_no_trimmed_paths! { todo!("Can't set a subslice of {curr_ty}!")}
             };
-            ops.extend(place_elem_set_at(
-                curr_type,
+            assert!(!from_end, "Subslicing an array from end!");
+            let element = crate::utilis::monomorphize(&method_instance, *element, ctx);
+            let subarray = Ty::new_array(ctx, element, to - from);
+            let addr = super::subarray_adress(
+                curr_ty,
+                subarray,
+                *from,
                 ctx,
                 &method_instance,
                 type_cache,
-            ));
-            ops
-        }*/
+                addr_calc,
+            );
+            ptr_set_op(
+                subarray.into(),
+                ctx,
+                &method_instance,
+                type_cache,
+                addr,
+                value_calc,
+            )
+        }
+        // A whole enum variant is stored as the enum it belongs to.
+        PlaceElem::Downcast(_, _) => ptr_set_op(
+            curr_type,
+            ctx,
+            &method_instance,
+            type_cache,
+            addr_calc,
+            value_calc,
+        ),
         _ => todo!("Can't handle porojection {place_elem:?} in set"),
     }
 }
@@ -252,78 +296,88 @@ pub fn ptr_set_op<'ctx>(
     addr_calc: CILNode,
     value_calc: CILNode,
 ) -> CILRoot {
-    if let PlaceTy::Ty(pointed_type) = pointed_type {
-        match pointed_type.kind() {
-            TyKind::Int(int_ty) => match int_ty {
-                IntTy::I8 => CILRoot::STIndI8(addr_calc, value_calc),
-                IntTy::I16 => CILRoot::STIndI16(addr_calc, value_calc),
-                IntTy::I32 => CILRoot::STIndI32(addr_calc, value_calc),
-                IntTy::I64 => CILRoot::STIndI64(addr_calc, value_calc),
-                IntTy::Isize => CILRoot::STIndISize(addr_calc, value_calc),
-                IntTy::I128 => CILRoot::STObj {
-                    tpe: Box::new(DotnetTypeRef::int_128().into()),
-                    addr_calc,
-                    value_calc,
-                },
+    // An enum variant is stored in the same way as the enum it belongs to.
+    let pointed_type = match pointed_type {
+        PlaceTy::Ty(pointed_type) | PlaceTy::EnumVariant(pointed_type, _) => pointed_type,
+    };
+    match pointed_type.kind() {
+        TyKind::Int(int_ty) => match int_ty {
+            IntTy::I8 => CILRoot::STIndI8(addr_calc, value_calc),
+            IntTy::I16 => CILRoot::STIndI16(addr_calc, value_calc),
+            IntTy::I32 => CILRoot::STIndI32(addr_calc, value_calc),
+            IntTy::I64 => CILRoot::STIndI64(addr_calc, value_calc),
+            IntTy::Isize => CILRoot::STIndISize(addr_calc, value_calc),
+            IntTy::I128 => CILRoot::STObj {
+                tpe: Box::new(DotnetTypeRef::int_128().into()),
+                addr_calc,
+                value_calc,
             },
-            TyKind::Uint(int_ty) => match int_ty {
-                UintTy::U8 => CILRoot::STIndI8(addr_calc, value_calc),
-                UintTy::U16 => CILRoot::STIndI16(addr_calc, value_calc),
-                UintTy::U32 => CILRoot::STIndI32(addr_calc, value_calc),
-                UintTy::U64 => CILRoot::STIndI64(addr_calc, value_calc),
-                UintTy::Usize => CILRoot::STIndISize(addr_calc, value_calc),
-                UintTy::U128 => CILRoot::STObj {
-                    tpe: Box::new(DotnetTypeRef::uint_128().into()),
-                    addr_calc,
-                    value_calc,
-                },
+        },
+        TyKind::Uint(int_ty) => match int_ty {
+            UintTy::U8 => CILRoot::STIndI8(addr_calc, value_calc),
+            UintTy::U16 => CILRoot::STIndI16(addr_calc, value_calc),
+            UintTy::U32 => CILRoot::STIndI32(addr_calc, value_calc),
+            UintTy::U64 => CILRoot::STIndI64(addr_calc, value_calc),
+            UintTy::Usize => CILRoot::STIndISize(addr_calc, value_calc),
+            UintTy::U128 => CILRoot::STObj {
+                tpe: Box::new(DotnetTypeRef::uint_128().into()),
+                addr_calc,
+                value_calc,
             },
-            TyKind::Float(float_ty) => match float_ty {
-                FloatTy::F32 => CILRoot::STIndF32(addr_calc, value_calc),
-                FloatTy::F64 => CILRoot::STIndF64(addr_calc, value_calc),
-                _ => todo!("Can't yet set {float_ty:?} behnind a pointer."),
+        },
+        TyKind::Float(float_ty) => match float_ty {
+            FloatTy::F32 => CILRoot::STIndF32(addr_calc, value_calc),
+            FloatTy::F64 => CILRoot::STIndF64(addr_calc, value_calc),
+            FloatTy::F16 | FloatTy::F128 => CILRoot::STObj {
+                tpe: Box::new(float_ty.into()),
+                addr_calc,
+                value_calc,
             },
-            TyKind::Bool => CILRoot::STIndI8(addr_calc, value_calc), // Both Rust bool and a managed bool are 1 byte wide. .NET bools are 4 byte wide only in the context of Marshaling/PInvoke,
-            // due to historic reasons(BOOL was an alias for int in early Windows, and it stayed this way.) - FractalFir
-            TyKind::Char => CILRoot::STIndI32(addr_calc, value_calc), // always 4 bytes wide: https://doc.rust-lang.org/std/primitive.char.html#representation
-            TyKind::Adt(_, _) | TyKind::Tuple(_) | TyKind::Array(_, _) | TyKind::Closure(_, _) => {
-                let pointed_type =
-                    type_cache.type_from_cache(pointed_type, tyctx, Some(*method_instance));
+        },
+        TyKind::Bool => CILRoot::STIndI8(addr_calc, value_calc), // Both Rust bool and a managed bool are 1 byte wide. .NET bools are 4 byte wide only in the context of Marshaling/PInvoke,
+        // due to historic reasons(BOOL was an alias for int in early Windows, and it stayed this way.) - FractalFir
+        TyKind::Char => CILRoot::STIndI32(addr_calc, value_calc), // always 4 bytes wide: https://doc.rust-lang.org/std/primitive.char.html#representation
+        TyKind::Adt(_, _)
+        | TyKind::Tuple(_)
+        | TyKind::Array(_, _)
+        | TyKind::Closure(_, _)
+        | TyKind::Coroutine(_, _)
+        | TyKind::Dynamic(_, _, DynKind::DynStar) => {
+            let pointed_type =
+                type_cache.type_from_cache(pointed_type, tyctx, Some(*method_instance));
+            CILRoot::STObj {
+                tpe: pointed_type.into(),
+                addr_calc,
+                value_calc,
+            }
+        }
+        TyKind::Ref(_, inner, _) => {
+            if pointer_to_is_fat(*inner, tyctx, Some(*method_instance)) {
                 CILRoot::STObj {
-                    tpe: pointed_type.into(),
+                    tpe: type_cache
+                        .type_from_cache(pointed_type, tyctx, Some(*method_instance))
+                        .into(),
                     addr_calc,
                     value_calc,
                 }
+            } else {
+                CILRoot::STIndISize(addr_calc, value_calc)
             }
-            TyKind::Ref(_, inner, _) => {
-                if pointer_to_is_fat(*inner, tyctx, Some(*method_instance)) {
-                    CILRoot::STObj {
-                        tpe: type_cache
-                            .type_from_cache(pointed_type, tyctx, Some(*method_instance))
-                            .into(),
-                        addr_calc,
-                        value_calc,
-                    }
-                } else {
-                    CILRoot::STIndISize(addr_calc, value_calc)
-                }
-            }
-            TyKind::RawPtr(ty, _) => {
-                if pointer_to_is_fat(*ty, tyctx, Some(*method_instance)) {
-                    CILRoot::STObj {
-                        tpe: type_cache
-                            .type_from_cache(pointed_type, tyctx, Some(*method_instance))
-                            .into(),
-                        addr_calc,
-                        value_calc,
-                    }
-                } else {
-                    CILRoot::STIndISize(addr_calc, value_calc)
+        }
+        TyKind::RawPtr(ty, _) => {
+            if pointer_to_is_fat(*ty, tyctx, Some(*method_instance)) {
+                CILRoot::STObj {
+                    tpe: type_cache
+                        .type_from_cache(pointed_type, tyctx, Some(*method_instance))
+                        .into(),
+                    addr_calc,
+                    value_calc,
                 }
+            } else {
+                CILRoot::STIndISize(addr_calc, value_calc)
             }
-            _ => todo!(" can't deref type {pointed_type:?} yet"),
         }
-    } else {
-        todo!("Can't set the value behind a poitner to an enum variant!");
+        TyKind::FnPtr(_) => CILRoot::STIndISize(addr_calc, value_calc),
+        _ => todo!(" can't deref type {pointed_type:?} yet"),
     }
 }
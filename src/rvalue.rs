@@ -4,9 +4,9 @@ use crate::cil_tree::cil_root::CILRoot;
 use crate::function_sig::FnSig;
 use crate::operand::handle_operand;
 
-use crate::{conv_usize, ld_field, ldc_i32, ldc_u64, size_of};
+use crate::{call, conv_usize, ld_field, ldc_i32, ldc_u64, size_of};
 
-use crate::r#type::{pointer_to_is_fat, TyCache, Type};
+use crate::r#type::{pointer_to_is_fat, DotnetTypeRef, TyCache, Type};
 use rustc_middle::{
     mir::{CastKind, NullOp, Place, Rvalue},
     ty::{
@@ -174,9 +174,18 @@ pub fn handle_rvalue<'tcx>(
                 conv_usize!(ldc_u64!(crate::utilis::align_of(ty, tyctx) as u64))
             }
             NullOp::OffsetOf(fields) => {
-                assert_eq!(fields.len(), 1);
-                //let (variant, field) = fields[0];
-                todo!("Can't calc offset of yet!");
+                let ty = crate::utilis::monomorphize(&method_instance, *ty, tyctx);
+                let layout = tyctx
+                    .layout_of(rustc_middle::ty::ParamEnvAnd {
+                        param_env: ParamEnv::reveal_all(),
+                        value: ty,
+                    })
+                    .expect("Could not get type layout!");
+                // Each (variant, field) pair selects a field of the previous one, so nested fields(`a.b.c`) and fields of enum variants are supported.
+                let offset = tyctx
+                    .offset_of_subfield(ParamEnv::reveal_all(), layout, fields.iter())
+                    .bytes();
+                conv_usize!(ldc_u64!(offset))
             }
 
             rustc_middle::mir::NullOp::UbChecks => {
@@ -220,6 +229,17 @@ pub fn handle_rvalue<'tcx>(
                 (Type::U16, Type::DotnetChar) => {
                     handle_operand(operand, tyctx, method, method_instance, tycache)
                 }
+                // Managed object references can't be reinterpreted trough memory, so they must be cast.
+                (Type::DotnetType(_) | Type::ManagedArray { .. }, Type::DotnetType(dst_ref))
+                    if src.is_gcref() && dst.is_gcref() =>
+                {
+                    let val = handle_operand(operand, tyctx, method, method_instance, tycache);
+                    if src == dst || dst_ref.as_ref() == &DotnetTypeRef::object_type() {
+                        val
+                    } else {
+                        CILNode::CheckedCast(Box::new((val, dst_ref.as_ref().clone())))
+                    }
+                }
 
                 (_, Type::F64) => CILNode::TemporaryLocal(Box::new((
                     src,
@@ -313,13 +333,14 @@ pub fn handle_rvalue<'tcx>(
         Rvalue::Cast(CastKind::FloatToFloat, operand, target) => {
             let target = crate::utilis::monomorphize(&method_instance, *target, tyctx);
             let target = tycache.type_from_cache(target, tyctx, Some(method_instance));
-            let mut ops = handle_operand(operand, tyctx, method, method_instance, tycache);
-            match target {
-                Type::F32 => ops = CILNode::ConvF32(ops.into()),
-                Type::F64 => ops = CILNode::ConvF64(ops.into()),
-                _ => panic!("Can't preform a FloatToFloat cast to type {target:?}"),
-            }
-            ops
+            let src = operand.ty(&method.local_decls, tyctx);
+            let src = crate::utilis::monomorphize(&method_instance, src, tyctx);
+            let src = tycache.type_from_cache(src, tyctx, Some(method_instance));
+            crate::casts::float_to_float(
+                &src,
+                &target,
+                handle_operand(operand, tyctx, method, method_instance, tycache),
+            )
         }
         Rvalue::Cast(
             CastKind::PointerCoercion(PointerCoercion::ReifyFnPointer),
@@ -464,25 +485,65 @@ pub fn handle_rvalue<'tcx>(
         }
         Rvalue::ThreadLocalRef(def_id) => {
             if !def_id.is_local() && tyctx.needs_thread_local_shim(*def_id) {
-                let _instance = Instance {
+                // Thread locals from other crates are accessed using the shim generated by their defining crate.
+                let instance = Instance {
                     def: InstanceDef::ThreadLocalShim(*def_id),
                     args: GenericArgs::empty(),
                 };
-                // Call instance
-                todo!("Thread locals with shims unsupported!")
+                let call_info =
+                    crate::call_info::CallInfo::sig_from_instance_(instance, tyctx, tycache);
+                let function_name = crate::utilis::function_name(tyctx.symbol_name(instance));
+                call!(
+                    CallSite::new(None, function_name, call_info.sig().clone(), true),
+                    []
+                )
             } else {
                 let alloc_id = tyctx.reserve_and_set_static_alloc(*def_id);
-                CILNode::LoadGlobalAllocPtr {
+                CILNode::LoadThreadLocalPtr {
                     alloc_id: alloc_id.0.into(),
                 }
             }
         }
-        Rvalue::Cast(rustc_middle::mir::CastKind::FnPtrToPtr, _, _) => {
-            todo!("Unusported cast kind:FnPtrToPtr")
+        Rvalue::Cast(
+            CastKind::FnPtrToPtr | CastKind::PointerCoercion(PointerCoercion::UnsafeFnPointer),
+            operand,
+            target,
+        ) => {
+            // Function pointers are just native ints, so this cast is a NOP.
+            let target = crate::utilis::monomorphize(&method_instance, *target, tyctx);
+            let target = tycache.type_from_cache(target, tyctx, Some(method_instance));
+            CILNode::TransmutePtr {
+                val: Box::new(handle_operand(
+                    operand,
+                    tyctx,
+                    method,
+                    method_instance,
+                    tycache,
+                )),
+                new_ptr: Box::new(target),
+            }
+        }
+        Rvalue::Cast(CastKind::DynStar, operand, target) => {
+            crate::unsize::dyn_star(tyctx, method, method_instance, tycache, operand, *target)
         }
-        Rvalue::Cast(rustc_middle::mir::CastKind::DynStar, _, _) => {
-            todo!("Unusported cast kind:DynStar")
+        Rvalue::Cast(
+            CastKind::PointerCoercion(PointerCoercion::ClosureFnPointer(_)),
+            operand,
+            _,
+        ) => {
+            let operand_ty = operand.ty(method, tyctx);
+            let operand_ty = crate::utilis::monomorphize(&method_instance, operand_ty, tyctx);
+            let TyKind::Closure(def_id, subst) = operand_ty.kind() else {
+                panic!("ERROR: Can't coerce {operand_ty:?} into a function pointer, since it is not a closure.")
+            };
+            // Only non-capturing closures can be coerced, so the `FnOnce` shim does not need a real closure environment.
+            let instance =
+                Instance::resolve_closure(tyctx, *def_id, subst, rustc_middle::ty::ClosureKind::FnOnce);
+            let function_name = crate::utilis::function_name(tyctx.symbol_name(instance));
+            let function_sig = FnSig::sig_from_instance_(instance, tyctx, tycache)
+                .expect("Could not get function signature when trying to get a function pointer!");
+            let shim = CallSite::new(None, function_name, function_sig, true);
+            CILNode::LDFtn(CallSite::closure_fn_ptr(&shim).into())
         }
-        Rvalue::Cast(_, _, _) => todo!(),
     }
 }
@@ -64,6 +64,14 @@ pub fn handle_statement<'tcx>(
             ) {
                 return None;
             }
+            crate::place::check_gcref_store(
+                &place,
+                statement.source_info.span,
+                tyctx,
+                method,
+                method_instance,
+                type_cache,
+            );
             Some(
                 crate::place::place_set(
                     &place,
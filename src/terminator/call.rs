@@ -2,20 +2,23 @@ use crate::{
     call,
     call_info::CallInfo,
     call_virt,
-    cil::{CallSite, FieldDescriptor},
+    cil::{CILOp, CallSite, FieldDescriptor},
     cil_tree::{cil_node::CILNode, cil_root::CILRoot},
     conv_usize,
     function_sig::FnSig,
     interop::AssemblyRef,
-    ld_field, ldc_u32,
+    ld_field, ld_field_address, ldc_u32,
     operand::operand_address,
     r#type::{DotnetTypeRef, Type},
     size_of,
-    utilis::{garg_to_string, CTOR_FN_NAME, MANAGED_CALL_FN_NAME, MANAGED_CALL_VIRT_FN_NAME},
+    utilis::{
+        garg_to_string, CTOR_FN_NAME, DELEGATE_CTOR_FN_NAME, MANAGED_ARRAY_FN_NAME,
+        MANAGED_CALL_FN_NAME, MANAGED_CALL_VIRT_FN_NAME,
+    },
 };
 use rustc_middle::{
     mir::{Body, Operand, Place},
-    ty::{GenericArg, Instance, InstanceDef, ParamEnv, Ty, TyCtxt, TyKind},
+    ty::{DynKind, GenericArg, Instance, InstanceDef, ParamEnv, Ty, TyCtxt, TyKind},
 };
 use rustc_span::source_map::Spanned;
 fn argc_from_fn_name(function_name: &str, prefix: &str) -> u32 {
@@ -52,11 +55,11 @@ fn call_managed<'tyctx>(
         .expect("Can't get the function signature");
 
     if argument_count == 0 {
-        let ret = crate::r#type::Type::Void;
+        // The signature of the intrinsic has no arguments, and returns the result of the managed call.
         let call_site = CallSite::new(
             Some(tpe.clone()),
             managed_fn_name.into(),
-            FnSig::new(&[], &ret),
+            signature.clone(),
             true,
         );
         if *signature.output() == crate::r#type::Type::Void {
@@ -141,11 +144,10 @@ fn callvirt_managed<'tyctx>(
     let signature = FnSig::sig_from_instance_(fn_instance, tyctx, type_cache)
         .expect("Can't get the function signature");
     if argument_count == 0 {
-        let ret = crate::r#type::Type::Void;
         let call = CallSite::new(
             Some(tpe.clone()),
             managed_fn_name.into(),
-            FnSig::new(&[], &ret),
+            signature.clone(),
             true,
         );
         if *signature.output() == crate::r#type::Type::Void {
@@ -281,6 +283,155 @@ fn call_ctor<'tyctx>(
         )
     }
 }
+/// Creates a new delegate pointing to a Rust function, and places a reference to it in destination
+fn call_delegate_ctor<'tyctx>(
+    tyctx: TyCtxt<'tyctx>,
+    subst_ref: &[GenericArg<'tyctx>],
+    args: &[Spanned<Operand<'tyctx>>],
+    destination: &Place<'tyctx>,
+    method: &'tyctx Body<'tyctx>,
+    method_instance: Instance<'tyctx>,
+    type_cache: &mut crate::r#type::TyCache,
+) -> CILRoot {
+    // Delegates are always constructed from a target object and a function pointer.
+    assert!(
+        args.len() == 2,
+        "A delegate must be constructed from exactly 2 arguments: its target and a function pointer!"
+    );
+    let delegate = subst_ref[0]
+        .as_type()
+        .expect("The delegate type must be specified!");
+    let delegate = crate::utilis::monomorphize(&method_instance, delegate, tyctx);
+    let delegate = type_cache
+        .type_from_cache(delegate, tyctx, Some(method_instance))
+        .as_dotnet()
+        .expect("Delegates must be managed classes!");
+    let target_ty =
+        crate::utilis::monomorphize(&method_instance, args[0].node.ty(method, tyctx), tyctx);
+    let target = match target_ty.kind() {
+        // No target, the function is called as a static method.
+        TyKind::Tuple(elements) if elements.is_empty() => CILNode::RawOpsParrentless {
+            ops: [CILOp::LdNull].into(),
+        },
+        // A managed object(for closures, a `RustClosureState` owning the closure)
+        _ => crate::operand::handle_operand(
+            &args[0].node,
+            tyctx,
+            method,
+            method_instance,
+            type_cache,
+        ),
+    };
+    // The function pointer is already a native int, which is what delegate constructors expect.
+    let fn_ptr =
+        crate::operand::handle_operand(&args[1].node, tyctx, method, method_instance, type_cache);
+    let sig = FnSig::new(
+        &[
+            delegate.clone().into(),
+            DotnetTypeRef::object_type().into(),
+            Type::ISize,
+        ],
+        &Type::Void,
+    );
+    crate::place::place_set(
+        destination,
+        tyctx,
+        CILNode::NewObj {
+            site: CallSite::boxed(Some(delegate), ".ctor".into(), sig, false),
+            args: [target, fn_ptr].into(),
+        },
+        method,
+        method_instance,
+        type_cache,
+    )
+}
+/// Handles operations on managed arrays(used for interop)
+fn call_managed_array<'tyctx>(
+    tyctx: TyCtxt<'tyctx>,
+    subst_ref: &[GenericArg<'tyctx>],
+    function_name: &str,
+    args: &[Spanned<Operand<'tyctx>>],
+    destination: &Place<'tyctx>,
+    method: &'tyctx Body<'tyctx>,
+    method_instance: Instance<'tyctx>,
+    type_cache: &mut crate::r#type::TyCache,
+) -> CILRoot {
+    let op_start = function_name.find(MANAGED_ARRAY_FN_NAME).unwrap() + MANAGED_ARRAY_FN_NAME.len();
+    let op_end = op_start + function_name[op_start..].find('_').unwrap();
+    let element = subst_ref[0]
+        .as_type()
+        .expect("The element type of a managed array must be specified!");
+    let element = crate::utilis::monomorphize(&method_instance, element, tyctx);
+    let element = type_cache.type_from_cache(element, tyctx, Some(method_instance));
+    let mut args = args
+        .iter()
+        .map(|arg| {
+            crate::operand::handle_operand(&arg.node, tyctx, method, method_instance, type_cache)
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+    match &function_name[op_start..op_end] {
+        "new" => {
+            let size = args.next().expect("A managed array must be allocated with a size!");
+            crate::place::place_set(
+                destination,
+                tyctx,
+                CILNode::NewArr {
+                    tpe: Box::new(element),
+                    size: Box::new(size),
+                },
+                method,
+                method_instance,
+                type_cache,
+            )
+        }
+        "len" => {
+            let arr = args.next().expect("Missing managed array!");
+            crate::place::place_set(
+                destination,
+                tyctx,
+                CILNode::LDLen { arr: Box::new(arr) },
+                method,
+                method_instance,
+                type_cache,
+            )
+        }
+        "get" => {
+            let arr = args.next().expect("Missing managed array!");
+            let idx = args.next().expect("Missing managed array index!");
+            crate::place::place_set(
+                destination,
+                tyctx,
+                CILNode::LdObj {
+                    ptr: Box::new(CILNode::LDElemAdress {
+                        tpe: Box::new(element.clone()),
+                        arr: Box::new(arr),
+                        idx: Box::new(idx),
+                    }),
+                    obj: Box::new(element),
+                },
+                method,
+                method_instance,
+                type_cache,
+            )
+        }
+        "set" => {
+            let arr = args.next().expect("Missing managed array!");
+            let idx = args.next().expect("Missing managed array index!");
+            let value = args.next().expect("Missing value to store in a managed array!");
+            CILRoot::STObj {
+                tpe: Box::new(element.clone()),
+                addr_calc: CILNode::LDElemAdress {
+                    tpe: Box::new(element),
+                    arr: Box::new(arr),
+                    idx: Box::new(idx),
+                },
+                value_calc: value,
+            }
+        }
+        op => todo!("Unsuported managed array operation {op:?}"),
+    }
+}
 pub fn call_closure<'tyctx>(
     args: &[Spanned<Operand<'tyctx>>],
     destination: &Place<'tyctx>,
@@ -393,11 +544,34 @@ pub fn call<'tyctx>(
     };
     if let rustc_middle::ty::InstanceDef::Virtual(_def, fn_idx) = instance.def {
         assert!(!args.is_empty());
-        let fat_ptr_ty =
+        let receiver_ty =
             crate::utilis::monomorphize(&method_instance, args[0].node.ty(body, tyctx), tyctx);
+        // A `&self` method called trough a `dyn*` gets a reference to the `dyn*` itself, which holds the vtable.
+        let dyn_star = match receiver_ty.kind() {
+            TyKind::Ref(_, inner, _)
+                if matches!(inner.kind(), TyKind::Dynamic(_, _, DynKind::DynStar)) =>
+            {
+                Some(*inner)
+            }
+            _ => None,
+        };
+        let (fat_ptr_ty, fat_ptr_address) = match dyn_star {
+            Some(dyn_star) => (
+                dyn_star,
+                crate::operand::handle_operand(
+                    &args[0].node,
+                    tyctx,
+                    body,
+                    method_instance,
+                    type_cache,
+                ),
+            ),
+            None => (
+                receiver_ty,
+                operand_address(&args[0].node, tyctx, body, method_instance, type_cache),
+            ),
+        };
         let fat_ptr_type = type_cache.type_from_cache(fat_ptr_ty, tyctx, Some(method_instance));
-        let fat_ptr_address =
-            operand_address(&args[0].node, tyctx, body, method_instance, type_cache);
         let vtable_ptr = ld_field!(
             fat_ptr_address.clone(),
             FieldDescriptor::new(
@@ -415,14 +589,17 @@ pub fn call<'tyctx>(
             ptr: Box::new(vtable_ptr + vtable_offset),
         };
         // Get the addres of the object
-        let obj_ptr = ld_field!(
-            fat_ptr_address,
-            FieldDescriptor::new(
-                fat_ptr_type.as_dotnet().unwrap(),
-                Type::Ptr(Type::Void.into()),
-                "data_pointer".into()
-            )
+        let data_pointer = FieldDescriptor::new(
+            fat_ptr_type.as_dotnet().unwrap(),
+            Type::Ptr(Type::Void.into()),
+            "data_pointer".into(),
         );
+        let obj_ptr = if dyn_star.is_some() {
+            // The value is stored inline, inside the `dyn*`, so `self` points to it.
+            ld_field_address!(fat_ptr_address, data_pointer)
+        } else {
+            ld_field!(fat_ptr_address, data_pointer)
+        };
         // Get the call info
         let call_info = CallInfo::sig_from_instance_(instance, tyctx, type_cache);
         let mut signature = call_info.sig().clone();
@@ -463,7 +640,7 @@ pub fn call<'tyctx>(
     let function_name = crate::utilis::function_name(tyctx.symbol_name(instance));
     if crate::utilis::is_fn_intrinsic(fn_type, tyctx) {
         return super::intrinsics::handle_intrinsic(
-            &function_name,
+            tyctx.item_name(instance.def_id()),
             args,
             destination,
             tyctx,
@@ -477,7 +654,30 @@ pub fn call<'tyctx>(
     }
 
     // Checks if function is "magic"
-    if function_name.contains(CTOR_FN_NAME) {
+    if function_name.contains(MANAGED_ARRAY_FN_NAME) {
+        // Managed array operation
+        return call_managed_array(
+            tyctx,
+            subst_ref,
+            &function_name,
+            args,
+            destination,
+            body,
+            method_instance,
+            type_cache,
+        );
+    } else if function_name.contains(DELEGATE_CTOR_FN_NAME) {
+        // Delegate constructor
+        return call_delegate_ctor(
+            tyctx,
+            subst_ref,
+            args,
+            destination,
+            body,
+            method_instance,
+            type_cache,
+        );
+    } else if function_name.contains(CTOR_FN_NAME) {
         assert!(
             !call_info.split_last_tuple(),
             "Constructors may not use the `rust_call` calling convention!"
@@ -551,7 +751,9 @@ pub fn call<'tyctx>(
             type_cache,
         ));
     }
-    if crate::function_sig::is_fn_variadic(fn_type, tyctx) {
+    let is_variadic = crate::function_sig::is_fn_variadic(fn_type, tyctx);
+    let fixed_args = signature.inputs().len();
+    if is_variadic {
         signature.set_inputs(
             args.iter()
                 .map(|operand| {
@@ -587,7 +789,10 @@ pub fn call<'tyctx>(
     if let InstanceDef::DropGlue(_def, None) = instance.def {
         return CILRoot::Nop;
     };
-    let call_site = CallSite::new(None, function_name, signature, true);
+    let mut call_site = CallSite::new(None, function_name, signature, true);
+    if is_variadic && *crate::config::VARARG_CALLS && tyctx.is_foreign_item(instance.def_id()) {
+        call_site.set_vararg(fixed_args);
+    }
     // Hande
     if is_void {
         CILRoot::Call {
@@ -0,0 +1,247 @@
+use crate::{
+    builtin::atomics::{atomic_builtin_name, atomic_builtin_sig, atomic_type},
+    call,
+    cil::CallSite,
+    cil_tree::{cil_node::CILNode, cil_root::CILRoot},
+    eq,
+    function_sig::FnSig,
+    ld_field,
+    operand::handle_operand,
+    place::{place_adress, place_set},
+    r#type::{tycache, DotnetTypeRef, Type},
+    utilis::field_descrptor,
+};
+use tycache::TyCache;
+
+use super::{IntrinsicCall, IntrinsicDesc, IntrinsicTy};
+/// Builds the descriptions of `atomic_{op}_{ordering}` intrinsics, for each listed operation and ordering.
+macro_rules! atomics {
+    ($($op:literal($args:literal,$ty:ident)[$($ordering:literal),*]),* $(,)?) => {
+        &[$($(IntrinsicDesc::new(concat!("atomic_", $op, "_", $ordering), $args, IntrinsicTy::$ty, handle_atomic),)*)*]
+    };
+}
+/// All the supported atomic intrinsics.
+pub(super) static ATOMICS: &[IntrinsicDesc] = atomics![
+    "fence"(0, None)["seqcst", "acquire", "release", "acqrel"],
+    "singlethreadfence"(0, None)["seqcst", "acquire", "release", "acqrel"],
+    "load"(1, Any)["seqcst", "acquire", "relaxed", "unordered"],
+    "store"(2, Any)["seqcst", "release", "relaxed", "unordered"],
+    "cxchg"(3, Any)[
+        "relaxed_relaxed", "relaxed_acquire", "relaxed_seqcst",
+        "acquire_relaxed", "acquire_acquire", "acquire_seqcst",
+        "release_relaxed", "release_acquire", "release_seqcst",
+        "acqrel_relaxed", "acqrel_acquire", "acqrel_seqcst",
+        "seqcst_relaxed", "seqcst_acquire", "seqcst_seqcst"
+    ],
+    "cxchgweak"(3, Any)[
+        "relaxed_relaxed", "relaxed_acquire", "relaxed_seqcst",
+        "acquire_relaxed", "acquire_acquire", "acquire_seqcst",
+        "release_relaxed", "release_acquire", "release_seqcst",
+        "acqrel_relaxed", "acqrel_acquire", "acqrel_seqcst",
+        "seqcst_relaxed", "seqcst_acquire", "seqcst_seqcst"
+    ],
+    "xchg"(2, Any)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "xadd"(2, Any)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "xsub"(2, Any)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "and"(2, Any)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "nand"(2, Any)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "or"(2, Any)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "xor"(2, Any)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "max"(2, Int)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "min"(2, Int)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "umax"(2, Int)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+    "umin"(2, Int)["seqcst", "acquire", "release", "acqrel", "relaxed"],
+];
+/// Lowers an intrinsic named `atomic_{op}_{orderings}`. The argument count is checked by the registry.
+/// Operations stronger than relaxed use either `System.Threading.Volatile`, or `System.Threading.Interlocked` (which is a full fence).
+pub fn handle_atomic<'tyctx>(
+    call: &IntrinsicCall<'_, 'tyctx>,
+    type_cache: &mut TyCache,
+) -> CILRoot {
+    let IntrinsicCall {
+        name,
+        args,
+        destination,
+        tyctx,
+        body,
+        method_instance,
+        ..
+    } = *call;
+    let fn_name = name.as_str();
+    let (op, ordering) = fn_name
+        .strip_prefix("atomic_")
+        .and_then(|name| name.split_once('_'))
+        .unwrap_or_else(|| panic!("Invalid atomic intrinsic name {fn_name:?}"));
+    if op == "fence" || op == "singlethreadfence" {
+        // A full barrier is stronger than any ordering, and also prevents the JIT from reordering accesses around it.
+        return CILRoot::Call {
+            site: CallSite::new(
+                Some(DotnetTypeRef::interlocked()),
+                "MemoryBarrier".into(),
+                FnSig::new(&[], &Type::Void),
+                true,
+            ),
+            args: [].into(),
+        };
+    }
+    let ty = call.ty(0);
+    let tpe = type_cache.type_from_cache(ty, tyctx, Some(method_instance));
+    let operand = |idx: usize, type_cache: &mut TyCache| {
+        handle_operand(&args[idx].node, tyctx, body, method_instance, type_cache)
+    };
+    match op {
+        "load" => {
+            let ptr = operand(0, type_cache);
+            let value = match ordering {
+                // .NET guarantess all aligned loads are tear-free.
+                "relaxed" | "unordered" => {
+                    crate::place::deref_op(ty.into(), tyctx, &method_instance, type_cache, ptr)
+                }
+                _ => {
+                    let vtpe = volatile_type(&tpe);
+                    from_atomic(
+                        call!(
+                            CallSite::new(
+                                Some(DotnetTypeRef::volatile()),
+                                "Read".into(),
+                                FnSig::new(&[Type::ManagedReference(vtpe.clone().into())], &vtpe),
+                                true,
+                            ),
+                            [ptr]
+                        ),
+                        &tpe,
+                    )
+                }
+            };
+            place_set(destination, tyctx, value, body, method_instance, type_cache)
+        }
+        "store" => {
+            let ptr = operand(0, type_cache);
+            let val = operand(1, type_cache);
+            match ordering {
+                "relaxed" | "unordered" => crate::place::ptr_set_op(
+                    ty.into(),
+                    tyctx,
+                    &method_instance,
+                    type_cache,
+                    ptr,
+                    val,
+                ),
+                "release" => {
+                    let vtpe = volatile_type(&tpe);
+                    CILRoot::Call {
+                        site: CallSite::new(
+                            Some(DotnetTypeRef::volatile()),
+                            "Write".into(),
+                            FnSig::new(
+                                &[Type::ManagedReference(vtpe.clone().into()), vtpe.clone()],
+                                &Type::Void,
+                            ),
+                            true,
+                        ),
+                        args: [ptr, to_atomic(val, &tpe)].into(),
+                    }
+                }
+                // A sequentially consistent store needs a full fence, so it is implemented as an exchange.
+                _ => CILRoot::Pop {
+                    tree: call!(atomic_builtin("xchg", &tpe), [ptr, to_atomic(val, &tpe)]),
+                },
+            }
+        }
+        "cxchg" | "cxchgweak" => {
+            // *T
+            let dst = operand(0, type_cache);
+            // T
+            let old = operand(1, type_cache);
+            // T
+            let src = operand(2, type_cache);
+            let exchange_res = from_atomic(
+                call!(
+                    atomic_builtin("cxchg", &tpe),
+                    [dst, to_atomic(old.clone(), &tpe), to_atomic(src, &tpe)]
+                ),
+                &tpe,
+            );
+            // Set a field of the destination
+            let dst_ty = destination.ty(body, tyctx);
+            let fld_desc = field_descrptor(dst_ty.ty, 0, tyctx, method_instance, type_cache);
+            assert_eq!(*fld_desc.tpe(), tpe);
+            // Set the value of the result.
+            let set_val = CILRoot::SetField {
+                addr: place_adress(destination, tyctx, body, method_instance, type_cache),
+                value: exchange_res,
+                desc: fld_desc.clone(),
+            };
+            // Get the result back
+            let val = CILNode::SubTrees(
+                [set_val].into(),
+                ld_field!(
+                    place_adress(destination, tyctx, body, method_instance, type_cache),
+                    fld_desc
+                )
+                .into(),
+            );
+            // Compare the result to comparand(aka `old`)
+            let cmp = eq!(val, old);
+            let fld_desc = field_descrptor(dst_ty.ty, 1, tyctx, method_instance, type_cache);
+            assert_eq!(*fld_desc.tpe(), Type::Bool);
+
+            CILRoot::SetField {
+                addr: place_adress(destination, tyctx, body, method_instance, type_cache),
+                value: cmp,
+                desc: fld_desc.clone(),
+            }
+        }
+        "xchg" | "xadd" | "xsub" | "and" | "or" | "xor" | "nand" | "max" | "min" | "umax"
+        | "umin" => {
+            let ptr = operand(0, type_cache);
+            let val = operand(1, type_cache);
+            place_set(
+                destination,
+                tyctx,
+                from_atomic(
+                    call!(atomic_builtin(op, &tpe), [ptr, to_atomic(val, &tpe)]),
+                    &tpe,
+                ),
+                body,
+                method_instance,
+                type_cache,
+            )
+        }
+        _ => todo!("Unsupported atomic intrinsic {fn_name}"),
+    }
+}
+fn atomic_builtin(op: &str, tpe: &Type) -> CallSite {
+    CallSite::builtin(
+        atomic_builtin_name(op, tpe),
+        atomic_builtin_sig(op, tpe),
+        true,
+    )
+}
+/// `Volatile` has overloads for all primitive types, but pointers must be passed as `nuint`.
+fn volatile_type(tpe: &Type) -> Type {
+    match tpe {
+        Type::Ptr(_) | Type::DelegatePtr(_) => Type::USize,
+        _ => tpe.clone(),
+    }
+}
+/// Converts a value of type `tpe` to the type the atomic builtins operate on.
+fn to_atomic(val: CILNode, tpe: &Type) -> CILNode {
+    match tpe {
+        Type::Ptr(_) | Type::DelegatePtr(_) => CILNode::TransmutePtr {
+            val: Box::new(val),
+            new_ptr: Box::new(atomic_type(tpe)),
+        },
+        _ => val,
+    }
+}
+/// Converts a value returned by an atomic builtin back to `tpe`.
+fn from_atomic(val: CILNode, tpe: &Type) -> CILNode {
+    match tpe {
+        Type::Ptr(_) | Type::DelegatePtr(_) => CILNode::TransmutePtr {
+            val: Box::new(val),
+            new_ptr: Box::new(tpe.clone()),
+        },
+        _ => val,
+    }
+}
@@ -7,25 +7,19 @@ use crate::{
     place::place_set,
     r#type::{tycache, DotnetTypeRef},
 };
-use rustc_middle::{
-    mir::{Body, Operand, Place},
-    ty::{Instance, TyCtxt, TyKind, UintTy},
-};
-use rustc_span::source_map::Spanned;
+use rustc_middle::ty::{TyKind, UintTy};
 use tycache::TyCache;
-pub fn bswap<'tyctx>(
-    args: &[Spanned<Operand<'tyctx>>],
-    destination: &Place<'tyctx>,
-    tyctx: TyCtxt<'tyctx>,
-    body: &'tyctx Body<'tyctx>,
-    method_instance: Instance<'tyctx>,
-    type_cache: &mut TyCache,
-) -> CILRoot {
-    debug_assert_eq!(
-        args.len(),
-        1,
-        "The intrinsic `bswap` MUST take in exactly 1 argument!"
-    );
+
+use super::IntrinsicCall;
+pub fn bswap<'tyctx>(call: &IntrinsicCall<'_, 'tyctx>, type_cache: &mut TyCache) -> CILRoot {
+    let IntrinsicCall {
+        args,
+        destination,
+        tyctx,
+        body,
+        method_instance,
+        ..
+    } = *call;
     let ty = args[0].node.ty(body, tyctx);
     let ty = crate::utilis::monomorphize(&method_instance, ty, tyctx);
     let tpe = type_cache.type_from_cache(ty, tyctx, Some(method_instance));
@@ -0,0 +1,202 @@
+use crate::{
+    call,
+    cil::CallSite,
+    cil_tree::{cil_node::CILNode, cil_root::CILRoot},
+    conv_f32, conv_f64,
+    function_sig::FnSig,
+    ldc_i32,
+    operand::handle_operand,
+    place::place_set,
+    r#type::{tycache, DotnetTypeRef, Type},
+};
+use tycache::TyCache;
+
+use super::IntrinsicCall;
+/// Lowers a float intrinsic(e.g. `sqrtf32` or `sinf64`) to the matching method of `System.MathF`(for `f32`) or `System.Math`(for `f64`).
+pub fn math<'tyctx>(call: &IntrinsicCall<'_, 'tyctx>, type_cache: &mut TyCache) -> CILRoot {
+    let IntrinsicCall {
+        name,
+        args,
+        destination,
+        tyctx,
+        body,
+        method_instance,
+        ..
+    } = *call;
+    let fn_name = name.as_str();
+    let (op, tpe, class) = if let Some(op) = fn_name.strip_suffix("f32") {
+        (op, Type::F32, DotnetTypeRef::mathf())
+    } else if let Some(op) = fn_name.strip_suffix("f64") {
+        (op, Type::F64, DotnetTypeRef::math())
+    } else {
+        panic!("The intrinsic `{fn_name}` does not operate on f32 or f64!")
+    };
+    let mut ops: Vec<_> = args
+        .iter()
+        .map(|arg| handle_operand(&arg.node, tyctx, body, method_instance, type_cache))
+        .collect();
+    let value = match op {
+        "powi" => {
+            // `MathF.Pow` is less precise, so `powi` is always computed using doubles.
+            let res = call!(
+                CallSite::boxed(
+                    Some(DotnetTypeRef::math()),
+                    "Pow".into(),
+                    FnSig::new(&[Type::F64, Type::F64], &Type::F64),
+                    true,
+                ),
+                [conv_f64!(ops[0].clone()), conv_f64!(ops[1].clone())]
+            );
+            if tpe == Type::F32 {
+                conv_f32!(res)
+            } else {
+                res
+            }
+        }
+        // There is no `Exp2` in `System.Math`.
+        "exp2" => {
+            let two = match tpe {
+                Type::F32 => CILNode::LdcF32(2.0),
+                _ => CILNode::LdcF64(2.0),
+            };
+            call!(
+                CallSite::boxed(
+                    Some(class),
+                    "Pow".into(),
+                    FnSig::new(&[tpe.clone(), tpe.clone()], &tpe),
+                    true,
+                ),
+                [two, ops.remove(0)]
+            )
+        }
+        // `Math.Min` and `Math.Max` propagate NaNs, while `minnum` and `maxnum` return the other operand, like `MinNumber` and `MaxNumber`.
+        "minnum" | "maxnum" => {
+            let class = match tpe {
+                Type::F32 => DotnetTypeRef::single(),
+                _ => DotnetTypeRef::double(),
+            };
+            let method = if op == "minnum" {
+                "MinNumber"
+            } else {
+                "MaxNumber"
+            };
+            call!(
+                CallSite::boxed(
+                    Some(class),
+                    method.into(),
+                    FnSig::new(&[tpe.clone(), tpe.clone()], &tpe),
+                    true,
+                ),
+                ops
+            )
+        }
+        "round" | "rint" | "nearbyint" | "roundeven" => {
+            // Rust's `round` rounds half-way cases away from zero, while the rest rounds them to even.
+            let mode = if op == "round" {
+                MIDPOINT_AWAY_FROM_ZERO
+            } else {
+                MIDPOINT_TO_EVEN
+            };
+            call!(
+                CallSite::boxed(
+                    Some(class),
+                    "Round".into(),
+                    FnSig::new(&[tpe.clone(), midpoint_rounding().into()], &tpe),
+                    true,
+                ),
+                [ops.remove(0), ldc_i32!(mode)]
+            )
+        }
+        _ => {
+            let method = match op {
+                "sqrt" => "Sqrt",
+                "sin" => "Sin",
+                "cos" => "Cos",
+                "pow" => "Pow",
+                "exp" => "Exp",
+                "log" => "Log",
+                "log10" => "Log10",
+                "log2" => "Log2",
+                "fma" => "FusedMultiplyAdd",
+                "fabs" => "Abs",
+                "copysign" => "CopySign",
+                "floor" => "Floor",
+                "ceil" => "Ceiling",
+                "trunc" => "Truncate",
+                _ => todo!("Unsupported float intrinsic {fn_name}"),
+            };
+            call!(
+                CallSite::boxed(
+                    Some(class),
+                    method.into(),
+                    FnSig::new(&vec![tpe.clone(); ops.len()], &tpe),
+                    true,
+                ),
+                ops
+            )
+        }
+    };
+    place_set(destination, tyctx, value, body, method_instance, type_cache)
+}
+/// `System.MidpointRounding.ToEven`
+const MIDPOINT_TO_EVEN: i32 = 0;
+/// `System.MidpointRounding.AwayFromZero`
+const MIDPOINT_AWAY_FROM_ZERO: i32 = 1;
+fn midpoint_rounding() -> DotnetTypeRef {
+    DotnetTypeRef::new(Some("System.Runtime"), "System.MidpointRounding")
+}
+/// Lowers the float arithmetic intrinsics which allow the compiler to assume more than IEEE does(`fadd_fast`, `fmul_algebraic`, ...).
+/// .NET can't use those assumptions, so they are lowered to ordinary arithmetic.
+pub fn arith<'tyctx>(call: &IntrinsicCall<'_, 'tyctx>, type_cache: &mut TyCache) -> CILRoot {
+    let IntrinsicCall {
+        name,
+        args,
+        destination,
+        tyctx,
+        body,
+        method_instance,
+        ..
+    } = *call;
+    let a = handle_operand(&args[0].node, tyctx, body, method_instance, type_cache);
+    let b = handle_operand(&args[1].node, tyctx, body, method_instance, type_cache);
+    let fn_name = name.as_str();
+    let op = fn_name
+        .strip_suffix("_fast")
+        .or_else(|| fn_name.strip_suffix("_algebraic"))
+        .unwrap_or(fn_name);
+    let value = match op {
+        "fadd" => a + b,
+        "fsub" => a - b,
+        "fmul" => a * b,
+        "fdiv" => CILNode::Div(a.into(), b.into()),
+        "frem" => CILNode::Rem(a.into(), b.into()),
+        _ => todo!("Unsupported float intrinsic {fn_name}"),
+    };
+    place_set(destination, tyctx, value, body, method_instance, type_cache)
+}
+pub fn float_to_int_unchecked<'tyctx>(
+    call: &IntrinsicCall<'_, 'tyctx>,
+    type_cache: &mut TyCache,
+) -> CILRoot {
+    let IntrinsicCall {
+        args,
+        destination,
+        tyctx,
+        body,
+        method_instance,
+        ..
+    } = *call;
+    let src = call.ty(0);
+    let src = type_cache.type_from_cache(src, tyctx, Some(method_instance));
+    let target = call.ty(1);
+    let target = type_cache.type_from_cache(target, tyctx, Some(method_instance));
+    let value = handle_operand(&args[0].node, tyctx, body, method_instance, type_cache);
+    place_set(
+        destination,
+        tyctx,
+        crate::casts::float_to_int(src, &target, value),
+        body,
+        method_instance,
+        type_cache,
+    )
+}
@@ -0,0 +1,192 @@
+use crate::{cil_tree::cil_root::CILRoot, function_sig::FnSig, r#type::TyCache};
+use rustc_hir::def::{DefKind, Res};
+use rustc_middle::{
+    mir::{Body, Operand, Place},
+    ty::{Instance, Ty, TyCtxt},
+};
+use rustc_span::{def_id::DefId, source_map::Spanned, sym, Symbol};
+use std::collections::HashMap;
+/// A call to an intrinsic, which is being lowered.
+pub struct IntrinsicCall<'a, 'tyctx> {
+    /// Name of the called intrinsic.
+    pub name: Symbol,
+    pub args: &'a [Spanned<Operand<'tyctx>>],
+    pub destination: &'a Place<'tyctx>,
+    pub tyctx: TyCtxt<'tyctx>,
+    pub body: &'tyctx Body<'tyctx>,
+    /// The method containing the call.
+    pub method_instance: Instance<'tyctx>,
+    /// The called intrinsic.
+    pub call_instance: Instance<'tyctx>,
+    pub signature: FnSig,
+    pub span: rustc_span::Span,
+}
+impl<'a, 'tyctx> IntrinsicCall<'a, 'tyctx> {
+    /// The `idx`-th generic argument of the intrinsic, if it is a type.
+    pub fn generic_ty_at(&self, idx: usize) -> Option<Ty<'tyctx>> {
+        let ty = self.call_instance.args.get(idx)?.as_type()?;
+        Some(crate::utilis::monomorphize(
+            &self.method_instance,
+            ty,
+            self.tyctx,
+        ))
+    }
+    /// The first generic argument of the intrinsic, if it has one.
+    pub fn generic_ty(&self) -> Option<Ty<'tyctx>> {
+        self.generic_ty_at(0)
+    }
+    /// The `idx`-th generic argument of the intrinsic. Panics if the intrinsic has no such type argument.
+    pub fn ty(&self, idx: usize) -> Ty<'tyctx> {
+        self.generic_ty_at(idx).unwrap_or_else(|| {
+            panic!(
+                "The intrinsic `{}` works only on types, but has no type argument {idx}!",
+                self.name
+            )
+        })
+    }
+}
+/// Lowers a call to an intrinsic.
+pub type IntrinsicHandler = for<'a, 'tyctx> fn(&IntrinsicCall<'a, 'tyctx>, &mut TyCache) -> CILRoot;
+/// Constraint on the first generic argument of an intrinsic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntrinsicTy {
+    /// The intrinsic is not generic.
+    None,
+    /// The intrinsic works on any type.
+    Any,
+    /// The intrinsic works only on integers.
+    Int,
+    /// The intrinsic works only on floats.
+    Float,
+}
+/// Description of a supported intrinsic.
+pub struct IntrinsicDesc {
+    name: &'static str,
+    args: usize,
+    ty: IntrinsicTy,
+    handler: IntrinsicHandler,
+}
+impl IntrinsicDesc {
+    pub const fn new(
+        name: &'static str,
+        args: usize,
+        ty: IntrinsicTy,
+        handler: IntrinsicHandler,
+    ) -> Self {
+        Self {
+            name,
+            args,
+            ty,
+            handler,
+        }
+    }
+    /// Checks that the call matches the declared argument count and type constraints of this intrinsic.
+    /// Reports an error at the call site, and returns `false`, if it does not.
+    pub fn check(&self, call: &IntrinsicCall) -> bool {
+        let error = |msg: String| {
+            call.tyctx.dcx().span_err(call.span, msg);
+            false
+        };
+        if call.args.len() != self.args {
+            return error(format!(
+                "the intrinsic `{}` must take in exactly {} argument(s), but got {}",
+                call.name,
+                self.args,
+                call.args.len()
+            ));
+        }
+        let ty = match self.ty {
+            IntrinsicTy::None => return true,
+            IntrinsicTy::Any | IntrinsicTy::Int | IntrinsicTy::Float => call.generic_ty(),
+        };
+        let Some(ty) = ty else {
+            return error(format!(
+                "the intrinsic `{}` must have a type argument",
+                call.name
+            ));
+        };
+        match self.ty {
+            IntrinsicTy::Int if !ty.is_integral() => error(format!(
+                "the intrinsic `{}` works only on integers, not `{ty}`",
+                call.name
+            )),
+            IntrinsicTy::Float if !ty.is_floating_point() => error(format!(
+                "the intrinsic `{}` works only on floats, not `{ty}`",
+                call.name
+            )),
+            _ => true,
+        }
+    }
+    pub fn handle(&self, call: &IntrinsicCall, type_cache: &mut TyCache) -> CILRoot {
+        (self.handler)(call, type_cache)
+    }
+}
+/// All supported intrinsics, keyed by their interned names.
+pub struct IntrinsicRegistry {
+    by_name: HashMap<Symbol, &'static IntrinsicDesc>,
+}
+impl IntrinsicRegistry {
+    /// Interns the names of all intrinsics in `descs`.
+    pub fn new(descs: &[&'static [IntrinsicDesc]]) -> Self {
+        let mut by_name = HashMap::with_capacity(descs.iter().map(|descs| descs.len()).sum());
+        for desc in descs.iter().copied().flatten() {
+            let prev = by_name.insert(Symbol::intern(desc.name), desc);
+            assert!(
+                prev.is_none(),
+                "Intrinsic `{}` registered twice!",
+                desc.name
+            );
+        }
+        Self { by_name }
+    }
+    /// Finds the description of the intrinsic `name`.
+    pub fn find(&self, name: Symbol) -> Option<&'static IntrinsicDesc> {
+        self.by_name.get(&name).copied()
+    }
+}
+/// Returns the names of all intrinsics exposed by `core::intrinsics`(including its submodules, like `simd`),
+/// which are not present in `registry`.
+pub(super) fn unsupported_in(registry: &IntrinsicRegistry, tyctx: TyCtxt) -> Vec<Symbol> {
+    let Some(core) = tyctx
+        .crates(())
+        .iter()
+        .find(|krate| tyctx.crate_name(**krate) == sym::core)
+    else {
+        // `core` is the crate being compiled, or is not linked at all.
+        return vec![];
+    };
+    let Some(intrinsics) = tyctx
+        .module_children(core.as_def_id())
+        .iter()
+        .find(|child| child.ident.name == sym::intrinsics)
+        .and_then(|child| child.res.opt_def_id())
+    else {
+        return vec![];
+    };
+    let mut unsupported = Vec::new();
+    collect_unsupported(registry, tyctx, intrinsics, &mut unsupported);
+    unsupported.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    unsupported.dedup();
+    unsupported
+}
+fn collect_unsupported(
+    registry: &IntrinsicRegistry,
+    tyctx: TyCtxt,
+    module: DefId,
+    unsupported: &mut Vec<Symbol>,
+) {
+    for child in tyctx.module_children(module) {
+        match child.res {
+            Res::Def(DefKind::Mod, submodule) => {
+                collect_unsupported(registry, tyctx, submodule, unsupported);
+            }
+            Res::Def(DefKind::Fn, def_id)
+                if tyctx.is_intrinsic(def_id, child.ident.name)
+                    && registry.find(child.ident.name).is_none() =>
+            {
+                unsupported.push(child.ident.name);
+            }
+            _ => (),
+        }
+    }
+}
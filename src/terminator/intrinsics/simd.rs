@@ -0,0 +1,522 @@
+use crate::{
+    call,
+    cil::CallSite,
+    cil_tree::{cil_node::CILNode, cil_root::CILRoot},
+    eq,
+    function_sig::FnSig,
+    gt_un, ldc_i32, ldc_u32,
+    operand::handle_operand,
+    place::place_set,
+    r#type::{tycache, DotnetTypeRef, Type},
+};
+use rustc_middle::{
+    mir::{ConstValue, Operand},
+    ty::{ParamEnv, Ty},
+};
+use tycache::TyCache;
+
+use super::{IntrinsicCall, IntrinsicDesc, IntrinsicTy};
+/// All the supported `simd_*` intrinsics.
+pub(super) static SIMD: &[IntrinsicDesc] = &[
+    IntrinsicDesc::new("simd_add", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_sub", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_mul", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_div", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_rem", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_shl", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_shr", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_and", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_or", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_xor", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_fmin", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_fmax", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_fma", 3, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_neg", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_fabs", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_fsqrt", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_floor", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_ceil", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_trunc", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_fsin", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_fcos", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_fexp", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_flog", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_eq", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_ne", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_lt", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_le", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_gt", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_ge", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_select", 3, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_extract", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_insert", 3, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_shuffle", 3, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_cast", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_as", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_reduce_add_ordered", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new(
+        "simd_reduce_add_unordered",
+        1,
+        IntrinsicTy::Any,
+        handle_simd,
+    ),
+    IntrinsicDesc::new("simd_reduce_mul_ordered", 2, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new(
+        "simd_reduce_mul_unordered",
+        1,
+        IntrinsicTy::Any,
+        handle_simd,
+    ),
+    IntrinsicDesc::new("simd_reduce_and", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_reduce_or", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_reduce_xor", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_reduce_max", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_reduce_min", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_reduce_all", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_reduce_any", 1, IntrinsicTy::Any, handle_simd),
+    IntrinsicDesc::new("simd_bitmask", 1, IntrinsicTy::Any, handle_simd),
+];
+/// A Rust SIMD type(`repr(simd)`). Values of it are stored like any other struct, and get reinterpreted as the .NET vector
+/// of the same size only while an intrinsic operates on them, so that field accesses and aggregates keep working.
+struct SimdTy {
+    lanes: u64,
+    element: Type,
+    /// Size of the whole vector, in bytes.
+    size: u64,
+    /// The .NET type of the Rust SIMD type.
+    rust: Type,
+}
+impl SimdTy {
+    fn new<'tyctx>(
+        ty: Ty<'tyctx>,
+        call: &IntrinsicCall<'_, 'tyctx>,
+        type_cache: &mut TyCache,
+    ) -> Self {
+        let rust = type_cache.type_from_cache(ty, call.tyctx, Some(call.method_instance));
+        let (lanes, element) = ty.simd_size_and_type(call.tyctx);
+        let element = type_cache.type_from_cache(element, call.tyctx, Some(call.method_instance));
+        let size = crate::utilis::compiletime_sizeof(ty, call.tyctx);
+        assert!(
+            DotnetTypeRef::is_simd_vector(&element, size),
+            "The SIMD type {ty:?} has no .NET equivalent!"
+        );
+        Self {
+            lanes,
+            element,
+            size,
+            rust,
+        }
+    }
+    /// Reinterprets `value`, of the Rust SIMD type, as the .NET vector.
+    fn to_vector(&self, value: CILNode) -> CILNode {
+        reinterpret_value(value, self.rust.clone(), self.tpe())
+    }
+    /// Reinterprets `vector` as a value of the Rust SIMD type.
+    fn from_vector(&self, vector: CILNode) -> CILNode {
+        reinterpret_value(vector, self.tpe(), self.rust.clone())
+    }
+    fn tpe(&self) -> Type {
+        DotnetTypeRef::simd_vector(self.element.clone(), self.size).into()
+    }
+    /// The type of this vector in the signatures of generic vector methods(`VectorN<!!0>`).
+    fn generic(&self) -> Type {
+        DotnetTypeRef::simd_vector(Type::CallGenericArg(0), self.size).into()
+    }
+    /// Calls the generic method `name` of the class `VectorN`, instantiated with the element type of this vector.
+    fn call(&self, name: &str, inputs: &[Type], output: &Type, args: Vec<CILNode>) -> CILNode {
+        let mut site = CallSite::new(
+            Some(DotnetTypeRef::simd_helper(self.size)),
+            name.into(),
+            FnSig::new(inputs, output),
+            true,
+        );
+        site.set_generics(vec![self.element.clone()]);
+        call!(site, args)
+    }
+    /// Calls the generic method `name`, taking `args.len()` vectors, and returning a vector.
+    fn elementwise(&self, name: &str, args: Vec<CILNode>) -> CILNode {
+        let inputs = vec![self.generic(); args.len()];
+        self.call(name, &inputs, &self.generic(), args)
+    }
+    /// Returns the value of the lane `lane` of `vector`.
+    fn lane(&self, vector: CILNode, lane: u64) -> CILNode {
+        self.call(
+            "GetElement",
+            &[self.generic(), Type::I32],
+            &Type::CallGenericArg(0),
+            vec![
+                vector,
+                ldc_i32!(i32::try_from(lane).expect("Too many SIMD lanes!")),
+            ],
+        )
+    }
+    /// Returns the values of all lanes of `vector`.
+    fn lanes(&self, vector: &CILNode) -> Vec<CILNode> {
+        (0..self.lanes)
+            .map(|lane| self.lane(vector.clone(), lane))
+            .collect()
+    }
+    /// Creates a vector from the values of its lanes.
+    fn create(&self, lanes: Vec<CILNode>) -> CILNode {
+        assert_eq!(lanes.len() as u64, self.lanes);
+        let inputs = vec![self.element.clone(); lanes.len()];
+        call!(
+            CallSite::new(
+                Some(DotnetTypeRef::simd_helper(self.size)),
+                "Create".into(),
+                FnSig::new(&inputs, &self.tpe()),
+                true,
+            ),
+            lanes
+        )
+    }
+    /// Reinterprets the bits of `vector` as a vector with elements of type `target`.
+    fn reinterpret(&self, vector: CILNode, target: &Type) -> CILNode {
+        if *target == self.element {
+            return vector;
+        }
+        let name = match target {
+            Type::U8 => "AsByte",
+            Type::I8 => "AsSByte",
+            Type::U16 => "AsUInt16",
+            Type::I16 => "AsInt16",
+            Type::U32 => "AsUInt32",
+            Type::I32 => "AsInt32",
+            Type::U64 => "AsUInt64",
+            Type::I64 => "AsInt64",
+            Type::USize => "AsNUInt",
+            Type::ISize => "AsNInt",
+            Type::F32 => "AsSingle",
+            Type::F64 => "AsDouble",
+            _ => panic!("{target:?} can't be a SIMD element!"),
+        };
+        self.call(
+            name,
+            &[self.generic()],
+            &DotnetTypeRef::simd_vector(target.clone(), self.size).into(),
+            vec![vector],
+        )
+    }
+    /// A mask with the bit `n` set if the most significant bit of lane `n` is set.
+    fn most_significant_bits(&self, vector: CILNode) -> CILNode {
+        self.call(
+            "ExtractMostSignificantBits",
+            &[self.generic()],
+            &Type::U32,
+            vec![vector],
+        )
+    }
+    fn is_float(&self) -> bool {
+        matches!(self.element, Type::F32 | Type::F64)
+    }
+    fn is_signed(&self) -> bool {
+        matches!(
+            self.element,
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::ISize | Type::F32 | Type::F64
+        )
+    }
+}
+/// Reinterprets the bits of `value`(of type `from`) as a value of type `to`, by storing it in a temporary local and loading it back.
+fn reinterpret_value(value: CILNode, from: Type, to: Type) -> CILNode {
+    CILNode::TemporaryLocal(Box::new((
+        from,
+        [CILRoot::SetTMPLocal { value }].into(),
+        CILNode::LdObj {
+            ptr: Box::new(CILNode::TransmutePtr {
+                val: Box::new(CILNode::LoadAddresOfTMPLocal),
+                new_ptr: Box::new(Type::Ptr(Box::new(to.clone()))),
+            }),
+            obj: Box::new(to),
+        },
+    )))
+}
+/// Lowers the generic `simd_*` intrinsics listed in [`SIMD`]. Operations with a direct equivalent are lowered to methods of `System.Runtime.Intrinsics.VectorN`,
+/// the rest is done lane by lane.
+pub fn handle_simd<'tyctx>(call: &IntrinsicCall<'_, 'tyctx>, type_cache: &mut TyCache) -> CILRoot {
+    let IntrinsicCall {
+        name,
+        args,
+        destination,
+        tyctx,
+        body,
+        method_instance,
+        ..
+    } = *call;
+    let fn_name = name.as_str();
+    let generic = |idx: usize| call.ty(idx);
+    let operand = |idx: usize, type_cache: &mut TyCache| {
+        handle_operand(&args[idx].node, tyctx, body, method_instance, type_cache)
+    };
+    // Loads a SIMD operand as a .NET vector.
+    let vector_operand = |vector: &SimdTy, idx: usize, type_cache: &mut TyCache| {
+        vector.to_vector(operand(idx, type_cache))
+    };
+    let value = match fn_name {
+        "simd_add" | "simd_sub" | "simd_mul" | "simd_div" | "simd_and" | "simd_or" | "simd_xor"
+        | "simd_fmin" | "simd_fmax" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            let method = match fn_name {
+                "simd_add" => "Add",
+                "simd_sub" => "Subtract",
+                "simd_mul" => "Multiply",
+                "simd_div" => "Divide",
+                "simd_and" => "BitwiseAnd",
+                "simd_or" => "BitwiseOr",
+                "simd_xor" => "Xor",
+                "simd_fmin" => "Min",
+                "simd_fmax" => "Max",
+                _ => unreachable!(),
+            };
+            let lhs = vector_operand(&vector, 0, type_cache);
+            let rhs = vector_operand(&vector, 1, type_cache);
+            vector.from_vector(vector.elementwise(method, vec![lhs, rhs]))
+        }
+        "simd_neg" | "simd_fabs" | "simd_fsqrt" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            let method = match fn_name {
+                "simd_neg" => "Negate",
+                "simd_fabs" => "Abs",
+                "simd_fsqrt" => "Sqrt",
+                _ => unreachable!(),
+            };
+            let arg = vector_operand(&vector, 0, type_cache);
+            vector.from_vector(vector.elementwise(method, vec![arg]))
+        }
+        "simd_eq" | "simd_ne" | "simd_lt" | "simd_le" | "simd_gt" | "simd_ge" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            let mask = SimdTy::new(generic(1), call, type_cache);
+            let method = match fn_name {
+                "simd_eq" | "simd_ne" => "Equals",
+                "simd_lt" => "LessThan",
+                "simd_le" => "LessThanOrEqual",
+                "simd_gt" => "GreaterThan",
+                "simd_ge" => "GreaterThanOrEqual",
+                _ => unreachable!(),
+            };
+            let lhs = vector_operand(&vector, 0, type_cache);
+            let rhs = vector_operand(&vector, 1, type_cache);
+            let mut res = vector.elementwise(method, vec![lhs, rhs]);
+            if fn_name == "simd_ne" {
+                res = vector.elementwise("OnesComplement", vec![res]);
+            }
+            mask.from_vector(vector.reinterpret(res, &mask.element))
+        }
+        "simd_select" => {
+            let mask = SimdTy::new(generic(0), call, type_cache);
+            let vector = SimdTy::new(generic(1), call, type_cache);
+            // Rust requires all lanes of the mask to be either all zeroes or all ones, so a bitwise select is enough.
+            let mask = mask.reinterpret(vector_operand(&mask, 0, type_cache), &vector.element);
+            let if_true = vector_operand(&vector, 1, type_cache);
+            let if_false = vector_operand(&vector, 2, type_cache);
+            vector
+                .from_vector(vector.elementwise("ConditionalSelect", vec![mask, if_true, if_false]))
+        }
+        "simd_extract" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            vector.call(
+                "GetElement",
+                &[vector.generic(), Type::I32],
+                &Type::CallGenericArg(0),
+                vec![
+                    vector_operand(&vector, 0, type_cache),
+                    operand(1, type_cache),
+                ],
+            )
+        }
+        "simd_insert" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            vector.from_vector(vector.call(
+                "WithElement",
+                &[vector.generic(), Type::I32, Type::CallGenericArg(0)],
+                &vector.generic(),
+                vec![
+                    vector_operand(&vector, 0, type_cache),
+                    operand(1, type_cache),
+                    operand(2, type_cache),
+                ],
+            ))
+        }
+        "simd_shuffle" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            let out = SimdTy::new(generic(2), call, type_cache);
+            let x = vector_operand(&vector, 0, type_cache);
+            let y = vector_operand(&vector, 1, type_cache);
+            let lanes = shuffle_indices(call, out.lanes)
+                .into_iter()
+                .map(|idx| {
+                    if idx < vector.lanes {
+                        vector.lane(x.clone(), idx)
+                    } else {
+                        vector.lane(y.clone(), idx - vector.lanes)
+                    }
+                })
+                .collect();
+            out.from_vector(out.create(lanes))
+        }
+        "simd_shl" | "simd_shr" | "simd_rem" | "simd_fma" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            let args: Vec<_> = (0..args.len())
+                .map(|idx| vector.lanes(&vector_operand(&vector, idx, type_cache)))
+                .collect();
+            let lanes = (0..vector.lanes as usize)
+                .map(|lane| {
+                    let a = Box::new(args[0][lane].clone());
+                    let b = Box::new(args[1][lane].clone());
+                    match (fn_name, vector.is_signed()) {
+                        ("simd_shl", _) => CILNode::Shl(a, b),
+                        ("simd_shr", true) => CILNode::Shr(a, b),
+                        ("simd_shr", false) => CILNode::ShrUn(a, b),
+                        ("simd_rem", true) => CILNode::Rem(a, b),
+                        ("simd_rem", false) => CILNode::RemUn(a, b),
+                        ("simd_fma", _) => *a * *b + args[2][lane].clone(),
+                        _ => unreachable!(),
+                    }
+                })
+                .collect();
+            vector.from_vector(vector.create(lanes))
+        }
+        "simd_floor" | "simd_ceil" | "simd_trunc" | "simd_fsin" | "simd_fcos" | "simd_fexp"
+        | "simd_flog" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            let math = match vector.element {
+                Type::F32 => DotnetTypeRef::mathf(),
+                Type::F64 => DotnetTypeRef::math(),
+                _ => panic!("The intrinsic `{fn_name}` works only on floats!"),
+            };
+            let method = match fn_name {
+                "simd_floor" => "Floor",
+                "simd_ceil" => "Ceiling",
+                "simd_trunc" => "Truncate",
+                "simd_fsin" => "Sin",
+                "simd_fcos" => "Cos",
+                "simd_fexp" => "Exp",
+                "simd_flog" => "Log",
+                _ => unreachable!(),
+            };
+            let site = CallSite::new(
+                Some(math),
+                method.into(),
+                FnSig::new(&[vector.element.clone()], &vector.element),
+                true,
+            );
+            let lanes = vector
+                .lanes(&vector_operand(&vector, 0, type_cache))
+                .into_iter()
+                .map(|lane| call!(site.clone(), [lane]))
+                .collect();
+            vector.from_vector(vector.create(lanes))
+        }
+        "simd_cast" | "simd_as" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            let out = SimdTy::new(generic(1), call, type_cache);
+            let lanes = vector
+                .lanes(&vector_operand(&vector, 0, type_cache))
+                .into_iter()
+                .map(|lane| match (vector.is_float(), out.is_float()) {
+                    (false, false) => {
+                        crate::casts::int_to_int(vector.element.clone(), &out.element, lane)
+                    }
+                    (false, true) => {
+                        crate::casts::int_to_float(vector.element.clone(), &out.element, lane)
+                    }
+                    (true, false) => {
+                        crate::casts::float_to_int(vector.element.clone(), &out.element, lane)
+                    }
+                    (true, true) => match out.element {
+                        Type::F32 => CILNode::ConvF32(lane.into()),
+                        _ => CILNode::ConvF64(lane.into()),
+                    },
+                })
+                .collect();
+            out.from_vector(out.create(lanes))
+        }
+        "simd_reduce_add_ordered"
+        | "simd_reduce_add_unordered"
+        | "simd_reduce_mul_ordered"
+        | "simd_reduce_mul_unordered"
+        | "simd_reduce_and"
+        | "simd_reduce_or"
+        | "simd_reduce_xor"
+        | "simd_reduce_max"
+        | "simd_reduce_min" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            let mut lanes = vector
+                .lanes(&vector_operand(&vector, 0, type_cache))
+                .into_iter();
+            // The ordered reductions start with an accumulator.
+            let init = if args.len() == 2 {
+                operand(1, type_cache)
+            } else {
+                lanes.next().expect("SIMD vectors can't be empty!")
+            };
+            let math = CallSite::new(
+                Some(DotnetTypeRef::math()),
+                if fn_name == "simd_reduce_max" {
+                    "Max"
+                } else {
+                    "Min"
+                }
+                .into(),
+                FnSig::new(
+                    &[vector.element.clone(), vector.element.clone()],
+                    &vector.element,
+                ),
+                true,
+            );
+            lanes.fold(init, |acc, lane| match fn_name {
+                "simd_reduce_add_ordered" | "simd_reduce_add_unordered" => acc + lane,
+                "simd_reduce_mul_ordered" | "simd_reduce_mul_unordered" => acc * lane,
+                "simd_reduce_and" => CILNode::And(acc.into(), lane.into()),
+                "simd_reduce_or" => acc | lane,
+                "simd_reduce_xor" => CILNode::XOr(acc.into(), lane.into()),
+                _ => call!(math.clone(), [acc, lane]),
+            })
+        }
+        "simd_reduce_all" | "simd_reduce_any" | "simd_bitmask" => {
+            let vector = SimdTy::new(generic(0), call, type_cache);
+            let bits = vector.most_significant_bits(vector_operand(&vector, 0, type_cache));
+            match fn_name {
+                "simd_reduce_all" => eq!(bits, ldc_u32!(u32::MAX >> (32 - vector.lanes))),
+                "simd_reduce_any" => gt_un!(bits, ldc_u32!(0)),
+                _ => {
+                    let out = type_cache.type_from_cache(generic(1), tyctx, Some(method_instance));
+                    crate::casts::int_to_int(Type::U32, &out, bits)
+                }
+            }
+        }
+        _ => unreachable!("The SIMD intrinsic {fn_name} is not registered!"),
+    };
+    place_set(destination, tyctx, value, body, method_instance, type_cache)
+}
+/// Reads the constant lane indices of a `simd_shuffle`.
+fn shuffle_indices(call: &IntrinsicCall, lanes: u64) -> Vec<u64> {
+    let Operand::Constant(indices) = &call.args[2].node else {
+        panic!("The indices of `simd_shuffle` must be a constant!");
+    };
+    let indices = crate::utilis::monomorphize(&call.method_instance, indices.const_, call.tyctx);
+    let bytes: Vec<u8> = match indices
+        .eval(call.tyctx, ParamEnv::reveal_all(), call.span)
+        .expect("Could not evaluate the indices of `simd_shuffle`!")
+    {
+        ConstValue::Indirect { alloc_id, offset } => {
+            let alloc = call.tyctx.global_alloc(alloc_id).unwrap_memory();
+            let start = offset.bytes_usize();
+            alloc
+                .inner()
+                .inspect_with_uninit_and_ptr_outside_interpreter(start..start + lanes as usize * 4)
+                .into()
+        }
+        ConstValue::Scalar(scalar) => scalar
+            .to_bits(scalar.size())
+            .expect("The indices of `simd_shuffle` can't be a pointer!")
+            .to_le_bytes()[..lanes as usize * 4]
+            .into(),
+        ConstValue::ZeroSized | ConstValue::Slice { .. } => {
+            panic!("Invalid indices of `simd_shuffle`:{indices:?}")
+        }
+    };
+    bytes
+        .chunks_exact(4)
+        .map(|idx| u64::from(u32::from_le_bytes(idx.try_into().unwrap())))
+        .collect()
+}
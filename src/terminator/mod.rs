@@ -12,7 +12,7 @@ use rustc_middle::{
 };
 
 mod call;
-mod intrinsics;
+pub(crate) mod intrinsics;
 pub fn handle_call_terminator<'tycxt>(
     terminator: &Terminator<'tycxt>,
     body: &'tycxt Body<'tycxt>,
@@ -26,7 +26,14 @@ pub fn handle_call_terminator<'tycxt>(
     target: Option<BasicBlock>,
 ) -> Vec<CILTree> {
     let mut trees = Vec::new();
-
+    crate::place::check_gcref_store(
+        destination,
+        terminator.source_info.span,
+        tyctx,
+        method,
+        method_instance,
+        type_cache,
+    );
     let func_ty = match func {
         Operand::Constant(fn_const) => fn_const.ty(),
         Operand::Copy(called) | Operand::Move(called) => {
@@ -170,7 +177,13 @@ pub fn handle_terminator<'ctx>(
                 CILRoot::VoidRet.into()
             } else {
                 CILRoot::Ret {
-                    tree: CILNode::LDLoc(0),
+                    tree: crate::place::local_get_aligned(
+                        0,
+                        method,
+                        tyctx,
+                        &method_instance,
+                        type_cache,
+                    ),
                 }
                 .into()
             }
@@ -287,13 +300,21 @@ pub fn handle_terminator<'ctx>(
             }
             .into()
         }
-        TerminatorKind::CoroutineDrop {} => todo!("Can't drop corutines yet!"),
+        // The coroutine state transform lowers `Yield` into a write to the `CoroutineState` return place followed by a `Return`, and `CoroutineDrop`
+        // into a `Return` from the drop shim. Those terminators may only survive in MIR of coroutines that are never resumed, so reaching them is an error.
+        TerminatorKind::CoroutineDrop {} => {
+            let loc = terminator.source_info.span;
+            rustc_middle::ty::print::with_no_trimmed_paths! {CILRoot::throw(&format!("CoroutineDrop not removed by the state transform reached at {loc:?}!")).into()}
+        }
         TerminatorKind::Yield {
             value: _,
             resume: _,
             resume_arg: _,
             drop: _,
-        } => todo!("Can't yeld yet!"), //_ => todo!("Unhandled terminator kind {kind:?}", kind = terminator.kind),
+        } => {
+            let loc = terminator.source_info.span;
+            rustc_middle::ty::print::with_no_trimmed_paths! {CILRoot::throw(&format!("Yield not removed by the state transform reached at {loc:?}!")).into()}
+        } //_ => todo!("Unhandled terminator kind {kind:?}", kind = terminator.kind),
     };
     let last = res.last().unwrap().root();
     assert!(
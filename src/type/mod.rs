@@ -24,8 +24,10 @@ pub fn mangle(tpe: &Type) -> std::borrow::Cow<'static, str> {
         Type::I64 => "i64".into(),
         Type::I128 => "i128".into(),
         Type::ISize => "is".into(),
+        Type::F16 => "f16".into(),
         Type::F32 => "f32".into(),
         Type::F64 => "f64".into(),
+        Type::F128 => "f128".into(),
         Type::Ptr(inner) => format!("p{inner}", inner = mangle(inner)).into(),
         Type::DotnetType(tpe) => {
             assert!(
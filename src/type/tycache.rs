@@ -2,12 +2,12 @@ use super::{tuple_name, tuple_typedef, DotnetTypeRef, Type, TypeDef};
 use crate::{
     access_modifier::AccessModifer,
     function_sig::FnSig,
-    r#type::{closure_typedef, escape_field_name},
+    r#type::{closure_typedef, coroutine_typedef, escape_field_name},
     utilis::adt::FieldOffsetIterator,
     IString,
 };
 use rustc_middle::ty::{
-    AdtDef, AdtKind, GenericArg, Instance, List, ParamEnv, Ty, TyCtxt, TyKind, UintTy,
+    AdtDef, AdtKind, DynKind, GenericArg, Instance, List, ParamEnv, Ty, TyCtxt, TyKind, UintTy,
 };
 use rustc_span::def_id::DefId;
 use std::collections::HashMap;
@@ -16,6 +16,8 @@ pub struct TyCache {
     type_def_cache: HashMap<IString, TypeDef>,
     cycle_prevention: Vec<IString>,
     ptr_components: Option<DefId>,
+    /// Locals of the method being compiled which are stored in stack buffers, and the locals holding the addresses of those buffers.
+    aligned_locals: HashMap<usize, u32>,
 }
 fn create_typedef<'tyctx>(
     _cache: &mut TyCache,
@@ -39,11 +41,28 @@ impl TyCache {
             type_def_cache: HashMap::new(),
             cycle_prevention: vec![],
             ptr_components: None,
+            aligned_locals: HashMap::new(),
         }
     }
     pub fn defs(&self) -> impl Iterator<Item = &TypeDef> {
         self.type_def_cache.values()
     }
+    /// Returns the definition of `tpe`, if it is defined by this cache.
+    #[must_use]
+    pub fn type_def(&self, tpe: &DotnetTypeRef) -> Option<&TypeDef> {
+        self.type_def_cache.get(tpe.name_path())
+    }
+    /// Checks if `tpe` is, or contains(directly or in any of its fields) a reference to a GC-managed object.
+    #[must_use]
+    pub fn contains_gcref(&self, tpe: &Type) -> bool {
+        match tpe {
+            Type::DotnetType(dref) if dref.asm().is_none() => self
+                .type_def_cache
+                .get(dref.name_path())
+                .is_some_and(|def| def.fields().iter().any(|(_, fld)| self.contains_gcref(fld))),
+            _ => tpe.is_gcref(),
+        }
+    }
     #[must_use]
     /// Gets the definition ID of the `PtrComponents` type.
     /// # Panics
@@ -93,6 +112,14 @@ impl TyCache {
         self.cycle_prevention.pop();
         DotnetTypeRef::new(None, name)
     }
+    /// Sets the locals stored in stack buffers of the method about to be compiled. See [`crate::place::stack_buffer_locals`].
+    pub(crate) fn set_aligned_locals(&mut self, aligned_locals: HashMap<usize, u32>) {
+        self.aligned_locals = aligned_locals;
+    }
+    /// If `local` of the method being compiled is stored in a stack buffer, returns the local holding the address of that buffer.
+    pub(crate) fn aligned_local_ptr(&self, local: usize) -> Option<u32> {
+        self.aligned_locals.get(&local).copied()
+    }
     pub fn recover_from_panic(&mut self) {
         self.cycle_prevention.clear();
     }
@@ -133,7 +160,7 @@ impl TyCache {
         let explicit_offsets =
             crate::utilis::adt::FieldOffsetIterator::fields((*layout.layout.0).clone()).collect();
         //let to_string = create_to_string(adt, subst, adt_ty, self, method, tyctx);
-        TypeDef::new(
+        let mut def = TypeDef::new(
             access,
             name.into(),
             vec![],
@@ -143,7 +170,11 @@ impl TyCache {
             0,
             None,
             Some(layout.layout.size().bytes()),
-        )
+        );
+        if *crate::config::RANDOMIZE_LAYOUT && !adt.repr().c() {
+            def.shuffle_fields();
+        }
+        def
     }
     fn union_<'tyctx>(
         &mut self,
@@ -172,10 +203,21 @@ impl TyCache {
                 value: adt_ty,
             })
             .expect("Could not get type layout!");
-        let explicit_offsets =
+        // All fields of a union overlap, so they all must be placed at offset 0. The explicit size makes the union
+        // exactly as big as its Rust counterpart, even if its aligement is greater than the one of any of its fields.
+        assert!(
+            matches!(layout.fields, rustc_target::abi::FieldsShape::Union(_)),
+            "Union {name} does not have a union layout: {:?}",
+            layout.fields
+        );
+        let explicit_offsets: Vec<u32> =
             crate::utilis::adt::FieldOffsetIterator::fields((*layout.layout.0).clone()).collect();
-
-        TypeDef::new(
+        assert!(
+            explicit_offsets.len() == fields.len()
+                && explicit_offsets.iter().all(|offset| *offset == 0),
+            "Union {name} has fields at non-zero offsets: {explicit_offsets:?}"
+        );
+        let mut def = TypeDef::new(
             access,
             name.into(),
             vec![],
@@ -185,7 +227,11 @@ impl TyCache {
             0,
             None,
             Some(layout.layout.size().bytes()),
-        )
+        );
+        if *crate::config::RANDOMIZE_LAYOUT && !adt.repr().c() {
+            def.shuffle_fields();
+        }
+        def
     }
     fn enum_<'tyctx>(
         &mut self,
@@ -290,7 +336,7 @@ impl TyCache {
             explicit_offsets.extend(field_offsets);
         }
         assert_eq!(fields.len(), explicit_offsets.len());
-        TypeDef::new(
+        let mut def = TypeDef::new(
             access,
             enum_name.into(),
             vec![],
@@ -300,7 +346,11 @@ impl TyCache {
             0,
             None,
             Some(layout.layout.size().bytes()),
-        )
+        );
+        if *crate::config::RANDOMIZE_LAYOUT && !adt.repr().c() {
+            def.shuffle_fields();
+        }
+        def
     }
     pub fn slice_ty<'tyctx>(
         &mut self,
@@ -380,6 +430,36 @@ impl TyCache {
                 }
                 DotnetTypeRef::new(None, &name).into()
             }
+            TyKind::Coroutine(def, args) => {
+                let name = crate::r#type::coroutine_name(*def, args, tyctx);
+                if !self.type_def_cache.contains_key(&name) {
+                    let coroutine = args.as_coroutine();
+                    let upvars: Box<[_]> = coroutine
+                        .upvar_tys()
+                        .iter()
+                        .map(|ty| self.type_from_cache(ty, tyctx, method))
+                        .collect();
+                    let variants: Vec<Vec<_>> = coroutine
+                        .state_tys(*def, tyctx)
+                        .map(|variant| {
+                            variant
+                                .map(|ty| self.type_from_cache(ty, tyctx, method))
+                                .collect()
+                        })
+                        .collect();
+                    let layout = tyctx
+                        .layout_of(rustc_middle::ty::ParamEnvAnd {
+                            param_env: ParamEnv::reveal_all(),
+                            value: ty,
+                        })
+                        .expect("Could not get type layout!");
+                    self.type_def_cache.insert(
+                        name.clone(),
+                        coroutine_typedef(name.clone(), &upvars, &variants, layout.layout, tyctx),
+                    );
+                }
+                DotnetTypeRef::new(None, &name).into()
+            }
             TyKind::Never => Type::Void,
             TyKind::RawPtr(typ, _) => {
                 if super::pointer_to_is_fat(*typ, tyctx, method) {
@@ -408,7 +488,7 @@ impl TyCache {
             TyKind::Adt(def, subst) => {
                 let name = crate::utilis::adt_name(*def, tyctx, subst);
                 if super::is_name_magic(name.as_ref()) {
-                    return super::magic_type(name.as_ref(), def, subst, tyctx);
+                    return super::magic_type(name.as_ref(), def, subst, tyctx, self, method);
                 }
                 self.adt(&name, *def, ty, subst, tyctx, method).into()
             }
@@ -506,6 +586,11 @@ impl TyCache {
                 }
                 DotnetTypeRef::array(&element, length).into()
             }
+            // `dyn*` has the same layout as a fat pointer to a `dyn`, so it is represented by the same type.
+            TyKind::Dynamic(preds, region, DynKind::DynStar) => {
+                let dyn_ty = Ty::new_dynamic(tyctx, preds, *region, DynKind::Dyn);
+                slice_ref_to(tyctx, self, Ty::new_slice(tyctx, dyn_ty), method)
+            }
             TyKind::Alias(_, _) => {
                 //self.cycle_prevention.push("ALIAS_PREV")
                 if let Some(method) = method {
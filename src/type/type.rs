@@ -1,9 +1,12 @@
 use crate::cil::CallSite;
 use crate::cil_tree::cil_node::CILNode;
 use crate::function_sig::FnSig;
+use crate::r#type::TyCache;
 use crate::{call, IString};
 use rustc_middle::middle::exported_symbols::ExportedSymbol;
-use rustc_middle::ty::{AdtDef, ConstKind, FloatTy, GenericArg, IntTy, Ty, TyCtxt, TyKind, UintTy};
+use rustc_middle::ty::{
+    AdtDef, ConstKind, FloatTy, GenericArg, Instance, IntTy, Ty, TyCtxt, TyKind, UintTy,
+};
 /// This struct represetnts either a primitive .NET type (F32,F64), or stores information on how to lookup a more complex type (struct,class,array)
 use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, PartialEq, Clone, Eq, Hash, Debug)]
@@ -16,6 +19,8 @@ pub enum Type {
     F16,
     F32,
     F64,
+    /// A 128 bit float, stored as its bits. .NET has no such type, so all operations on it are implemented in software.
+    F128,
     // Unsigned intiegers
     U8,
     U16,
@@ -81,6 +86,10 @@ impl DotnetTypeRef {
         Self::new(Some("System.Runtime"), "System.Single").with_valuetype(true)
     }
     #[must_use]
+    pub fn half() -> Self {
+        Self::new(Some("System.Runtime"), "System.Half").with_valuetype(true)
+    }
+    #[must_use]
     pub fn double() -> Self {
         Self::new(Some("System.Runtime"), "System.Double").with_valuetype(true)
     }
@@ -96,13 +105,56 @@ impl DotnetTypeRef {
     pub fn math() -> Self {
         Self::new(Some("System.Runtime"), "System.Math").with_valuetype(false)
     }
+    /// The .NET hardware-accelerated vector(`Vector64<T>`, `Vector128<T>` or `Vector256<T>`) of `size` bytes, with elements of type `element`.
+    #[must_use]
+    pub fn simd_vector(element: Type, size: u64) -> Self {
+        let mut vector = Self::new(
+            Some("System.Runtime.Intrinsics"),
+            &format!("System.Runtime.Intrinsics.Vector{bits}`1", bits = size * 8),
+        );
+        vector.set_generics([element]);
+        vector
+    }
+    /// The static class implementing operations on the vectors of `size` bytes.
+    #[must_use]
+    pub fn simd_helper(size: u64) -> Self {
+        Self::new(
+            Some("System.Runtime.Intrinsics"),
+            &format!("System.Runtime.Intrinsics.Vector{bits}", bits = size * 8),
+        )
+        .with_valuetype(false)
+    }
+    /// Checks if a Rust SIMD type of `size` bytes, with elements of type `element`, can be represented by a .NET vector.
+    #[must_use]
+    pub fn is_simd_vector(element: &Type, size: u64) -> bool {
+        matches!(size, 8 | 16 | 32)
+            && matches!(
+                element,
+                Type::U8
+                    | Type::I8
+                    | Type::U16
+                    | Type::I16
+                    | Type::U32
+                    | Type::I32
+                    | Type::U64
+                    | Type::I64
+                    | Type::USize
+                    | Type::ISize
+                    | Type::F32
+                    | Type::F64
+            )
+    }
     #[must_use]
     pub fn mathf() -> Self {
         Self::new(Some("System.Runtime"), "System.MathF").with_valuetype(false)
     }
     #[must_use]
     pub fn int_128() -> Self {
-        Self::new(Some("System.Runtime"), "System.Int128")
+        if *crate::config::EMULATE_INT128 {
+            Self::new(None, crate::builtin::int128::INT128)
+        } else {
+            Self::new(Some("System.Runtime"), "System.Int128")
+        }
     }
     #[must_use]
     pub fn binary_primitives() -> Self {
@@ -114,7 +166,11 @@ impl DotnetTypeRef {
     }
     #[must_use]
     pub fn uint_128() -> Self {
-        Self::new(Some("System.Runtime"), "System.UInt128")
+        if *crate::config::EMULATE_INT128 {
+            Self::new(None, crate::builtin::int128::UINT128)
+        } else {
+            Self::new(Some("System.Runtime"), "System.UInt128")
+        }
     }
     #[must_use]
     pub fn usize_type() -> Self {
@@ -205,6 +261,24 @@ impl DotnetTypeRef {
     pub(crate) fn interlocked() -> Self {
         Self::new(Some("System.Threading"), "System.Threading.Interlocked").with_valuetype(false)
     }
+    pub(crate) fn volatile() -> Self {
+        Self::new(Some("System.Threading"), "System.Threading.Volatile").with_valuetype(false)
+    }
+    /// The class owning the state of closures wrapped in delegates. Defined by the codegen in each assembly.
+    #[must_use]
+    pub fn closure_state() -> Self {
+        Self::new(None, "RustClosureState").with_valuetype(false)
+    }
+    /// The class freeing the per-thread buffers of `#[thread_local]` statics. Defined by the codegen in each assembly.
+    #[must_use]
+    pub fn thread_local_owner() -> Self {
+        Self::new(None, "RustThreadLocal").with_valuetype(false)
+    }
+    /// The exception type used to unwind Rust panics. Defined by the codegen in each assembly.
+    #[must_use]
+    pub fn rust_exception() -> Self {
+        Self::new(None, "RustException").with_valuetype(false)
+    }
     #[must_use]
     pub fn assembly() -> Self {
         Self::new(Some("System.Runtime"), "System.Reflection.Assembly").with_valuetype(false)
@@ -286,6 +360,15 @@ impl Type {
         }
     }
 
+    /// Checks if this type is a reference to a GC-managed object. Such references may only be stored in places the GC can see(locals, arguments, managed objects), and never in unmanaged memory.
+    #[must_use]
+    pub fn is_gcref(&self) -> bool {
+        match self {
+            Self::DotnetType(inner) => !inner.is_valuetype(),
+            Self::ManagedArray { .. } => true,
+            _ => false,
+        }
+    }
     #[must_use]
     pub fn as_delegate_ptr(&self) -> Option<&crate::function_sig::FnSig> {
         if let Self::DelegatePtr(v) = self {
@@ -361,7 +444,7 @@ impl From<&FloatTy> for Type {
             FloatTy::F16 => Self::F16,
             FloatTy::F32 => Self::F32,
             FloatTy::F64 => Self::F64,
-            FloatTy::F128 => todo!("Can't hanlde 128 bit floats yet!"),
+            FloatTy::F128 => Self::F128,
         }
     }
 }
@@ -381,6 +464,7 @@ impl From<DotnetTypeRef> for Type {
     }
 }
 const INTEROP_CLASS_TPE_NAME: &str = "RustcCLRInteropManagedClass";
+const INTEROP_GENERIC_CLASS_TPE_NAME: &str = "RustcCLRInteropManagedGenericClass";
 const INTEROP_STRUCT_TPE_NAME: &str = "RustcCLRInteropManagedStruct";
 const INTEROP_CHR_TPE_NAME: &str = "RustcCLRInteropManagedChar";
 const INTEROP_ARR_TPE_NAME: &str = "RustcCLRInteropManagedArray";
@@ -398,7 +482,8 @@ pub fn magic_type<'tyctx>(
     _adt: &AdtDef<'tyctx>,
     subst: &[GenericArg<'tyctx>],
     ctx: TyCtxt<'tyctx>,
-    //method: &Instance<'tyctx>,
+    tycache: &mut TyCache,
+    method: Option<Instance<'tyctx>>,
 ) -> Type {
     if name.contains(INTEROP_CLASS_TPE_NAME) {
         assert!(
@@ -415,6 +500,31 @@ pub fn magic_type<'tyctx>(
             is_valuetype: false,
         };
         Type::DotnetType(dotnet_tpe.into())
+    } else if name.contains(INTEROP_GENERIC_CLASS_TPE_NAME) {
+        assert!(
+            subst.len() == 3,
+            "Managed generic object reference must have exactly 3 generic arguments: assembly, class path and a tuple of generic arguments!"
+        );
+        let assembly: Box<str> = garg_to_string(subst[0], ctx).into();
+        let assembly = Some(assembly).filter(|assembly| !assembly.is_empty());
+        let name = garg_to_string(subst[1], ctx).into();
+        let generics = subst[2]
+            .as_type()
+            .expect("Generic arguments of a managed class must be specified as a tuple type!");
+        let TyKind::Tuple(generics) = generics.kind() else {
+            panic!("Generic arguments of a managed class must be specified as a tuple type, not {generics:?}!")
+        };
+        let generics = generics
+            .iter()
+            .map(|garg| tycache.type_from_cache(garg, ctx, method))
+            .collect();
+        let dotnet_tpe = DotnetTypeRef {
+            assembly,
+            name_path: name,
+            generics,
+            is_valuetype: false,
+        };
+        Type::DotnetType(dotnet_tpe.into())
     } else if name.contains(INTEROP_STRUCT_TPE_NAME) {
         assert!(
             subst.len() == 2,
@@ -432,19 +542,16 @@ pub fn magic_type<'tyctx>(
         Type::DotnetType(dotnet_tpe.into())
     } else if name.contains(INTEROP_ARR_TPE_NAME) {
         assert!(subst.len() == 2, "Managed array reference must have exactly 2 generic arguments: type and dimension count!");
-        let element = &subst[0].as_type().expect("Array type must be specified!");
+        let element = subst[0].as_type().expect("Array type must be specified!");
         let dimensions = garag_to_usize(subst[1], ctx);
-        let _ = (element, dimensions);
-        /*
-
-        Type::DotnetArray(
-            DotnetArray {
-                element,
-                dimensions,
-            }
-            .into(),
-        )*/
-        todo!()
+        let dims = u8::try_from(dimensions)
+            .ok()
+            .and_then(std::num::NonZeroU8::new)
+            .expect("Managed arrays must have between 1 and 255 dimensions!");
+        Type::ManagedArray {
+            element: Box::new(tycache.type_from_cache(element, ctx, method)),
+            dims,
+        }
     } else if name.contains(INTEROP_CHR_TPE_NAME) {
         Type::DotnetChar
     } else {
@@ -10,8 +10,9 @@ use crate::{
     utilis::adt::FieldOffsetIterator,
     IString,
 };
+use rustc_middle::ty::{CoroutineArgs, GenericArg, Instance, List, TyCtxt};
 use rustc_span::def_id::DefId;
-use rustc_target::abi::Layout;
+use rustc_target::abi::{Layout, VariantIdx};
 use serde::{Deserialize, Serialize};
 pub(crate) const CUSTOM_INTEROP_TYPE_DEF: &str = "RustcCLRInteropManagedCustomTypeDef";
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
@@ -86,9 +87,36 @@ impl TypeDef {
     pub fn explicit_offsets(&self) -> Option<&Vec<u32>> {
         self.explicit_offsets.as_ref()
     }
+    /// Returns the explicit offset of the field `name`, if this type has explicit offsets.
+    #[must_use]
+    pub fn field_offset(&self, name: &str) -> Option<u32> {
+        let idx = self.fields.iter().position(|(field, _)| field.as_ref() == name)?;
+        self.explicit_offsets.as_ref().map(|offsets| offsets[idx])
+    }
     pub fn add_method(&mut self, method: Method) {
         self.functions.push(method);
     }
+    /// Shuffles the declaration order of the fields of a type with explicit offsets. The layout of such a type does not depend on that order,
+    /// so this flushes out bugs in code assuming a certain field order. The order depends only on the name of the type, so all crates agree on it.
+    pub fn shuffle_fields(&mut self) {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        let Some(offsets) = self.explicit_offsets.as_mut() else {
+            return;
+        };
+        let mut hasher = DefaultHasher::new();
+        self.name.hash(&mut hasher);
+        // Xorshift needs a non-zero state.
+        let mut state = hasher.finish() | 1;
+        // Fisher-Yates shuffle, applied to both the fields and their offsets.
+        for idx in (1..self.fields.len()).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let other = usize::try_from(state % (idx as u64 + 1)).unwrap();
+            self.fields.swap(idx, other);
+            offsets.swap(idx, other);
+        }
+    }
     pub fn methods(&self) -> impl Iterator<Item = &Method> {
         self.functions.iter()
     }
@@ -155,22 +183,22 @@ impl TypeDef {
 }
 impl From<TypeDef> for Type {
     fn from(val: TypeDef) -> Type {
-        Type::DotnetType(DotnetTypeRef::new(None, val.name()).into())
+        Type::DotnetType(Box::new(val.into()))
     }
 }
 impl From<&TypeDef> for Type {
     fn from(val: &TypeDef) -> Type {
-        Type::DotnetType(DotnetTypeRef::new(None, val.name()).into())
+        Type::DotnetType(Box::new(val.into()))
     }
 }
 impl From<TypeDef> for DotnetTypeRef {
     fn from(val: TypeDef) -> DotnetTypeRef {
-        DotnetTypeRef::new(None, val.name())
+        DotnetTypeRef::new(None, val.name()).with_valuetype(val.extends().is_none())
     }
 }
 impl From<&TypeDef> for DotnetTypeRef {
     fn from(val: &TypeDef) -> DotnetTypeRef {
-        DotnetTypeRef::new(None, val.name())
+        DotnetTypeRef::new(None, val.name()).with_valuetype(val.extends().is_none())
     }
 }
 #[must_use]
@@ -228,7 +256,7 @@ pub fn closure_typedef(
         .collect();
     let offsets: Vec<_> = FieldOffsetIterator::fields((*layout.0).clone()).collect();
     assert_eq!(fields.len(), offsets.len());
-    TypeDef::new(
+    let mut def = TypeDef::new(
         AccessModifer::Public,
         name.into(),
         vec![],
@@ -238,7 +266,81 @@ pub fn closure_typedef(
         0,
         None,
         Some(layout.size().bytes()),
-    )
+    );
+    if *crate::config::RANDOMIZE_LAYOUT {
+        def.shuffle_fields();
+    }
+    def
+}
+/// Returns the name of the coroutine state machine type. Uses the mangled symbol of the coroutine body, so that each monomorphization gets its own type.
+pub fn coroutine_name<'tyctx>(
+    def_id: DefId,
+    args: &'tyctx List<GenericArg<'tyctx>>,
+    tyctx: TyCtxt<'tyctx>,
+) -> IString {
+    let instance = Instance::new(def_id, args);
+    let auto_mangled =
+        rustc_symbol_mangling::symbol_name_for_instance_in_crate(tyctx, instance, def_id.krate);
+    let demangled = format!("{}", rustc_demangle::demangle(&auto_mangled));
+    crate::utilis::escape_class_name(&format!("Coroutine.{}", demangled.replace("::", ".")))
+}
+/// Name of the field `field_idx` of the coroutine variant `variant`(eg. `Suspend0_1`).
+pub fn coroutine_field_name(variant: VariantIdx, field_idx: u32) -> IString {
+    format!("{}_{field_idx}", CoroutineArgs::variant_name(variant)).into()
+}
+/// Creates the typedef of a coroutine. The upvars are stored in fields `f_{idx}`, the state in `value__`, and the locals saved across suspension points
+/// are stored in per-variant fields, named like the fields of an enum.
+#[must_use]
+pub fn coroutine_typedef<'tyctx>(
+    name: IString,
+    upvars: &[Type],
+    variants: &[Vec<Type>],
+    layout: Layout<'tyctx>,
+    tyctx: TyCtxt<'tyctx>,
+) -> TypeDef {
+    let upvar_offsets = FieldOffsetIterator::fields((*layout.0).clone());
+    let mut fields: Vec<_> = upvars
+        .iter()
+        .enumerate()
+        .map(|(idx, ty)| (format!("f_{idx}").into(), ty.clone()))
+        .collect();
+    let mut offsets: Vec<_> = upvar_offsets.take(upvars.len()).collect();
+    assert_eq!(fields.len(), offsets.len());
+    let (tag_type, tag_offset) = crate::utilis::adt::enum_tag_info(layout, tyctx);
+    if tag_type != Type::Void {
+        fields.push(("value__".into(), tag_type));
+        offsets.push(tag_offset);
+    }
+    for (vidx, variant) in variants.iter().enumerate() {
+        let vidx = VariantIdx::from_usize(vidx);
+        let variant_offsets: Vec<_> = FieldOffsetIterator::fields(
+            crate::utilis::adt::get_variant_at_index(vidx, (*layout.0).clone()),
+        )
+        .collect();
+        assert_eq!(variant_offsets.len(), variant.len());
+        for (fidx, ty) in variant.iter().enumerate() {
+            fields.push((
+                coroutine_field_name(vidx, u32::try_from(fidx).unwrap()),
+                ty.clone(),
+            ));
+        }
+        offsets.extend(variant_offsets);
+    }
+    let mut def = TypeDef::new(
+        AccessModifer::Public,
+        name,
+        vec![],
+        fields,
+        vec![],
+        Some(offsets),
+        0,
+        None,
+        Some(layout.size().bytes()),
+    );
+    if *crate::config::RANDOMIZE_LAYOUT {
+        def.shuffle_fields();
+    }
+    def
 }
 #[must_use]
 pub fn arr_name(element_count: usize, element: &Type) -> IString {
@@ -263,7 +365,7 @@ pub fn tuple_typedef(elements: &[Type], layout: Layout) -> TypeDef {
         .map(|(idx, ele)| (format!("Item{}", idx + 1).into(), ele.clone()))
         .collect();
     let explicit_offsets = FieldOffsetIterator::fields((*layout.0).clone()).collect();
-    TypeDef::new(
+    let mut def = TypeDef::new(
         AccessModifer::Public,
         name,
         vec![],
@@ -272,9 +374,14 @@ pub fn tuple_typedef(elements: &[Type], layout: Layout) -> TypeDef {
         Some(explicit_offsets),
         0,
         None,
-        None,
-        
-    )
+        // With explicit offsets, .NET sizes a type to the end of its last field. Tuples may have trailing padding(eg. `(u64, u8)` is 16 bytes, not 9),
+        // so without an explicit size, arrays of tuples(and pointer arithmetic on them) would use the wrong stride.
+        Some(layout.size().bytes()),
+    );
+    if *crate::config::RANDOMIZE_LAYOUT {
+        def.shuffle_fields();
+    }
+    def
 }
 #[must_use]
 pub fn get_array_type(element_count: usize, element: Type, explict_size: u64) -> TypeDef {
@@ -412,5 +519,8 @@ pub fn get_array_type(element_count: usize, element: Type, explict_size: u64) ->
         //to_string.set_ops(ops);
         //def.add_method(to_string);
     }
+    if *crate::config::RANDOMIZE_LAYOUT {
+        def.shuffle_fields();
+    }
     def
 }
@@ -40,6 +40,9 @@ pub fn unop<'ctx>(
                 ),
                 args: [parrent_node].into(),
             },
+            TyKind::Float(float) if crate::binop::float::is_non_native(*float) => {
+                crate::binop::float::neg(*float, parrent_node)
+            }
             _ => CILNode::Neg(parrent_node.into()),
         },
         UnOp::Not => match ty.kind() {
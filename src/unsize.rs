@@ -4,12 +4,12 @@ use crate::cil_tree::cil_root::CILRoot;
 
 use crate::operand::handle_operand;
 
-use crate::{conv_usize, ld_field, ld_field_address, ldc_u64};
+use crate::{conv_usize, ld_field, ld_field_address, ldc_u32, ldc_u64, size_of};
 
 use crate::r#type::{DotnetTypeRef, TyCache, Type};
 use rustc_middle::{
     mir::Operand,
-    ty::{Instance, Ty, TyCtxt, TyKind},
+    ty::{Instance, Mutability, Ty, TyCtxt, TyKind},
 };
 struct UnsizeInfo<'tyctx> {
     /// Type the source pointer points to
@@ -182,10 +182,79 @@ pub fn unsize<'tyctx>(
             )))
         }
         (
-            TyKind::Dynamic(_data_a, _, _src_dyn_kind),
-            TyKind::Dynamic(_data_b, _, _target_dyn_kind),
+            TyKind::Dynamic(data_a, _, src_dyn_kind),
+            TyKind::Dynamic(data_b, _, target_dyn_kind),
         ) => {
-            todo!("dyn to dyn cats not yet supported!")
+            assert_eq!(
+                src_dyn_kind, target_dyn_kind,
+                "Casts between `dyn` and `dyn*` are not supported!"
+            );
+            let target_points_to = target.builtin_deref(true).unwrap();
+            // The source is a fat pointer too, with the same layout as a raw pointer to its pointee.
+            let source_dotnet = tycache
+                .type_from_cache(
+                    Ty::new_ptr(tyctx, info.source_points_to, Mutability::Mut),
+                    tyctx,
+                    Some(method_instance),
+                )
+                .as_dotnet()
+                .unwrap();
+            let old_vtable = ld_field!(
+                info.source_ptr.clone(),
+                FieldDescriptor::new(source_dotnet.clone(), Type::USize, "metadata".into())
+            );
+            let obj_ptr = ld_field!(
+                info.source_ptr,
+                FieldDescriptor::new(
+                    source_dotnet,
+                    Type::Ptr(Type::Void.into()),
+                    "data_pointer".into()
+                )
+            );
+            // Trait upcasting: the vtable of the subtrait contains a pointer to the vtable of the supertrait, unless the supertrait vtable is a prefix of it.
+            // A target without a principal trait(e.g. `dyn Send`) only uses the common vtable header(drop, size, align), so the source vtable can be reused as-is.
+            let vtable_slot = match (data_a.principal_def_id(), data_b.principal_def_id()) {
+                (src, target) if src == target => None,
+                (_, None) => None,
+                _ => tyctx.supertrait_vtable_slot((info.source_points_to, target_points_to)),
+            };
+            let vtable = match vtable_slot {
+                Some(slot) => {
+                    let vtable_index = ldc_u32!(
+                        u32::try_from(slot).expect("More tahn 2^32 entries in a vtable!")
+                    );
+                    let vtable_offset = conv_usize!(vtable_index * size_of!(Type::USize));
+                    CILNode::LDIndUSize {
+                        ptr: Box::new(CILNode::TransmutePtr {
+                            val: Box::new(old_vtable + vtable_offset),
+                            new_ptr: Box::new(Type::Ptr(Box::new(Type::USize))),
+                        }),
+                    }
+                }
+                None => old_vtable,
+            };
+            let metadata_field =
+                FieldDescriptor::new(info.target_dotnet.clone(), Type::USize, "metadata".into());
+            let ptr_field = FieldDescriptor::new(
+                info.target_dotnet,
+                Type::Ptr(Type::Void.into()),
+                "data_pointer".into(),
+            );
+            let init_vtable_ptr = CILRoot::SetField {
+                addr: info.target_ptr.clone(),
+                value: vtable,
+                desc: metadata_field,
+            };
+            let init_obj_ptr = CILRoot::SetField {
+                addr: info.target_ptr,
+                value: obj_ptr,
+                desc: ptr_field,
+            };
+            CILNode::TemporaryLocal(Box::new((
+                info.target_type,
+                [init_vtable_ptr, init_obj_ptr].into(),
+                CILNode::LoadTMPLocal,
+            )))
         }
         (_, TyKind::Dynamic(data, _, _dyn_kind)) => {
             let alloc_id = tyctx.vtable_allocation((info.source_points_to, data.principal()));
@@ -226,3 +295,69 @@ pub fn unsize<'tyctx>(
         ),
     }
 }
+/// Preforms a `dyn*` coercion on operand `operand`, converting it to the `target` type.
+/// A `dyn*` is laid out like a fat pointer to a `dyn`: the pointer-sized value itself, followed by a pointer to its vtable.
+pub fn dyn_star<'tyctx>(
+    tyctx: TyCtxt<'tyctx>,
+    method: &rustc_middle::mir::Body<'tyctx>,
+    method_instance: Instance<'tyctx>,
+    tycache: &mut TyCache,
+    operand: &Operand<'tyctx>,
+    target: Ty<'tyctx>,
+) -> CILNode {
+    let target = crate::utilis::monomorphize(&method_instance, target, tyctx);
+    let source = crate::utilis::monomorphize(&method_instance, operand.ty(method, tyctx), tyctx);
+    let TyKind::Dynamic(data, _, _) = target.kind() else {
+        panic!("Non dyn* type:{target:?}")
+    };
+    let source_type = tycache.type_from_cache(source, tyctx, Some(method_instance));
+    let target_type = tycache.type_from_cache(target, tyctx, Some(method_instance));
+    let target_dotnet = target_type.as_dotnet().unwrap();
+    let value = handle_operand(operand, tyctx, method, method_instance, tycache);
+    let value = match source_type {
+        Type::ISize | Type::USize | Type::Ptr(_) => value,
+        // The value may be wrapped in an ADT(e.g. `Box` or `NonNull`), so it has to be read trough memory.
+        _ => CILNode::TemporaryLocal(Box::new((
+            source_type,
+            [CILRoot::SetTMPLocal { value }].into(),
+            CILNode::LDIndPtr {
+                ptr: Box::new(CILNode::TransmutePtr {
+                    val: Box::new(CILNode::LoadAddresOfTMPLocal),
+                    new_ptr: Box::new(Type::Ptr(Box::new(Type::Ptr(Box::new(Type::Void))))),
+                }),
+                loaded_ptr: Box::new(Type::Ptr(Box::new(Type::Void))),
+            },
+        ))),
+    };
+    let alloc_id = tyctx.vtable_allocation((source, data.principal()));
+    let metadata_field =
+        FieldDescriptor::new(target_dotnet.clone(), Type::USize, "metadata".into());
+    let ptr_field = FieldDescriptor::new(
+        target_dotnet,
+        Type::Ptr(Type::Void.into()),
+        "data_pointer".into(),
+    );
+    let init_vtable_ptr = CILRoot::SetField {
+        addr: CILNode::LoadAddresOfTMPLocal,
+        value: CILNode::TransmutePtr {
+            val: Box::new(CILNode::LoadGlobalAllocPtr {
+                alloc_id: alloc_id.0.into(),
+            }),
+            new_ptr: Box::new(Type::USize),
+        },
+        desc: metadata_field,
+    };
+    let init_obj_ptr = CILRoot::SetField {
+        addr: CILNode::LoadAddresOfTMPLocal,
+        value: CILNode::TransmutePtr {
+            val: Box::new(value),
+            new_ptr: Box::new(Type::Ptr(Box::new(Type::Void))),
+        },
+        desc: ptr_field,
+    };
+    CILNode::TemporaryLocal(Box::new((
+        target_type,
+        [init_vtable_ptr, init_obj_ptr].into(),
+        CILNode::LoadTMPLocal,
+    )))
+}
@@ -126,7 +126,7 @@ fn primitive_to_type(primitive: rustc_target::abi::Primitive) -> Type {
         Primitive::Float(rustc_abi::Float::F16) => Type::F16,
         Primitive::Float(rustc_abi::Float::F32) => Type::F32,
         Primitive::Float(rustc_abi::Float::F64) => Type::F64,
-        Primitive::Float(rustc_abi::Float::F128) => todo!("No support for 128 bit floats yet!"),
+        Primitive::Float(rustc_abi::Float::F128) => Type::F128,
         Primitive::Pointer(_) => Type::Ptr(Type::Void.into()),
     }
 }
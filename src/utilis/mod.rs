@@ -1,16 +1,41 @@
 use rustc_middle::mir::interpret::AllocId;
 use rustc_middle::ty::{
-    AdtDef, Const, ConstKind, EarlyBinder, GenericArg, Instance, List, ParamEnv, SymbolName, Ty,
-    TyCtxt, TyKind, TypeFoldable,
+    AdtDef, Const, ConstKind, EarlyBinder, GenericArg, Instance, IntTy, List, ParamEnv, SymbolName,
+    Ty, TyCtxt, TyKind, TypeFoldable, UintTy,
 };
+use rustc_target::abi::VariantIdx;
 pub const CTOR_FN_NAME: &str = "rustc_clr_interop_managed_ctor";
 pub const MANAGED_CALL_FN_NAME: &str = "rustc_clr_interop_managed_call";
 pub const MANAGED_CALL_VIRT_FN_NAME: &str = "rustc_clr_interop_managed_call_virt";
+pub const DELEGATE_CTOR_FN_NAME: &str = "rustc_clr_interop_managed_delegate_ctor";
+pub const MANAGED_ARRAY_FN_NAME: &str = "rustc_clr_interop_managed_array_";
+/// Prefix of the `export_name` of functions exported using `mycorrhiza::export!`.
+pub const EXPORT_PREFIX: &str = "rustc_clr_interop_export:";
+/// If `instance` was exported using `mycorrhiza::export!`, returns the path of the class and the name of the method it should be exported as.
+pub fn exported_as(instance: Instance, tyctx: TyCtxt) -> Option<(IString, IString)> {
+    if !matches!(instance.def, rustc_middle::ty::InstanceDef::Item(_)) {
+        return None;
+    }
+    let export_name = tyctx.codegen_fn_attrs(instance.def_id()).export_name?;
+    let (class_path, method_name) = export_name
+        .as_str()
+        .strip_prefix(EXPORT_PREFIX)?
+        .rsplit_once("::")
+        .unwrap_or_else(|| panic!("Invalid export name {export_name:?}. Exported functions must specify both a class and a method name."));
+    Some((class_path.into(), method_name.into()))
+}
 pub fn is_function_magic(name: &str) -> bool {
-    name.contains(CTOR_FN_NAME) || name.contains(MANAGED_CALL_FN_NAME)
+    name.contains(CTOR_FN_NAME)
+        || name.contains(MANAGED_CALL_FN_NAME)
+        || name.contains(DELEGATE_CTOR_FN_NAME)
+        || name.contains(MANAGED_ARRAY_FN_NAME)
 }
 
-use crate::{cil::FieldDescriptor, r#type::TyCache, IString};
+use crate::{
+    cil::FieldDescriptor,
+    r#type::{DotnetTypeRef, TyCache},
+    IString,
+};
 pub mod adt;
 pub fn as_adt(ty: Ty) -> Option<(AdtDef, &List<GenericArg>)> {
     match ty.kind() {
@@ -115,6 +140,34 @@ pub fn enum_field_descriptor<'ctx>(
     method_instance: Instance<'ctx>,
     type_cache: &mut TyCache,
 ) -> FieldDescriptor {
+    if let TyKind::Coroutine(def_id, args) = owner_ty.kind() {
+        let variant_idx = VariantIdx::from_u32(variant_idx);
+        let field_ty = args
+            .as_coroutine()
+            .state_tys(*def_id, ctx)
+            .nth(variant_idx.as_usize())
+            .expect("No coroutine variant with such index!")
+            .nth(field_idx as usize)
+            .expect("No coroutine field with provided index!");
+        let field_ty = crate::utilis::monomorphize(&method_instance, field_ty, ctx);
+        let field_ty = type_cache.type_from_cache(field_ty, ctx, Some(method_instance));
+        let owner_type = type_cache
+            .type_from_cache(owner_ty, ctx, Some(method_instance))
+            .as_dotnet()
+            .expect("Coroutine type invalid!");
+        let field_name = crate::r#type::coroutine_field_name(variant_idx, field_idx);
+        check_field_offset(
+            owner_ty,
+            Some(variant_idx),
+            field_idx,
+            &owner_type,
+            &field_name,
+            ctx,
+            method_instance,
+            type_cache,
+        );
+        return FieldDescriptor::new(owner_type, field_ty, field_name);
+    }
     let (adt, subst) = as_adt(owner_ty).expect("Tried to get a field of a non ADT or tuple type!");
     let variant = adt
         .variants()
@@ -127,7 +180,7 @@ pub fn enum_field_descriptor<'ctx>(
         .nth(field_idx as usize)
         .expect("No enum field with provided index!");
     let variant_name: IString = variant.name.to_string().into();
-    let field_name = format!(
+    let field_name: IString = format!(
         "{variant_name}_{fname}",
         fname = crate::r#type::escape_field_name(&field.name.to_string())
     )
@@ -135,12 +188,64 @@ pub fn enum_field_descriptor<'ctx>(
     let field_ty = field.ty(ctx, subst);
     let field_ty = crate::utilis::monomorphize(&method_instance, field_ty, ctx);
     let field_ty = type_cache.type_from_cache(field_ty, ctx, Some(method_instance));
-    let owner_ty = type_cache
+    let owner_type = type_cache
         .type_from_cache(owner_ty, ctx, Some(method_instance))
         .as_dotnet()
         .expect("Error: tried to set a field of a non-object type!");
-
-    FieldDescriptor::new(owner_ty, field_ty, field_name)
+    check_field_offset(
+        owner_ty,
+        Some(VariantIdx::from_u32(variant_idx)),
+        field_idx,
+        &owner_type,
+        &field_name,
+        ctx,
+        method_instance,
+        type_cache,
+    );
+    FieldDescriptor::new(owner_type, field_ty, field_name)
+}
+/// Checks that `field_name` of `owner` resolves to the field `field_idx`(of the variant `variant`, if set) of `owner_ty`, at the offset computed by rustc.
+/// With `RANDOMIZE_LAYOUT`, fields are declared in a shuffled order, so this catches field accesses relying on the declaration order.
+pub(crate) fn check_field_offset<'tyctx>(
+    owner_ty: Ty<'tyctx>,
+    variant: Option<VariantIdx>,
+    field_idx: u32,
+    owner: &DotnetTypeRef,
+    field_name: &str,
+    tyctx: TyCtxt<'tyctx>,
+    method_instance: Instance<'tyctx>,
+    type_cache: &TyCache,
+) {
+    if !*crate::config::RANDOMIZE_LAYOUT {
+        return;
+    }
+    let Some(def) = type_cache.type_def(owner) else {
+        return;
+    };
+    let layout = tyctx
+        .layout_of(rustc_middle::ty::ParamEnvAnd {
+            param_env: ParamEnv::reveal_all(),
+            value: monomorphize(&method_instance, owner_ty, tyctx),
+        })
+        .expect("Could not get type layout!");
+    let expected = match variant {
+        Some(variant) => {
+            // Fields of uninhabited types may have no offset, and are never accessed.
+            let Some(offset) = crate::utilis::adt::FieldOffsetIterator::fields(
+                crate::utilis::adt::get_variant_at_index(variant, (*layout.layout.0).clone()),
+            )
+            .nth(field_idx as usize) else {
+                return;
+            };
+            u64::from(offset)
+        }
+        None => layout.fields.offset(field_idx as usize).bytes(),
+    };
+    assert_eq!(
+        def.field_offset(field_name).map(u64::from),
+        Some(expected),
+        "Field {field_name} of {owner_ty:?} does not resolve to its offset."
+    );
 }
 pub fn field_descrptor<'tyctx>(
     owner_ty: Ty<'tyctx>,
@@ -153,19 +258,27 @@ pub fn field_descrptor<'tyctx>(
         let element = elements[field_idx as usize];
         let element = monomorphize(&method_instance, element, tyctx);
         let element = type_cache.type_from_cache(element, tyctx, Some(method_instance));
-        return FieldDescriptor::new(
-            crate::r#type::simple_tuple(
-                &elements
-                    .iter()
-                    .map(|tpe| {
-                        let tpe = crate::utilis::monomorphize(&method_instance, tpe, tyctx);
-                        type_cache.type_from_cache(tpe, tyctx, Some(method_instance))
-                    })
-                    .collect::<Vec<_>>(),
-            ),
-            element,
-            format!("Item{}", field_idx + 1).into(),
+        let owner_type = crate::r#type::simple_tuple(
+            &elements
+                .iter()
+                .map(|tpe| {
+                    let tpe = crate::utilis::monomorphize(&method_instance, tpe, tyctx);
+                    type_cache.type_from_cache(tpe, tyctx, Some(method_instance))
+                })
+                .collect::<Vec<_>>(),
+        );
+        let field_name: IString = format!("Item{}", field_idx + 1).into();
+        check_field_offset(
+            owner_ty,
+            None,
+            field_idx,
+            &owner_type,
+            &field_name,
+            tyctx,
+            method_instance,
+            type_cache,
         );
+        return FieldDescriptor::new(owner_type, element, field_name);
     } else if let TyKind::Closure(_, args) = owner_ty.kind() {
         let closure = args.as_closure();
         let field_type = closure
@@ -177,12 +290,43 @@ pub fn field_descrptor<'tyctx>(
         let field_type = type_cache.type_from_cache(field_type, tyctx, Some(method_instance));
         let owner_ty = crate::utilis::monomorphize(&method_instance, owner_ty, tyctx);
         let owner_type = type_cache.type_from_cache(owner_ty, tyctx, Some(method_instance));
-        let field_name = format!("f_{field_idx}").into();
-        return FieldDescriptor::new(
-            owner_type.as_dotnet().expect("Closure type invalid!"),
-            field_type,
-            field_name,
+        let owner_type = owner_type.as_dotnet().expect("Closure type invalid!");
+        let field_name: IString = format!("f_{field_idx}").into();
+        check_field_offset(
+            owner_ty,
+            None,
+            field_idx,
+            &owner_type,
+            &field_name,
+            tyctx,
+            method_instance,
+            type_cache,
+        );
+        return FieldDescriptor::new(owner_type, field_type, field_name);
+    } else if let TyKind::Coroutine(_, args) = owner_ty.kind() {
+        let field_type = args
+            .as_coroutine()
+            .upvar_tys()
+            .iter()
+            .nth(field_idx as usize)
+            .expect("Could not find coroutine fields!");
+        let field_type = crate::utilis::monomorphize(&method_instance, field_type, tyctx);
+        let field_type = type_cache.type_from_cache(field_type, tyctx, Some(method_instance));
+        let owner_ty = crate::utilis::monomorphize(&method_instance, owner_ty, tyctx);
+        let owner_type = type_cache.type_from_cache(owner_ty, tyctx, Some(method_instance));
+        let owner_type = owner_type.as_dotnet().expect("Coroutine type invalid!");
+        let field_name: IString = format!("f_{field_idx}").into();
+        check_field_offset(
+            owner_ty,
+            None,
+            field_idx,
+            &owner_type,
+            &field_name,
+            tyctx,
+            method_instance,
+            type_cache,
         );
+        return FieldDescriptor::new(owner_type, field_type, field_name);
     }
     let (adt, subst) = as_adt(owner_ty).expect("Tried to get a field of a non ADT or tuple type!");
     let field = adt
@@ -193,11 +337,23 @@ pub fn field_descrptor<'tyctx>(
     let field_ty = field.ty(tyctx, subst);
     let field_ty = crate::utilis::monomorphize(&method_instance, field_ty, tyctx);
     let field_ty = type_cache.type_from_cache(field_ty, tyctx, Some(method_instance));
-    let owner_ty = type_cache
+    let owner_type = type_cache
         .type_from_cache(owner_ty, tyctx, Some(method_instance))
         .as_dotnet()
         .expect("Error: tried to set a field of a non-object type!");
-    FieldDescriptor::new(owner_ty, field_ty, field_name)
+    if !adt.is_enum() {
+        check_field_offset(
+            owner_ty,
+            None,
+            field_idx,
+            &owner_type,
+            &field_name,
+            tyctx,
+            method_instance,
+            type_cache,
+        );
+    }
+    FieldDescriptor::new(owner_type, field_ty, field_name)
     /*
     let def = type_cache.type_def_from_cache(owner_ty, ctx, Some(method_instance)); //TypeDef::from_ty(owner_ty, ctx, &method_instance);
     let def = def.clone();
@@ -350,7 +506,7 @@ pub(crate) fn alloc_id_to_u64(alloc_id: AllocId) -> u64 {
 pub fn is_fn_intrinsic<'tyctx>(fn_ty: Ty<'tyctx>, tyctx: TyCtxt<'tyctx>) -> bool {
     match fn_ty.kind() {
         TyKind::FnDef(did, _) => tyctx.is_intrinsic(*did, tyctx.item_name(*did)),
-        TyKind::Closure(_, _) => false,
+        TyKind::Closure(_, _) | TyKind::Coroutine(_, _) => false,
         _ => todo!("Can't get signature of {fn_ty}"),
     }
 }
@@ -377,14 +533,20 @@ pub fn is_zst<'tyctx>(ty: rustc_middle::ty::Ty<'tyctx>, tyctx: TyCtxt<'tyctx>) -
         .layout
         .is_zst()
 }
+/// Returns the aligement of `ty`, if it is greater than the one .NET guarantees for locals and arguments of that type.
 pub fn requries_align_adjustement<'tyctx>(ty: rustc_middle::ty::Ty<'tyctx>, tyctx: TyCtxt<'tyctx>)->Option<u64>{
     let layout =  tyctx
     .layout_of(rustc_middle::ty::ParamEnvAnd {
         param_env: ParamEnv::reveal_all(),
         value: ty,
     }).unwrap();
-    //TODO: some types requre aligement smaller than 16 bytes but larger than their size. Handle that. Requires reimplemting .NETs algiement clacualtions.
-    if layout.align.abi.bytes() > 16{
+    // .NET aligns locals to at most the pointer size. `System.Int128` and `System.UInt128` are the only exception, since they are always 16 byte aligned.
+    // This means every struct containing a 128 bit integer or float gets stored in a localloc buffer. That is slower, but intended: Rust code may rely on that aligement.
+    let guaranteed = match ty.kind() {
+        TyKind::Int(IntTy::I128) | TyKind::Uint(UintTy::U128) if !*crate::config::EMULATE_INT128 => 16,
+        _ => tyctx.data_layout.pointer_align.abi.bytes(),
+    };
+    if layout.align.abi.bytes() > guaranteed{
         Some(layout.align.abi.bytes())
     }
     else{
@@ -1 +1,221 @@
-
+use crate::{
+    access_modifier::AccessModifer,
+    assembly::Assembly,
+    basic_block::BasicBlock,
+    call,
+    cil::{CallSite, FieldDescriptor},
+    cil_tree::{cil_node::CILNode, cil_root::CILRoot},
+    conv_u64, conv_usize, eq,
+    function_sig::FnSig,
+    ldc_i32, ldc_u64,
+    method::{Method, MethodType},
+    or,
+    r#type::{arr_name, DotnetTypeRef, Type, TypeDef},
+    size_of,
+};
+/// Name of the method comparing a single size or offset with the value computed by rustc. Prefixed, so it can't collide with `#[no_mangle]` functions.
+const CHECK_LAYOUT: &str = "rustc_clr_check_layout";
+/// Name of the method checking the layout of all types.
+const VERIFY_LAYOUTS: &str = "rustc_clr_verify_layouts";
+fn console_write(arg: CILNode, tpe: Type, new_line: bool) -> CILRoot {
+    CILRoot::Call {
+        site: CallSite::new_extern(
+            DotnetTypeRef::console(),
+            if new_line { "WriteLine" } else { "Write" }.into(),
+            FnSig::new(&[tpe], &Type::Void),
+            true,
+        ),
+        args: [arg].into(),
+    }
+}
+/// Creates the method `rustc_clr_check_layout(string type_name, string what, nuint actual, nuint expected)`, which prints a message and returns 1 if `actual != expected`.
+fn check_layout() -> Method {
+    let string: Type = DotnetTypeRef::string_type().into();
+    Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(
+            &[string.clone(), string.clone(), Type::USize, Type::USize],
+            &Type::I32,
+        ),
+        CHECK_LAYOUT,
+        vec![],
+        vec![
+            BasicBlock::new(
+                vec![
+                    CILRoot::BTrue {
+                        target: 1,
+                        sub_target: 0,
+                        cond: eq!(CILNode::LDArg(2), CILNode::LDArg(3)),
+                    }
+                    .into(),
+                    console_write(
+                        CILNode::LdStr("Layout mismatch! Type:".into()),
+                        string.clone(),
+                        false,
+                    )
+                    .into(),
+                    console_write(CILNode::LDArg(0), string.clone(), false).into(),
+                    console_write(CILNode::LdStr(" ".into()), string.clone(), false).into(),
+                    console_write(CILNode::LDArg(1), string.clone(), false).into(),
+                    console_write(CILNode::LdStr(" is ".into()), string.clone(), false).into(),
+                    console_write(conv_u64!(CILNode::LDArg(2)), Type::U64, false).into(),
+                    console_write(CILNode::LdStr(" instead of ".into()), string, false).into(),
+                    console_write(conv_u64!(CILNode::LDArg(3)), Type::U64, true).into(),
+                    CILRoot::Ret { tree: ldc_i32!(1) }.into(),
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(vec![CILRoot::Ret { tree: ldc_i32!(0) }.into()], 1, None),
+        ],
+    )
+}
+/// Checks the size and field offsets of `tpe`, using the scratch buffer stored in local 0. Sets local 1 to 1 if any of them does not match.
+fn check_type(tpe: &TypeDef, trees: &mut Vec<crate::cil_tree::CILTree>) {
+    let string: Type = DotnetTypeRef::string_type().into();
+    let check_site = CallSite::new(
+        None,
+        CHECK_LAYOUT.into(),
+        FnSig::new(
+            &[string.clone(), string, Type::USize, Type::USize],
+            &Type::I32,
+        ),
+        true,
+    );
+    let mut check = |args: [CILNode; 4]| {
+        trees.push(
+            CILRoot::STLoc {
+                local: 1,
+                tree: or!(CILNode::LDLoc(1), call!(check_site.clone(), args)),
+            }
+            .into(),
+        );
+    };
+    let type_ref: DotnetTypeRef = tpe.into();
+    // .NET types can't be zero-sized, so the size of ZSTs is not checked.
+    if let Some(size) = tpe.explict_size().filter(|size| *size != 0) {
+        check([
+            CILNode::LdStr(tpe.name().into()),
+            CILNode::LdStr("size".into()),
+            conv_usize!(size_of!(Type::DotnetType(type_ref.clone().into()))),
+            conv_usize!(ldc_u64!(size)),
+        ]);
+    }
+    let Some(offsets) = tpe.explicit_offsets() else {
+        return;
+    };
+    // Arrays(from `get_array_type`) may have thousands of elements, laid out the same way.
+    // Checking the offset of the first one, and of the second one(the stride), is enough.
+    let is_array = tpe
+        .fields()
+        .first()
+        .is_some_and(|(_, element)| *arr_name(tpe.fields().len(), element) == *tpe.name());
+    let checked = if is_array { 2 } else { tpe.fields().len() };
+    for ((name, field_tpe), offset) in tpe.fields().iter().zip(offsets.iter()).take(checked) {
+        if *field_tpe == Type::Void {
+            continue;
+        }
+        let field_addr = CILNode::LDFieldAdress {
+            addr: Box::new(CILNode::TransmutePtr {
+                val: Box::new(CILNode::LDLoc(0)),
+                new_ptr: Box::new(Type::Ptr(Box::new(Type::DotnetType(
+                    type_ref.clone().into(),
+                )))),
+            }),
+            field: FieldDescriptor::new(type_ref.clone(), field_tpe.clone(), name.clone()).into(),
+        };
+        check([
+            CILNode::LdStr(tpe.name().into()),
+            CILNode::LdStr(format!("offset of {name}").into()),
+            conv_usize!(field_addr) - conv_usize!(CILNode::LDLoc(0)),
+            conv_usize!(ldc_u64!(u64::from(*offset))),
+        ]);
+    }
+}
+/// Adds a method checking that the runtime laid out all types with explicit offsets the same way rustc did, printing all mismatches, and then throwing if there were any.
+/// The method is called from the static constructor, so the check runs before any Rust code.
+pub fn add_layout_verification(asm: &mut Assembly) {
+    // Generic types can't be instantiated without their generic arguments.
+    let mut types: Vec<TypeDef> = asm
+        .types()
+        .map(|(_, tpe)| tpe)
+        .filter(|tpe| tpe.explicit_offsets().is_some() && tpe.gargc() == 0)
+        .cloned()
+        .collect();
+    // Makes the order of the messages deterministic.
+    types.sort_by(|a, b| a.name().cmp(b.name()));
+    // All field addresses are calculated from a buffer big enough to hold any of the checked types.
+    let max_size = types
+        .iter()
+        .filter_map(TypeDef::explict_size)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let mut trees = vec![
+        CILRoot::STLoc {
+            local: 0,
+            tree: call!(
+                CallSite::alloc(),
+                [conv_usize!(ldc_u64!(max_size)), conv_usize!(ldc_u64!(16))]
+            ),
+        }
+        .into(),
+        CILRoot::STLoc {
+            local: 1,
+            tree: ldc_i32!(0),
+        }
+        .into(),
+    ];
+    for tpe in &types {
+        check_type(tpe, &mut trees);
+    }
+    trees.push(
+        CILRoot::Call {
+            site: CallSite::new_extern(
+                DotnetTypeRef::native_mem(),
+                "AlignedFree".into(),
+                FnSig::new(&[Type::Ptr(Type::Void.into())], &Type::Void),
+                true,
+            ),
+            args: [CILNode::LDLoc(0)].into(),
+        }
+        .into(),
+    );
+    trees.push(
+        CILRoot::BTrue {
+            target: 1,
+            sub_target: 0,
+            cond: CILNode::LDLoc(1),
+        }
+        .into(),
+    );
+    trees.push(CILRoot::VoidRet.into());
+    let verify = Method::new(
+        AccessModifer::Private,
+        MethodType::Static,
+        FnSig::new(&[], &Type::Void),
+        VERIFY_LAYOUTS,
+        vec![
+            (Some("buffer".into()), Type::Ptr(Box::new(Type::Void))),
+            (Some("mismatch".into()), Type::I32),
+        ],
+        vec![
+            BasicBlock::new(trees, 0, None),
+            BasicBlock::new(
+                vec![CILRoot::throw(
+                    "The layout of some types does not match the one computed by rustc.",
+                )
+                .into()],
+                1,
+                None,
+            ),
+        ],
+    );
+    asm.add_static_initializer(CILRoot::Call {
+        site: verify.call_site(),
+        args: [].into(),
+    });
+    asm.add_method(check_layout());
+    asm.add_method(verify);
+}
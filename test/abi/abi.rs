@@ -0,0 +1,30 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code)]
+#![no_std]
+include!("../common.rs");
+extern "system" fn system_add(a:u32,b:u32)->u32{
+    a + b
+}
+extern "sysv64" fn sysv64_mul(a:u64,b:u64)->u64{
+    a * b
+}
+extern "efiapi" fn efiapi_sub(a:i32,b:i32)->i32{
+    a - b
+}
+extern "C-unwind" fn c_unwind_neg(a:i64)->i64{
+    -a
+}
+fn main(){
+    test_eq!(system_add(black_box(2),black_box(3)),5);
+    test_eq!(sysv64_mul(black_box(6),black_box(7)),42);
+    test_eq!(efiapi_sub(black_box(9),black_box(4)),5);
+    test_eq!(c_unwind_neg(black_box(8)),-8);
+    let fn_ptr:extern "system" fn(u32,u32)->u32 = black_box(system_add);
+    test_eq!(fn_ptr(black_box(1),black_box(1)),2);
+    // Calls to a variadic extern function, with differing argument types. Where the runtime supports it(`VARARG_CALLS`), those are `vararg` call sites.
+    // Elsewhere, each of them gets a non-variadic signature matching its arguments.
+    unsafe{
+        printf("%d %d\n\0".as_ptr() as *const i8,black_box(1_i32),black_box(2_i32));
+        printf("%s %lu\n\0".as_ptr() as *const i8,"abi\0".as_ptr(),black_box(3_u64));
+    }
+}
@@ -0,0 +1,45 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code)]
+#![no_std]
+include!("../common.rs");
+extern crate core;
+
+use core::intrinsics::*;
+
+fn main(){
+    let a:f32 = black_box(2.5);
+    test_eq!(unsafe{floorf32(a)},2.0);
+    test_eq!(unsafe{ceilf32(a)},3.0);
+    test_eq!(unsafe{truncf32(-a)},-2.0);
+    test_eq!(unsafe{roundf32(a)},3.0);
+    test_eq!(unsafe{rintf32(a)},2.0);
+    test_eq!(unsafe{fabsf32(-a)},2.5);
+    test_eq!(unsafe{copysignf32(a,-1.0)},-2.5);
+    test_eq!(unsafe{powif32(a,2)},6.25);
+    test_eq!(unsafe{exp2f32(black_box(3.0))},8.0);
+    test_eq!(unsafe{sqrtf32(black_box(16.0))},4.0);
+    let b:f64 = black_box(-2.5);
+    test_eq!(unsafe{floorf64(b)},-3.0);
+    test_eq!(unsafe{ceilf64(b)},-2.0);
+    test_eq!(unsafe{roundf64(b)},-3.0);
+    test_eq!(unsafe{nearbyintf64(b)},-2.0);
+    test_eq!(unsafe{fabsf64(b)},2.5);
+    test_eq!(unsafe{powf64(black_box(2.0),black_box(10.0))},1024.0);
+    test_eq!(unsafe{log2f64(black_box(1024.0))},10.0);
+    test_eq!(unsafe{log10f64(black_box(1000.0))},3.0);
+    test_eq!(unsafe{fmaf64(b,2.0,1.0)},-4.0);
+    test_eq!(unsafe{maxnumf64(b,1.0)},1.0);
+    test_eq!(unsafe{minnumf64(b,1.0)},-2.5);
+    // `minnum` and `maxnum` ignore a NaN operand.
+    let nan:f64 = black_box(f64::NAN);
+    test_eq!(unsafe{maxnumf64(nan,b)},b);
+    test_eq!(unsafe{minnumf64(b,nan)},b);
+    test!(unsafe{maxnumf64(nan,nan)}.is_nan());
+    let nan32:f32 = black_box(f32::NAN);
+    test_eq!(unsafe{minnumf32(nan32,a)},a);
+    test_eq!(unsafe{maxnumf32(a,nan32)},a);
+    test_eq!(unsafe{sinf64(black_box(0.0))},0.0);
+    test_eq!(unsafe{cosf64(black_box(0.0))},1.0);
+    test_eq!(unsafe{expf64(black_box(0.0))},1.0);
+    test_eq!(unsafe{logf64(black_box(1.0))},0.0);
+}
@@ -17,5 +17,39 @@ fn main(){
     let (val,is_eq) = unsafe{core::intrinsics::atomic_cxchgweak_acquire_relaxed(addr_of_mut!(u),10_u32,20)};
     test_eq!(val,10_u32);
     //test_eq!(is_eq,true);
+    test_rmw();
+    test_subword();
+}
+fn test_rmw(){
+    let mut u:u32 = black_box(0b1100);
+    test_eq!(unsafe{core::intrinsics::atomic_xadd_seqcst(addr_of_mut!(u),1)},0b1100);
+    test_eq!(unsafe{core::intrinsics::atomic_and_acqrel(addr_of_mut!(u),0b0101)},0b1101);
+    test_eq!(unsafe{core::intrinsics::atomic_or_relaxed(addr_of_mut!(u),0b0010)},0b0101);
+    test_eq!(unsafe{core::intrinsics::atomic_xor_acquire(addr_of_mut!(u),0b1111)},0b0111);
+    test_eq!(unsafe{core::intrinsics::atomic_nand_release(addr_of_mut!(u),0b1000)},0b1000);
+    test_eq!(u,!0b1000_u32);
+    let mut i:i64 = black_box(-5);
+    test_eq!(unsafe{core::intrinsics::atomic_max_seqcst(addr_of_mut!(i),3)},-5);
+    test_eq!(unsafe{core::intrinsics::atomic_min_seqcst(addr_of_mut!(i),-7)},3);
+    test_eq!(unsafe{core::intrinsics::atomic_load_seqcst(addr_of_mut!(i))},-7);
+    let mut u:usize = black_box(5);
+    test_eq!(unsafe{core::intrinsics::atomic_umax_relaxed(addr_of_mut!(u),usize::MAX)},5);
+    test_eq!(unsafe{core::intrinsics::atomic_umin_relaxed(addr_of_mut!(u),7)},usize::MAX);
+    test_eq!(unsafe{core::intrinsics::atomic_xchg_acquire(addr_of_mut!(u),9)},7);
+    unsafe{core::intrinsics::atomic_store_seqcst(addr_of_mut!(u),11)};
+    test_eq!(unsafe{core::intrinsics::atomic_load_acquire(addr_of_mut!(u))},11);
+}
+fn test_subword(){
+    // Sub-word atomics must leave the neighbouring bytes untouched.
+    let mut bytes:[u8;4] = black_box([1,2,3,4]);
+    let ptr = addr_of_mut!(bytes[1]);
+    let (val,is_eq) = unsafe{core::intrinsics::atomic_cxchg_seqcst_seqcst(ptr,2_u8,200)};
+    test_eq!(val,2_u8);
+    test_eq!(unsafe{core::intrinsics::atomic_xadd_relaxed(ptr,100)},200_u8);
+    test_eq!(bytes,[1,44,3,4]);
+    let mut short:i16 = black_box(-1);
+    test_eq!(unsafe{core::intrinsics::atomic_xchg_seqcst(addr_of_mut!(short),7)},-1);
+    test_eq!(short,7);
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::AcqRel);
 }
-    
\ No newline at end of file
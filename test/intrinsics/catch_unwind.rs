@@ -0,0 +1,44 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code)]
+#![no_std]
+include!("../common.rs");
+extern "C"{
+    fn _Unwind_RaiseException(exception:*mut u8)->i32;
+}
+// Raises a panic with the second byte of `data` as its payload.
+fn panicking(data:*mut u8){
+    unsafe{
+        *data = 1;
+        _Unwind_RaiseException(data.add(1));
+        // Never reached, since the panic unwinds past this point.
+        *data = 3;
+    }
+}
+fn not_panicking(data:*mut u8){
+    unsafe{*data = 4};
+}
+fn catch(data:*mut u8,payload:*mut u8){
+    unsafe{
+        test_eq!(*data,1);
+        test_eq!(payload,data.add(1));
+        *data = 2;
+    }
+}
+fn nested(data:*mut u8){
+    let caught = unsafe{core::intrinsics::catch_unwind(panicking,data,catch)};
+    test_eq!(caught,1);
+    // The outer catch must see this panic, and not the one already caught.
+    panicking(data);
+}
+fn main(){
+    let mut data = [0_u8;2];
+    let caught = unsafe{core::intrinsics::catch_unwind(panicking,data.as_mut_ptr(),catch)};
+    test_eq!(caught,1);
+    test_eq!(data[0],2);
+    let caught = unsafe{core::intrinsics::catch_unwind(not_panicking,data.as_mut_ptr(),catch)};
+    test_eq!(caught,0);
+    test_eq!(data[0],4);
+    let caught = unsafe{core::intrinsics::catch_unwind(nested,data.as_mut_ptr(),catch)};
+    test_eq!(caught,1);
+    test_eq!(data[0],2);
+}
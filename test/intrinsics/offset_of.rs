@@ -1,22 +1,36 @@
-#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![feature(lang_items,offset_of_enum,offset_of_nested,adt_const_params,associated_type_defaults,core_intrinsics,start)]
 #![allow(internal_features,incomplete_features,unused_variables,dead_code)]
 #![no_std]
 include!("../common.rs");
 use core::mem::offset_of;
 type Tuple = (u64, [i128; 4], u32, f64);
 #[derive(Default)]
+#[repr(C)]
 struct Quad<T:Default>{
     a:T,
     b:T,
     c:T,
     d:T
 }
+#[repr(C)]
+struct Nested{
+    a:u64,
+    inner:Quad<u32>,
+}
+#[repr(C, u8)]
+enum Tagged{
+    A(u8),
+    B(u32,u64),
+}
 fn main(){
    
     test_eq!(black_box(offset_of!(Tuple,0)),64);
     test_eq!(offset_of!(Tuple,1),0);
     test_eq!(offset_of!(Tuple,2),72);
     test_eq!(offset_of!(Tuple,3),80);
+    test_eq!(offset_of!(Quad<u32>,c),8);
+    test_eq!(offset_of!(Nested,inner.c),16);
+    test_eq!(offset_of!(Tagged,B.1),16);
    
 }
     
\ No newline at end of file
@@ -0,0 +1,102 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start,repr_simd)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code,non_camel_case_types)]
+#![no_std]
+include!("../common.rs");
+extern crate core;
+
+use core::intrinsics::simd::*;
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct f32x4(f32, f32, f32, f32);
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct i32x4(i32, i32, i32, i32);
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct u8x16([u8; 16]);
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct f64x2(f64, f64);
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct i64x2(i64, i64);
+const SHUFFLE:[u32; 4] = [0, 5, 2, 7];
+const REVERSE:[u32; 2] = [1, 0];
+
+fn main(){
+    let a = black_box(i32x4(1, 2, 3, 4));
+    let b = black_box(i32x4(10, 20, 30, 40));
+    let sum: i32x4 = unsafe{simd_add(a, b)};
+    test_eq!(sum.0, 11);
+    test_eq!(sum.3, 44);
+    let diff: i32x4 = unsafe{simd_sub(b, a)};
+    test_eq!(diff.1, 18);
+    test_eq!(unsafe{simd_extract::<_, i32>(diff, 2)}, 27);
+    // SIMD types are plain structs outside of intrinsics, so their fields can be written directly.
+    let mut written = diff;
+    written.1 = black_box(7);
+    test_eq!(unsafe{simd_extract::<_, i32>(written, 1)}, 7);
+    test_eq!(written.2, 27);
+    let inserted: i32x4 = unsafe{simd_insert(diff, 2, -1_i32)};
+    test_eq!(inserted.2, -1);
+    let mask: i32x4 = unsafe{simd_lt(a, i32x4(2, 2, 2, 2))};
+    test_eq!(mask.0, -1);
+    test_eq!(mask.1, 0);
+    let selected: i32x4 = unsafe{simd_select(mask, a, b)};
+    test_eq!(selected.0, 1);
+    test_eq!(selected.1, 20);
+    test_eq!(unsafe{simd_reduce_add_ordered::<_, i32>(a, 0)}, 10);
+    test_eq!(unsafe{simd_reduce_any(mask)}, true);
+    test_eq!(unsafe{simd_reduce_all(mask)}, false);
+    let f = black_box(f32x4(1.0, 4.0, 9.0, 16.0));
+    let prod: f32x4 = unsafe{simd_mul(f, f32x4(2.0, 2.0, 2.0, 2.0))};
+    test_eq!(prod.3, 32.0);
+    let root: f32x4 = unsafe{simd_fsqrt(f)};
+    test_eq!(root.2, 3.0);
+    let bytes = black_box(u8x16([1; 16]));
+    let doubled: u8x16 = unsafe{simd_shl(bytes, u8x16([1; 16]))};
+    test_eq!(doubled.0[15], 2);
+    // Indices past the lanes of the first vector select lanes of the second one.
+    let shuffled: i32x4 = unsafe{simd_shuffle(a, b, SHUFFLE)};
+    test_eq!(shuffled.0, 1);
+    test_eq!(shuffled.1, 20);
+    test_eq!(shuffled.2, 3);
+    test_eq!(shuffled.3, 40);
+    // The output may have a different number of lanes than the inputs.
+    let wide = black_box(i64x2(-5, 6));
+    let reversed: i64x2 = unsafe{simd_shuffle(wide, wide, REVERSE)};
+    test_eq!(reversed.0, 6);
+    test_eq!(reversed.1, -5);
+    let floats: f32x4 = unsafe{simd_cast(a)};
+    test_eq!(floats.1, 2.0);
+    let halves = black_box(f32x4(-1.5, 2.5, 300.0, -0.5));
+    let ints: i32x4 = unsafe{simd_cast(halves)};
+    test_eq!(ints.0, -1);
+    test_eq!(ints.1, 2);
+    test_eq!(ints.2, 300);
+    test_eq!(ints.3, 0);
+    let doubles: f64x2 = unsafe{simd_cast(wide)};
+    test_eq!(doubles.0, -5.0);
+    let lt_three: i32x4 = unsafe{simd_lt(a, i32x4(3, 3, 3, 3))};
+    test_eq!(unsafe{simd_bitmask::<_, u8>(lt_three)}, 0b0011);
+    test_eq!(unsafe{simd_bitmask::<_, u8>(mask)}, 0b0001);
+    let floored: f32x4 = unsafe{simd_floor(halves)};
+    test_eq!(floored.0, -2.0);
+    test_eq!(floored.1, 2.0);
+    let ceiled: f32x4 = unsafe{simd_ceil(halves)};
+    test_eq!(ceiled.0, -1.0);
+    test_eq!(ceiled.3, 0.0);
+    let truncated: f32x4 = unsafe{simd_trunc(halves)};
+    test_eq!(truncated.0, -1.0);
+    test_eq!(truncated.1, 2.0);
+    let zeroes = black_box(f64x2(0.0, 0.0));
+    let sines: f64x2 = unsafe{simd_fsin(zeroes)};
+    test_eq!(sines.1, 0.0);
+    let cosines: f64x2 = unsafe{simd_fcos(zeroes)};
+    test_eq!(cosines.0, 1.0);
+    let exps: f64x2 = unsafe{simd_fexp(zeroes)};
+    test_eq!(exps.0, 1.0);
+    let logs: f64x2 = unsafe{simd_flog(exps)};
+    test_eq!(logs.1, 0.0);
+}
@@ -0,0 +1,60 @@
+#![feature(trait_upcasting)]
+#![allow(dead_code)]
+use std::any::Any;
+use std::hint::black_box;
+trait Named{
+    fn name(&self)->u32;
+}
+trait Versioned{
+    fn version(&self)->u32;
+}
+trait Plugin:Named + Versioned{
+    fn run(&self)->u32;
+}
+struct TestObject(u32,u32,u32);
+impl Named for TestObject{
+    fn name(&self)->u32{
+        self.0
+    }
+}
+impl Versioned for TestObject{
+    fn version(&self)->u32{
+        self.1
+    }
+}
+impl Plugin for TestObject{
+    fn run(&self)->u32{
+        self.2
+    }
+}
+static mut DROPS:u32 = 0;
+impl Drop for TestObject{
+    fn drop(&mut self){
+        unsafe{DROPS += 1};
+    }
+}
+fn plugin(a:u32,b:u32,c:u32)->Box<dyn Plugin>{
+    Box::new(TestObject(black_box(a),black_box(b),black_box(c)))
+}
+fn main(){
+    // `Named` is the first supertrait, so its vtable is a prefix of the `Plugin` vtable.
+    let named:Box<dyn Named> = black_box(plugin(1,2,3));
+    assert!(named.name() == 1);
+    // The `Versioned` vtable has to be loaded from the `Plugin` vtable.
+    let versioned:Box<dyn Versioned> = black_box(plugin(4,5,6));
+    assert!(versioned.version() == 5);
+    // Dropping an upcasted box must still drop and free the original object.
+    drop(named);
+    drop(versioned);
+    assert!(unsafe{DROPS} == 2);
+    // Upcasting to a `dyn` without a principal trait keeps the original vtable.
+    let any:Box<dyn Any + Send> = Box::new(black_box(TestObject(7,8,9)));
+    let send:Box<dyn Send> = black_box(any);
+    assert!(std::mem::size_of_val(&*send) == std::mem::size_of::<TestObject>());
+    assert!(std::mem::align_of_val(&*send) == std::mem::align_of::<TestObject>());
+    drop(send);
+    assert!(unsafe{DROPS} == 3);
+    let any:&(dyn Any + Send) = &black_box(7_u64);
+    let send:&dyn Send = black_box(any);
+    assert!(std::mem::size_of_val(send) == 8);
+}
@@ -0,0 +1,10 @@
+fn main() {
+    let caught = std::panic::catch_unwind(|| {
+        panic!("Expected panic");
+    });
+    assert!(caught.is_err());
+    let payload = caught.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"Expected panic"));
+    let not_caught = std::panic::catch_unwind(|| std::hint::black_box(64));
+    assert_eq!(not_caught.ok(), Some(64));
+}
@@ -0,0 +1,67 @@
+#![feature(adt_const_params,core_intrinsics)]
+#![allow(internal_features,incomplete_features,dead_code,improper_ctypes_definitions)]
+// Mirrors the `&str` <-> `System.String` conversions of `mycorrhiza::system::MString`.
+#[derive(Clone,Copy)]
+struct RustcCLRInteropManagedClass<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str>{
+    prevent_construction:usize,
+}
+#[derive(Clone,Copy)]
+struct RustcCLRInteropManagedStruct<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str>{
+    size_hint:usize,
+}
+#[inline(never)]
+fn rustc_clr_interop_managed_call0_<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str,const IS_VALUETYPE:bool,const METHOD:&'static str,Ret>()->Ret{
+    std::process::abort();
+}
+#[inline(never)]
+fn rustc_clr_interop_managed_call1_<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str,const IS_VALUETYPE:bool,const METHOD:&'static str,const IS_STATIC:bool,Ret,Arg1>(arg1:Arg1)->Ret{
+    std::process::abort();
+}
+#[inline(never)]
+fn rustc_clr_interop_managed_call2_<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str,const IS_VALUETYPE:bool,const METHOD:&'static str,const IS_STATIC:bool,Ret,Arg1,Arg2>(arg1:Arg1,arg2:Arg2)->Ret{
+    std::process::abort();
+}
+#[inline(never)]
+fn rustc_clr_interop_managed_call3_<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str,const IS_VALUETYPE:bool,const METHOD:&'static str,const IS_STATIC:bool,Ret,Arg1,Arg2,Arg3>(arg1:Arg1,arg2:Arg2,arg3:Arg3)->Ret{
+    std::process::abort();
+}
+#[inline(never)]
+fn rustc_clr_interop_managed_call5_<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str,const IS_VALUETYPE:bool,const METHOD:&'static str,const IS_STATIC:bool,Ret,Arg1,Arg2,Arg3,Arg4,Arg5>(arg1:Arg1,arg2:Arg2,arg3:Arg3,arg4:Arg4,arg5:Arg5)->Ret{
+    std::process::abort();
+}
+type Object = RustcCLRInteropManagedClass<"System.Runtime","System.Object">;
+type MString = RustcCLRInteropManagedClass<"System.Runtime","System.String">;
+type Encoding = RustcCLRInteropManagedClass<"System.Runtime","System.Text.Encoding">;
+type GCHandle = RustcCLRInteropManagedStruct<"System.Runtime","System.Runtime.InteropServices.GCHandle">;
+type GCHandleType = RustcCLRInteropManagedStruct<"System.Runtime","System.Runtime.InteropServices.GCHandleType">;
+fn utf8()->Encoding{
+    rustc_clr_interop_managed_call0_::<"System.Runtime","System.Text.Encoding",false,"get_UTF8",Encoding>()
+}
+fn from_rust_str(string:&str)->MString{
+    rustc_clr_interop_managed_call3_::<"System.Runtime","System.Text.Encoding",false,"GetString",false,MString,Encoding,*const u8,i32>(utf8(),string.as_ptr(),string.len() as i32)
+}
+fn utf16_len(string:MString)->usize{
+    rustc_clr_interop_managed_call1_::<"System.Runtime","System.String",false,"get_Length",false,i32,MString>(string) as usize
+}
+fn utf8_len(string:MString)->usize{
+    rustc_clr_interop_managed_call2_::<"System.Runtime","System.Text.Encoding",false,"GetByteCount",false,i32,Encoding,MString>(utf8(),string) as usize
+}
+fn to_rust_string(string:MString)->String{
+    let mut buf = vec![0_u8;utf8_len(string)];
+    let pinned:GCHandleType = unsafe{core::intrinsics::transmute_unchecked::<usize,GCHandleType>(3)};
+    let mut handle = rustc_clr_interop_managed_call2_::<"System.Runtime","System.Runtime.InteropServices.GCHandle",true,"Alloc",true,GCHandle,Object,GCHandleType>(unsafe{core::intrinsics::transmute_unchecked(string)},pinned);
+    let chars = rustc_clr_interop_managed_call1_::<"System.Runtime","System.Runtime.InteropServices.GCHandle",true,"AddrOfPinnedObject",false,isize,*mut GCHandle>(&mut handle) as *const u16;
+    let written = rustc_clr_interop_managed_call5_::<"System.Runtime","System.Text.Encoding",false,"GetBytes",false,i32,Encoding,*const u16,i32,*mut u8,i32>(utf8(),chars,utf16_len(string) as i32,buf.as_mut_ptr(),buf.len() as i32);
+    rustc_clr_interop_managed_call1_::<"System.Runtime","System.Runtime.InteropServices.GCHandle",true,"Free",false,(),*mut GCHandle>(&mut handle);
+    buf.truncate(written as usize);
+    String::from_utf8(buf).unwrap()
+}
+fn main(){
+    for original in ["", "Hello, .NET!", "Zażółć gęślą jaźń", "🦀 crab"]{
+        let managed = from_rust_str(std::hint::black_box(original));
+        // Characters outside the BMP take 2 UTF-16 code units.
+        assert_eq!(utf16_len(managed),original.encode_utf16().count());
+        assert_eq!(utf8_len(managed),original.len());
+        assert_eq!(to_rust_string(managed),original);
+    }
+}
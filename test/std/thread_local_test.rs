@@ -0,0 +1,26 @@
+#![feature(thread_local)]
+use std::cell::Cell;
+#[thread_local]
+static COUNTER: Cell<u32> = Cell::new(5);
+thread_local! {
+    static NAME: Cell<u64> = const { Cell::new(7) };
+}
+fn main() {
+    COUNTER.set(COUNTER.get() + 1);
+    NAME.with(|name| name.set(name.get() * 2));
+    assert_eq!(COUNTER.get(), 6);
+    assert_eq!(NAME.with(Cell::get), 14);
+    let (counter, name) = std::thread::spawn(|| {
+        // Each thread starts with its own, freshly initialized, copy.
+        let counter = COUNTER.get();
+        COUNTER.set(100);
+        (counter, NAME.with(Cell::get))
+    })
+    .join()
+    .unwrap();
+    assert_eq!(counter, 5);
+    assert_eq!(name, 7);
+    // Changes made by other threads are not visible.
+    assert_eq!(COUNTER.get(), 6);
+    assert_eq!(NAME.with(Cell::get), 14);
+}
@@ -0,0 +1,53 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code)]
+#![no_std]
+include!("../common.rs");
+#[repr(align(64))]
+#[derive(Clone,Copy)]
+struct CacheLine{
+    val:u64,
+}
+#[repr(C,align(32))]
+#[derive(Clone,Copy)]
+struct Pair{
+    a:u32,
+    b:u32,
+}
+#[derive(Clone,Copy)]
+struct Wide{
+    tag:u8,
+    val:u128,
+}
+fn is_aligned<T>(val:&T)->bool{
+    (val as *const T as usize) % core::mem::align_of::<T>() == 0
+}
+fn by_value(line:CacheLine)->u64{
+    test!(is_aligned(&line));
+    line.val
+}
+fn make_pair(a:u32,b:u32)->Pair{
+    let pair = Pair{a,b};
+    test!(is_aligned(&pair));
+    pair
+}
+fn main(){
+    let line = CacheLine{val:black_box(0x40)};
+    test!(is_aligned(&line));
+    test_eq!(line.val,0x40);
+    let mut other = black_box(line);
+    other.val += 1;
+    test!(is_aligned(&other));
+    test_eq!(by_value(other),0x41);
+    let pair = make_pair(black_box(1),black_box(2));
+    test!(is_aligned(&pair));
+    test_eq!(pair.a + pair.b,3);
+    let lines = [line,other];
+    test!(is_aligned(&lines));
+    test_eq!(lines[1].val,0x41);
+    // Structs with 128 bit fields are over-aligned too.
+    let wide = Wide{tag:black_box(1),val:black_box(u128::MAX)};
+    test!(is_aligned(&wide));
+    test!(is_aligned(&wide.val));
+    test_eq!(wide.val,u128::MAX);
+    test_eq!(wide.tag,1);
+}
@@ -2,24 +2,53 @@
 #![allow(internal_features,incomplete_features,unused_variables,dead_code)]
 #![no_std]
 include!("../common.rs");
-
-fn main(){
-    /* 
-    let animal = black_box(Animal::Cow(8));
-    match animal{
-        Animal::Cow(val)=>{
-           
-            Put::putnl(val);
-            test_eq!(val,8);
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+fn noop_raw_waker() -> RawWaker {
+    fn clone(_: *const ()) -> RawWaker {
+        noop_raw_waker()
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    RawWaker::new(core::ptr::null(), &VTABLE)
+}
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = pin!(fut);
+    loop {
+        if let Poll::Ready(val) = fut.as_mut().poll(&mut cx) {
+            return val;
         }
-        Animal::Dog(val)=>{
-            Put::putnl(val);
-            test_eq!(0,1);
+    }
+}
+struct YieldOnce(bool);
+impl Future for YieldOnce {
+    type Output = ();
+    fn poll(mut self: core::pin::Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            Poll::Pending
         }
     }
-    //test_indirect(&animal);*/
+}
+fn main(){
    let x = black_box(async_fn(8,black_box(9.9)));
+   test_eq!(block_on(x), 17.9);
+   let sum = block_on(suspending_fn(black_box(3)));
+   test_eq!(sum, 6);
 }
 async fn async_fn(a:i32,b:f32)->f32{
     a as f32 + black_box(b)
 }
+async fn suspending_fn(n:u32)->u32{
+    let mut sum = 0;
+    for i in 1..=n {
+        YieldOnce(false).await;
+        sum += black_box(i);
+    }
+    sum
+}
@@ -0,0 +1,71 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code,improper_ctypes_definitions,improper_ctypes)]
+#![no_std]
+include!("../common.rs");
+#[derive(Clone,Copy)]
+struct RustcCLRInteropManagedClass<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str>{
+    prevent_construction:usize,
+}
+#[inline(never)]
+fn rustc_clr_interop_managed_ctor2_<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str,const IS_VALUETYPE:bool,Arg1,Arg2>(arg1:Arg1,arg2:Arg2)->RustcCLRInteropManagedClass<ASSEMBLY,CLASS_PATH>{
+    core::intrinsics::abort();
+}
+#[inline(never)]
+fn rustc_clr_interop_managed_call_virt1_<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str,const IS_VALUETYPE:bool,const METHOD:&'static str,const IS_STATIC:bool,Ret,Arg1>(arg1:Arg1)->Ret{
+    core::intrinsics::abort();
+}
+#[inline(never)]
+fn rustc_clr_interop_managed_delegate_ctor_<Delegate,Target,F>(target:Target,f:F)->Delegate{
+    core::intrinsics::abort();
+}
+type Object = RustcCLRInteropManagedClass<"System.Runtime","System.Object">;
+type Action = RustcCLRInteropManagedClass<"System.Runtime","System.Action">;
+type RustClosureState = RustcCLRInteropManagedClass<"","RustClosureState">;
+static mut CALLS:u32 = 0;
+static mut DROPS:u32 = 0;
+fn count(){
+    unsafe{CALLS += 1};
+}
+fn invoke(action:Action){
+    rustc_clr_interop_managed_call_virt1_::<"System.Runtime","System.Action",false,"Invoke",false,(),Action>(action);
+}
+fn collect(){
+    rustc_clr_interop_managed_call0_::<"System.Runtime","System.GC",false,"Collect",()>();
+    rustc_clr_interop_managed_call0_::<"System.Runtime","System.GC",false,"WaitForPendingFinalizers",()>();
+}
+// Mirrors `mycorrhiza::system::delegates::Action::from_closure`.
+fn from_closure<F:Fn()>(f:F)->Action{
+    let state = rustc_clr_interop_managed_call1_::<"System.Runtime.InteropServices","System.Runtime.InteropServices.Marshal",false,"AllocHGlobal",true,isize,isize>(core::mem::size_of::<F>().max(1) as isize) as *mut F;
+    unsafe{state.write(f)};
+    let drop:unsafe fn(*mut F) = core::ptr::drop_in_place::<F>;
+    let state = rustc_clr_interop_managed_ctor2_::<"","RustClosureState",false,*mut (),isize>(state as *mut (),drop as usize as isize);
+    rustc_clr_interop_managed_delegate_ctor_::<Action,RustClosureState,fn(Object)>(state,trampoline::<F>)
+}
+fn trampoline<F:Fn()>(target:Object){
+    let state:RustClosureState = unsafe{core::intrinsics::transmute_unchecked(target)};
+    let state = rustc_clr_interop_managed_call1_::<"","RustClosureState",false,"get_State",false,*mut (),RustClosureState>(state) as *mut F;
+    unsafe{(*state)()}
+}
+struct Guard(u32);
+impl Drop for Guard{
+    fn drop(&mut self){
+        unsafe{DROPS += 1};
+    }
+}
+#[inline(never)]
+fn call_closure(){
+    let guard = Guard(black_box(5));
+    let action = from_closure(move ||unsafe{CALLS += guard.0});
+    invoke(action);
+    invoke(action);
+}
+fn main(){
+    let action = rustc_clr_interop_managed_delegate_ctor_::<Action,(),fn()>((),count);
+    invoke(action);
+    test_eq!(unsafe{CALLS},1);
+    call_closure();
+    test_eq!(unsafe{CALLS},11);
+    // The closure is dropped once the delegate owning it gets collected.
+    collect();
+    test_eq!(unsafe{DROPS},1);
+}
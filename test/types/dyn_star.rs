@@ -0,0 +1,30 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start,dyn_star)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code)]
+#![no_std]
+include!("../common.rs");
+trait Value{
+    fn value(&self)->usize;
+}
+impl Value for usize{
+    fn value(&self)->usize{
+        *self
+    }
+}
+impl Value for &'static u8{
+    fn value(&self)->usize{
+        **self as usize + 1
+    }
+}
+static BYTE:u8 = 0x20;
+fn main(){
+    let val:usize = black_box(0x64);
+    let dyn_val:dyn* Value = val;
+    test_eq!(dyn_val.value(),0x64);
+    // A `dyn*` created from a pointer calls the implementation for that pointer type.
+    let byte:&'static u8 = black_box(&BYTE);
+    let dyn_ref:dyn* Value = byte;
+    test_eq!(dyn_ref.value(),0x21);
+    // The value is stored directly inside the `dyn*`, next to its vtable.
+    let (data,_vtable):(usize,usize) = unsafe{core::mem::transmute(dyn_val)};
+    test_eq!(data,0x64);
+}
@@ -1,4 +1,4 @@
-#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start,trait_upcasting)]
 #![allow(internal_features,incomplete_features,unused_variables,dead_code)]
 #![no_std]
 include!("../common.rs");
@@ -47,7 +47,41 @@ pub fn test(fun:Option<&dyn TestTrait>){
         _=>(),
     } 
 }
+pub trait Named{
+    fn name(&self)->u32;
+}
+pub trait Versioned{
+    fn version(&self)->u32;
+}
+pub trait Plugin:Named + Versioned{
+    fn run(&self)->u32;
+}
+impl Named for TestObject{
+    fn name(&self)->u32{
+        self.0
+    }
+}
+impl Versioned for TestObject{
+    fn version(&self)->u32{
+        self.1
+    }
+}
+impl Plugin for TestObject{
+    fn run(&self)->u32{
+        self.2
+    }
+}
+fn test_upcast(plugin:&dyn Plugin){
+    test_eq!(plugin.run(),3);
+    // `Named` is the first supertrait: its vtable is a prefix of the `Plugin` vtable.
+    let named:&dyn Named = black_box(plugin);
+    test_eq!(named.name(),1);
+    // `Versioned` has its own vtable, which needs to be loaded from the `Plugin` vtable.
+    let versioned:&dyn Versioned = black_box(plugin);
+    test_eq!(versioned.version(),2);
+}
 fn main(){
+    test_upcast(black_box(&TestObject(1,2,3)));
     test(None);
     let bob = TestObject(64,64,32);
     bob.test_fn();
@@ -0,0 +1,22 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code,unused_imports)]
+#![no_std]
+include!("../common.rs");
+#[export_name = "rustc_clr_interop_export:RustTest.Exported::Add"]
+pub fn add(a:i32,b:i32)->i32{
+    a + b
+}
+#[export_name = "rustc_clr_interop_export:RustTest.Exported::Store"]
+pub fn store(val:u64){
+    unsafe{STORED = val};
+}
+static mut STORED:u64 = 0;
+fn main(){
+    test_eq!(add(black_box(2),black_box(3)),5);
+    store(black_box(8));
+    test_eq!(unsafe{STORED},8);
+    // Call the public wrappers, the same way C# code would.
+    test_eq!(rustc_clr_interop_managed_call2_::<"","RustTest.Exported",true,"Add",true,i32,i32,i32>(black_box(4),black_box(5)),9);
+    rustc_clr_interop_managed_call1_::<"","RustTest.Exported",true,"Store",true,(),u64>(black_box(16));
+    test_eq!(unsafe{STORED},16);
+}
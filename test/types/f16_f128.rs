@@ -0,0 +1,80 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    f16,
+    f128
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+extern crate core;
+
+fn main() {
+    let a: f16 = black_box(1.5);
+    let b: f16 = black_box(2.25);
+    test_eq!(a + b, 3.75);
+    test_eq!(b - a, 0.75);
+    test_eq!(a * b, 3.375);
+    test_eq!(-a, -1.5);
+    test_eq!(a < b, true);
+    test_eq!(a >= b, false);
+    test_eq!(a as f32, 1.5);
+    test_eq!(black_box(7_i32) as f16, 7.0);
+    test_eq!(b as u8, 2);
+    let c: f128 = black_box(1.5);
+    let d: f128 = black_box(2.25);
+    test_eq!(c + d, 3.75);
+    test_eq!(d - c, 0.75);
+    test_eq!(c * d, 3.375);
+    test_eq!(d / c, 1.5);
+    test_eq!(-c, -1.5);
+    test_eq!(c < d, true);
+    test_eq!(c <= c, true);
+    test_eq!(c > d, false);
+    test_eq!(c as f64, 1.5);
+    test_eq!(black_box(0.1_f64) as f128 as f64, 0.1);
+    test_eq!(black_box(-3_i64) as f128, -3.0);
+    test_eq!(d as u32, 2);
+    let nan = black_box(f128::NAN);
+    test_eq!(nan == nan, false);
+    test_eq!(nan < c, false);
+    test_eq!(nan >= c, false);
+    test_eq!(black_box(-0.0_f128) == black_box(0.0_f128), true);
+    // Values differing only past the precision of `f64` must still compare, and convert, exactly.
+    let one: f128 = black_box(1.0);
+    let bits = unsafe { core::mem::transmute::<f128, u128>(one) };
+    let next = unsafe { core::mem::transmute::<u128, f128>(black_box(bits + 1)) };
+    let prev = unsafe { core::mem::transmute::<u128, f128>(black_box(bits - 1)) };
+    test_eq!(next == one, false);
+    test_eq!(next > one, true);
+    test_eq!(prev < one, true);
+    test_eq!(-next < -one, true);
+    test_eq!(next as f64, 1.0);
+    test_eq!(prev as u32, 0);
+    test_eq!(next as i8, 1);
+    test_eq!(black_box(u64::MAX) as f128 as u64, u64::MAX);
+    test_eq!(black_box(i64::MIN) as f128 as i64, i64::MIN);
+    test_eq!(black_box((1_i64 << 60) + 1) as f128 > (1_i64 << 60) as f128, true);
+    test_eq!(black_box(-1.0e30_f64) as f128 as i64, i64::MIN);
+    test_eq!(black_box(-2.0_f64) as f128 as u16, 0);
+    // 1 + 2^-24 + 2^-80 rounds up to 1 + 2^-23, but rounding it to a `f64` first would make it a tie, rounded down to 1.
+    let above_tie = unsafe {
+        core::mem::transmute::<u128, f128>(black_box(bits + (1 << 88) + (1 << 32)))
+    };
+    test_eq!(above_tie as f32, 1.0 + f32::EPSILON);
+    // Arithmetic is rounded to the precision of `f128`, not `f64`.
+    test_eq!(next - one, f128::EPSILON);
+    let three: f128 = black_box(3.0);
+    test_eq!(one / three * three, one);
+    test_eq!((one / three) as f64, 1.0 / 3.0);
+    test_eq!(f128::MIN_POSITIVE / black_box(2.0) > 0.0, true);
+    test_eq!(black_box(f128::MAX) * black_box(2.0), f128::INFINITY);
+    test_eq!(black_box(i128::MIN) as f128 as i128, i128::MIN);
+    test_eq!(black_box(u128::MAX) as f128 as u128, u128::MAX);
+    // 2^113 + 1 is a tie, rounded to even. 2^113 + 3 is also a tie, rounded up.
+    test_eq!(black_box((1_u128 << 113) + 1) as f128 as u128, 1 << 113);
+    test_eq!(black_box((1_u128 << 113) + 3) as f128 as u128, (1 << 113) + 4);
+}
@@ -0,0 +1,24 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code)]
+#![no_std]
+include!("../common.rs");
+fn ident(val:u32)->u32{
+    val
+}
+fn main(){
+    let fn_ptr:fn(u32)->u32 = black_box(ident);
+    let raw = fn_ptr as *const ();
+    test_eq!(raw as usize,fn_ptr as usize);
+    // Casting the pointer back must give a callable function pointer.
+    let back:fn(u32)->u32 = unsafe{core::mem::transmute(black_box(raw))};
+    test_eq!(back(7),7);
+    let unsafe_ptr:unsafe fn(u32)->u32 = black_box(fn_ptr);
+    test_eq!(unsafe{unsafe_ptr(8)},8);
+    // Non-capturing closures can be coerced into function pointers too.
+    let add:fn(u32,u32)->u32 = black_box(|a,b|a + b);
+    test_eq!(add(3,4),7);
+    let unit:fn() = black_box(||{});
+    unit();
+    let shift:fn(&u64)->u64 = |val|*val << 1;
+    test_eq!(black_box(shift)(&black_box(21)),42);
+}
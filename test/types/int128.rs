@@ -15,4 +15,41 @@ fn main(){
         _=>black_box(11),
     };
     black_box(x);
+    let a = black_box(0x1_0000_0000_0000_0001_u128);
+    let b = black_box(0xFFFF_FFFF_FFFF_FFFF_u128);
+    test_eq!(a + b, 0x2_0000_0000_0000_0000_u128);
+    test_eq!(a - b, 0x2_u128);
+    test_eq!(b * b, 0xFFFF_FFFF_FFFF_FFFE_0000_0000_0000_0001_u128);
+    test_eq!((a * b) / b, a);
+    test_eq!(a % b, 2);
+    test_eq!(a << 63, 0x8000_0000_0000_0000_8000_0000_0000_0000_u128);
+    test_eq!(a >> 64, 1);
+    test_eq!(!b, 0xFFFF_FFFF_FFFF_FFFF_0000_0000_0000_0000_u128);
+    test_eq!(a > b, true);
+    test_eq!(a as u64, 1);
+    test_eq!(b as f64, 18446744073709551615.0);
+    // Rounding the upper and lower halves separately would give a different result.
+    let e = black_box((1_u128 << 64) + (1 << 63) + 2049);
+    test_eq!(e as f64, 2.767011611056433e19);
+    test_eq!(-(e as i128) as f64, -2.767011611056433e19);
+    // Rounding to a `f64` first would give a different result.
+    let f = black_box(0x8000_0080_0000_0001_u128 << 32);
+    test_eq!(f as f32, 3.9614086e28);
+    test_eq!(-(f as i128) as f32, -3.9614086e28);
+    test_eq!(black_box(u128::MAX) as f32, f32::INFINITY);
+    let c = black_box(-7_i128);
+    let d = black_box(2_i128);
+    test_eq!(c / d, -3);
+    test_eq!(c % d, -1);
+    test_eq!(c >> 1, -4);
+    test_eq!(c < d, true);
+    test_eq!(-c, 7);
+    test_eq!(c as i8, -7);
+    test_eq!(black_box(-1_i32) as i128, -1);
+    test_eq!(black_box(-2.5_f64) as i128, -2);
+    test_eq!(c as f32, -7.0);
+    test_eq!(i128::MIN / black_box(-1_i128).wrapping_add(2), i128::MIN);
+    test_eq!(black_box(u64::MAX).checked_mul(2), None);
+    test_eq!(black_box(i64::MIN).checked_mul(-1), None);
+    test_eq!(black_box(-3_i64).checked_mul(5), Some(-15));
 }
@@ -88,6 +88,26 @@ fn rustc_clr_interop_managed_ctor2_<const ASSEMBLY:&'static str,const CLASS_PATH
 fn rustc_clr_interop_managed_ctor3_<const ASSEMBLY:&'static str,const CLASS_PATH:&'static str,const IS_VALUETYPE:bool,Arg1,Arg2,Arg3>(arg1:Arg1,arg2:Arg2,arg3:Arg3)->RustcCLRInteropManagedClass<ASSEMBLY,CLASS_PATH>{
     core::intrinsics::abort();
 }
+#[allow(dead_code)]
+#[inline(never)]
+fn rustc_clr_interop_managed_array_new_<T>(len:usize)->RustcCLRInteropManagedArray<T,1>{
+    core::intrinsics::abort();
+}
+#[allow(dead_code)]
+#[inline(never)]
+fn rustc_clr_interop_managed_array_len_<T>(arr:RustcCLRInteropManagedArray<T,1>)->usize{
+    core::intrinsics::abort();
+}
+#[allow(dead_code)]
+#[inline(never)]
+fn rustc_clr_interop_managed_array_get_<T>(arr:RustcCLRInteropManagedArray<T,1>,idx:usize)->T{
+    core::intrinsics::abort();
+}
+#[allow(dead_code)]
+#[inline(never)]
+fn rustc_clr_interop_managed_array_set_<T>(arr:RustcCLRInteropManagedArray<T,1>,idx:usize,value:T){
+    core::intrinsics::abort();
+}
 //struct RustcCLRInteropManagedBool;
 fn main(){
     let chr:*mut RustcCLRInteropManagedChar = core::ptr::null_mut();
@@ -99,19 +119,20 @@ fn main(){
     black_box(string);
     let tpe:*mut RustcCLRInteropManagedClass<"System.Runtime","System.Type"> = core::ptr::null_mut();
     black_box(tpe);
-    //let arr:*mut RustcCLRInteropManagedArray<i32,1> = core::ptr::null_mut();
-    //black_box(arr);
-    //let arr:*mut RustcCLRInteropManagedArray<i32,2> = core::ptr::null_mut();
-    //black_box(arr);
-    //let arr:*mut RustcCLRInteropManagedArray<i32,3> = core::ptr::null_mut();
-    //black_box(arr);
-    //let arr:*mut RustcCLRInteropManagedArray<i32,4> = core::ptr::null_mut();
-    //black_box(arr);
-    //let arr:*mut RustcCLRInteropManagedArray<RustcCLRInteropManagedClass<"System.Runtime","System.Object">,1> = core::ptr::null_mut();
-    //black_box(arr);
+    let arr:*mut RustcCLRInteropManagedArray<i32,1> = core::ptr::null_mut();
+    black_box(arr);
+    let arr:*mut RustcCLRInteropManagedArray<i32,2> = core::ptr::null_mut();
+    black_box(arr);
+    let arr:*mut RustcCLRInteropManagedArray<i32,3> = core::ptr::null_mut();
+    black_box(arr);
+    let arr:*mut RustcCLRInteropManagedArray<i32,4> = core::ptr::null_mut();
+    black_box(arr);
+    let arr:*mut RustcCLRInteropManagedArray<RustcCLRInteropManagedClass<"System.Runtime","System.Object">,1> = core::ptr::null_mut();
+    black_box(arr);
     //test_ctors();
     console_writeline();
     new_helloworld();
+    managed_arrays();
 }
 fn test_ctors(){
     let obj = black_box(rustc_clr_interop_managed_ctor0_::<"System.Runtime","System.Object",false>());
@@ -135,3 +156,17 @@ fn new_helloworld(){
     let m_char = unsafe{core::mem::transmute::<u16,RustcCLRInteropManagedChar>(black_box(64))}; 
     rustc_clr_interop_managed_call2_::<"System.Runtime","System.Text.StringBuilder",false,"Append",false,StringBuilder,StringBuilder,RustcCLRInteropManagedChar>(sb,m_char);
 }
+fn managed_arrays(){
+    let arr = rustc_clr_interop_managed_array_new_::<i32>(black_box(8));
+    test_eq!(rustc_clr_interop_managed_array_len_(arr),8);
+    for idx in 0..8{
+        rustc_clr_interop_managed_array_set_(arr,idx,(idx * idx) as i32);
+    }
+    for idx in 0..8{
+        test_eq!(rustc_clr_interop_managed_array_get_(arr,idx),(idx * idx) as i32);
+    }
+    let objs = rustc_clr_interop_managed_array_new_::<Object>(black_box(2));
+    let obj = rustc_clr_interop_managed_ctor0_::<"System.Runtime","System.Object",false>();
+    rustc_clr_interop_managed_array_set_(objs,1,obj);
+    black_box(rustc_clr_interop_managed_array_get_(objs,1));
+}
@@ -0,0 +1,59 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code)]
+#![no_std]
+include!("../common.rs");
+// Temporaries bigger than 1024 bytes are stored in `localloc` buffers, instead of .NET locals.
+#[derive(Clone,Copy)]
+struct Big{
+    tag:u8,
+    data:[u64;256],
+}
+fn make_big(tag:u8)->Big{
+    let mut big = Big{tag,data:[0;256]};
+    let mut i = 0;
+    while i < big.data.len(){
+        big.data[i] = i as u64 * tag as u64;
+        i += 1;
+    }
+    big
+}
+fn sum(big:Big)->u64{
+    let copy = big;
+    let mut res = 0;
+    for val in copy.data{
+        res += val;
+    }
+    res
+}
+fn depth(n:u32)->u64{
+    let mut data = [n as u64;512];
+    data[black_box(3)] += 1;
+    if n == 0{
+        data[3]
+    }
+    else{
+        depth(n - 1) + data[3] + data[511]
+    }
+}
+fn main(){
+    let big = make_big(black_box(2));
+    test_eq!(big.tag,2);
+    test_eq!(big.data[255],510);
+    test_eq!(sum(black_box(big)),65280);
+    // The buffer is allocated once, not on every iteration.
+    let mut addr = 0;
+    let mut i = 0;
+    while i < black_box(4){
+        let mut arr = [black_box(i as u32);1024];
+        arr[1023] += 1;
+        let curr = &arr as *const _ as usize;
+        if i != 0{
+            test_eq!(curr,addr);
+        }
+        addr = curr;
+        test_eq!(arr[0] + arr[1023],2 * i as u32 + 1);
+        i += 1;
+    }
+    // Each call gets its own buffer.
+    test_eq!(depth(black_box(3)),16);
+}
@@ -8,5 +8,20 @@ fn main(){
     x.write(black_box(89));
     let x = unsafe { x.assume_init() };
     test_eq!(x,89);
+    test_eq!(core::mem::size_of::<MaybeUninit<[u64;3]>>(),24);
+    let mut arr = MaybeUninit::<[u64;3]>::uninit();
+    let ptr = arr.as_mut_ptr() as *mut u64;
+    for i in 0..3{
+        unsafe{ptr.add(i).write(black_box(i as u64 * 10))};
+    }
+    let arr = unsafe { arr.assume_init() };
+    test_eq!(arr,[0,10,20]);
+    let mut elems:[MaybeUninit<u32>;4] = unsafe{MaybeUninit::uninit().assume_init()};
+    for (idx,elem) in elems.iter_mut().enumerate(){
+        elem.write(black_box(idx as u32 + 1));
+    }
+    let sum:u32 = elems.iter().map(|elem|unsafe{elem.assume_init()}).sum();
+    test_eq!(sum,10);
+    let zeroed = MaybeUninit::<(u32,u64)>::zeroed();
+    test_eq!(unsafe{zeroed.assume_init()},(0,0));
 }
-
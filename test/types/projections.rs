@@ -0,0 +1,93 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code)]
+#![no_std]
+include!("../common.rs");
+enum Shape{
+    Circle(f32),
+    Rect(f64,f64),
+}
+#[inline(never)]
+fn set_radius(shape:*mut Shape,radius:f32){
+    // Storing a float behind a pointer to an enum variant.
+    if let Shape::Circle(r) = unsafe{&mut *shape}{
+        *r = radius;
+    }
+}
+#[inline(never)]
+fn area(shape:&Shape)->f64{
+    match *shape{
+        Shape::Circle(r) => (r * r * 3.0) as f64,
+        Shape::Rect(w,h) => w * h,
+    }
+}
+#[inline(never)]
+fn ends(slice:&[u32])->(u32,u32,usize){
+    match slice{
+        [first,middle @ ..,last] => (*first,*last,middle.len()),
+        _ => (0,0,0),
+    }
+}
+#[inline(never)]
+fn second_to_last(slice:&mut [u32])->u32{
+    if let [..,second_to_last,_] = slice{
+        *second_to_last += 1;
+        *second_to_last
+    } else{
+        0
+    }
+}
+#[inline(never)]
+fn opaque_pair(done:bool)->impl Copy + Into<(u32,u32)>{
+    if done{
+        return (5_u32,6_u32);
+    }
+    // Destructuring the return type of this function while defining it requires an `OpaqueCast`.
+    let (first,second) = opaque_pair(true);
+    (second,first)
+}
+fn first_byte<'a>(bytes:&'a [u8])->&'a u8{
+    &bytes[0]
+}
+static BYTES:[u8;2] = [7,8];
+fn main(){
+    // `OpaqueCast` on an `impl Trait` return place
+    let pair = opaque_pair(black_box(false));
+    let (first,second):(u32,u32) = pair.into();
+    test_eq!(first,6);
+    test_eq!(second,5);
+    // `Subtype`, from a higher-ranked fn pointer to a less general one.
+    let general:for<'a> fn(&'a [u8])->&'a u8 = black_box(first_byte);
+    let specific:fn(&'static [u8])->&'static u8 = general;
+    test_eq!(*specific(black_box(&BYTES)),7);
+    // `ConstantIndex` from end on an array.
+    let arr = black_box([1_u32,2,3,4,5]);
+    let [..,before_last,last] = arr;
+    test_eq!(before_last,4);
+    test_eq!(last,5);
+    // `Subslice` on an array
+    let [first,middle @ ..,_] = arr;
+    test_eq!(first,1);
+    test_eq!(middle[0],2);
+    test_eq!(middle[2],4);
+    // `Subslice` and `ConstantIndex` from end on a slice
+    let (first,last,len) = ends(black_box(&arr[..]));
+    test_eq!(first,1);
+    test_eq!(last,5);
+    test_eq!(len,3);
+    let mut arr = arr;
+    test_eq!(second_to_last(black_box(&mut arr[..])),5);
+    test_eq!(arr[3],5);
+    // `Downcast` followed by a field of a variant behind a pointer
+    let mut shape = black_box(Shape::Circle(1.0));
+    set_radius(&mut shape,2.0);
+    test_eq!(area(&shape),12.0);
+    let shape = black_box(Shape::Rect(2.0,4.0));
+    test_eq!(area(&shape),8.0);
+    // Tuples with trailing padding must keep their Rust size, so that elements of an array of them don't overlap.
+    let mut padded = black_box([(1_u64,2_u8),(3_u64,4_u8)]);
+    test_eq!(core::mem::size_of::<(u64,u8)>(),16);
+    padded[0].1 = black_box(5);
+    test_eq!(padded[1].0,3);
+    let second = unsafe{&*(&padded[0] as *const (u64,u8)).add(1)};
+    test_eq!(second.1,4);
+}
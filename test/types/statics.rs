@@ -3,6 +3,25 @@
 #![no_std]
 include!("../common.rs");
 static mut INT32:i32 = 0;
+trait Log{
+    fn level(&self)->u32;
+}
+struct Logger(u32);
+impl Log for Logger{
+    fn level(&self)->u32{
+        self.0
+    }
+}
+static LOGGER:&dyn Log = &Logger(7);
+static LOGGERS:[&dyn Log;2] = [&Logger(1),&Logger(2)];
+fn add(a:u32,b:u32)->u32{
+    a + b
+}
+fn mul(a:u32,b:u32)->u32{
+    a * b
+}
+static HANDLER:fn(u32,u32)->u32 = add;
+const DISPATCH:&[fn(u32,u32)->u32] = &[add,mul];
 fn main(){
     let zero = unsafe{INT32};
     unsafe{INT32 += 1};
@@ -12,4 +31,10 @@ fn main(){
     unsafe{INT32 *= two};
     let four = unsafe{INT32};
     test_eq!(four,4);
+    test_eq!(black_box(LOGGER).level(),7);
+    test_eq!(black_box(&LOGGERS)[0].level(),1);
+    test_eq!(black_box(&LOGGERS)[1].level(),2);
+    test_eq!(black_box(HANDLER)(2,3),5);
+    test_eq!((black_box(DISPATCH)[0])(2,3),5);
+    test_eq!((black_box(DISPATCH)[1])(2,3),6);
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+#![feature(lang_items,adt_const_params,associated_type_defaults,core_intrinsics,start)]
+#![allow(internal_features,incomplete_features,unused_variables,dead_code)]
+#![no_std]
+include!("../common.rs");
+use core::mem::{size_of,align_of,ManuallyDrop};
+#[derive(Clone,Copy)]
+union IntOrFloat{
+    int:u32,
+    float:f32,
+    bytes:[u8;4],
+}
+#[derive(Clone,Copy)]
+#[repr(C)]
+struct Pair{
+    a:u16,
+    b:u64,
+}
+#[derive(Clone,Copy)]
+union Nested{
+    pair:Pair,
+    inner:IntOrFloat,
+    words:[u64;2],
+    empty:(),
+}
+#[repr(C,align(16))]
+union Aligned{
+    byte:u8,
+    short:u16,
+}
+union Droppable{
+    slice:ManuallyDrop<&'static [u8]>,
+    raw:(usize,usize),
+}
+fn main(){
+    test_eq!(size_of::<IntOrFloat>(),4);
+    test_eq!(size_of::<Nested>(),16);
+    test_eq!(size_of::<Aligned>(),16);
+    test_eq!(align_of::<Aligned>(),16);
+    // Reading a field after writing another one must reinterpret the same bytes.
+    let mut val = IntOrFloat{float:black_box(1.0)};
+    test_eq!(unsafe{val.int},0x3f80_0000);
+    val.int = black_box(0x0403_0201);
+    test_eq!(unsafe{val.bytes},[1,2,3,4]);
+    unsafe{val.bytes[3] = black_box(0x40)};
+    test_eq!(unsafe{val.float},f32::from_bits(0x4003_0201));
+    val.bytes = black_box([0,0,0,0x40]);
+    test_eq!(unsafe{val.float},2.0);
+    // All fields of a union start at offset 0.
+    let mut nested = Nested{words:[black_box(0),black_box(0)]};
+    nested.inner = IntOrFloat{int:black_box(0xAABB)};
+    test_eq!(unsafe{nested.pair.a},0xAABB);
+    unsafe{nested.pair.b = black_box(0x1122_3344_5566_7788)};
+    test_eq!(unsafe{nested.words[1]},0x1122_3344_5566_7788);
+    test_eq!(unsafe{nested.inner.int},0xAABB);
+    let copy = black_box(nested);
+    test_eq!(unsafe{copy.words[1]},0x1122_3344_5566_7788);
+    let aligned = Aligned{short:black_box(0x0102)};
+    test_eq!(unsafe{aligned.byte},0x02);
+    test_eq!((&aligned as *const Aligned as usize) % 16,0);
+    let droppable = Droppable{slice:ManuallyDrop::new(black_box(b"union"))};
+    let (ptr,len) = unsafe{droppable.raw};
+    test_eq!(len,5);
+    test_eq!(unsafe{*(ptr as *const u8)},b'u');
+}